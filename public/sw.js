@@ -0,0 +1,22 @@
+// Shows announcement notifications sent through Web Push (see server/src/push.rs).
+self.addEventListener("push", function (event) {
+    var data = {};
+    try {
+        data = event.data ? event.data.json() : {};
+    } catch (e) {
+        data = { body: event.data.text() };
+    }
+
+    event.waitUntil(
+        self.registration.showNotification(data.title || "Yeni duyuru", {
+            body: data.body || "",
+            icon: "/assets/image/favicon.ico",
+            data: { url: data.url || "/#duyurular" }
+        })
+    );
+});
+
+self.addEventListener("notificationclick", function (event) {
+    event.notification.close();
+    event.waitUntil(clients.openWindow(event.notification.data.url));
+});
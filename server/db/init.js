@@ -7,30 +7,98 @@ let db = new sqlite3.Database("./server/db/database.db", (err) => {
   console.log("Connected to the SQlite database.");
 });
 
+// Adds a column introduced after a table was first created. SQLite has no
+// ADD COLUMN IF NOT EXISTS, so the error on an up-to-date table is ignored.
+// Queries read rows by position, so columns must be added in the order the
+// CREATE TABLE statement lists them.
+function addColumn(table, definition) {
+  db.run(`ALTER TABLE ${table} ADD COLUMN ${definition}`, (err) => {
+    if (err && !err.message.includes("duplicate column name")) {
+      console.error(err.message);
+    }
+  });
+}
+
 db.serialize(() => {
   db.run(`CREATE TABLE IF NOT EXISTS users (
             id INTEGER PRIMARY KEY,
             username TEXT NOT NULL,
             password TEXT NOT NULL,
-            name TEXT NOT NULL
+            name TEXT NOT NULL,
+            email TEXT NOT NULL DEFAULT '',
+            is_admin INTEGER NOT NULL DEFAULT 0
           )`);
 
+  addColumn("users", "email TEXT NOT NULL DEFAULT ''");
+  addColumn("users", "is_admin INTEGER NOT NULL DEFAULT 0");
+
+  // Accounts created before roles existed start out as editors; the oldest
+  // one is promoted so the user management page stays reachable.
+  db.run(`UPDATE users SET is_admin = 1
+          WHERE id = (SELECT MIN(id) FROM users)
+            AND NOT EXISTS (SELECT 1 FROM users WHERE is_admin = 1)`);
+
+  // Usernames used to be case-sensitive, so older databases may hold names
+  // that differ only by case. The oldest account keeps its name; the others
+  // get their id appended and are listed here so their owners can be told.
+  const isDuplicate = `EXISTS (
+            SELECT 1 FROM users AS older
+            WHERE older.username = users.username COLLATE NOCASE AND older.id < users.id
+          )`;
+  db.all(`SELECT id, username FROM users WHERE ${isDuplicate}`, (err, rows) => {
+    if (err) {
+      return console.error(err.message);
+    }
+    rows.forEach((row) => {
+      console.warn(`Renamed duplicate username "${row.username}" to "${row.username}-${row.id}".`);
+    });
+  });
+  db.run(`UPDATE users SET username = username || '-' || id WHERE ${isDuplicate}`);
+
+  db.run(`CREATE UNIQUE INDEX IF NOT EXISTS users_username_unique
+          ON users (username COLLATE NOCASE)`);
+
+  // Subscribers filter on the category; announcements without one reach
+  // everybody. Empty SEO fields are derived from the title and content.
   db.run(`CREATE TABLE IF NOT EXISTS announcements (
             id INTEGER PRIMARY KEY,
             image TEXT NOT NULL,
             title TEXT NOT NULL,
             content TEXT NOT NULL,
             date TEXT NOT NULL,
-            author TEXT NOT NULL
+            author TEXT NOT NULL,
+            category TEXT NOT NULL DEFAULT '',
+            seo_title TEXT NOT NULL DEFAULT '',
+            seo_description TEXT NOT NULL DEFAULT ''
           )`);
 
+  addColumn("announcements", "category TEXT NOT NULL DEFAULT ''");
+  addColumn("announcements", "seo_title TEXT NOT NULL DEFAULT ''");
+  addColumn("announcements", "seo_description TEXT NOT NULL DEFAULT ''");
+
   db.run(`CREATE TABLE IF NOT EXISTS articles (
             id INTEGER PRIMARY KEY,
             image TEXT NOT NULL,
             title TEXT NOT NULL,
             content TEXT NOT NULL,
             date TEXT NOT NULL,
-            author TEXT NOT NULL
+            author TEXT NOT NULL,
+            seo_title TEXT NOT NULL DEFAULT '',
+            seo_description TEXT NOT NULL DEFAULT ''
+          )`);
+
+  addColumn("articles", "seo_title TEXT NOT NULL DEFAULT ''");
+  addColumn("articles", "seo_description TEXT NOT NULL DEFAULT ''");
+
+  // English (or other) versions of announcements and articles; the rows
+  // themselves hold the Turkish text.
+  db.run(`CREATE TABLE IF NOT EXISTS translations (
+            entity TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            lang TEXT NOT NULL,
+            title TEXT NOT NULL DEFAULT '',
+            content TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY (entity, entity_id, lang)
           )`);
 
   db.run(`CREATE TABLE IF NOT EXISTS messages (
@@ -38,11 +106,233 @@ db.serialize(() => {
             name TEXT,
             email TEXT,
             message TEXT,
-            ip_address TEXT
+            ip_address TEXT,
+            received_at TEXT,
+            is_read INTEGER NOT NULL DEFAULT 0,
+            assigned_staff_id INTEGER,
+            status TEXT NOT NULL DEFAULT 'new',
+            notes TEXT NOT NULL DEFAULT '',
+            spam_score INTEGER NOT NULL DEFAULT 0,
+            category_id INTEGER,
+            extra_fields TEXT NOT NULL DEFAULT '[]',
+            attachment_filename TEXT,
+            attachment_name TEXT
+          )`);
+
+  addColumn("messages", "received_at TEXT");
+  addColumn("messages", "is_read INTEGER NOT NULL DEFAULT 0");
+  addColumn("messages", "assigned_staff_id INTEGER");
+  addColumn("messages", "status TEXT NOT NULL DEFAULT 'new'");
+  addColumn("messages", "notes TEXT NOT NULL DEFAULT ''");
+  addColumn("messages", "spam_score INTEGER NOT NULL DEFAULT 0");
+  addColumn("messages", "category_id INTEGER");
+  addColumn("messages", "extra_fields TEXT NOT NULL DEFAULT '[]'");
+  addColumn("messages", "attachment_filename TEXT");
+  addColumn("messages", "attachment_name TEXT");
+
+  db.run(`CREATE TABLE IF NOT EXISTS contact_categories (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            staff_id INTEGER,
+            display_order INTEGER NOT NULL DEFAULT 0
+          )`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS contact_fields (
+            id INTEGER PRIMARY KEY,
+            label TEXT NOT NULL,
+            field_type TEXT NOT NULL DEFAULT 'text',
+            required INTEGER NOT NULL DEFAULT 0,
+            options TEXT NOT NULL DEFAULT '',
+            display_order INTEGER NOT NULL DEFAULT 0
+          )`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS message_replies (
+            id INTEGER PRIMARY KEY,
+            message_id INTEGER NOT NULL,
+            author TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            body TEXT NOT NULL,
+            sent_at TEXT NOT NULL,
+            FOREIGN KEY (message_id) REFERENCES messages(id)
+          )`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS documents (
+            id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            category TEXT NOT NULL,
+            academic_year TEXT NOT NULL,
+            version TEXT NOT NULL,
+            filename TEXT NOT NULL,
+            original_name TEXT NOT NULL,
+            upload_date TEXT NOT NULL,
+            content_text TEXT,
+            change_note TEXT NOT NULL DEFAULT ''
+          )`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS document_versions (
+            id INTEGER PRIMARY KEY,
+            document_id INTEGER NOT NULL,
+            version TEXT NOT NULL,
+            filename TEXT NOT NULL,
+            original_name TEXT NOT NULL,
+            upload_date TEXT NOT NULL,
+            change_note TEXT NOT NULL DEFAULT '',
+            FOREIGN KEY (document_id) REFERENCES documents(id)
+          )`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS staff (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            title TEXT NOT NULL DEFAULT '',
+            program TEXT NOT NULL,
+            office TEXT NOT NULL DEFAULT '',
+            phone TEXT NOT NULL DEFAULT '',
+            email TEXT NOT NULL DEFAULT '',
+            photo TEXT NOT NULL DEFAULT '/assets/image/avatar.png',
+            office_hours TEXT NOT NULL DEFAULT '',
+            display_order INTEGER NOT NULL DEFAULT 0
+          )`);
+
+  // Times are local, `YYYY-MM-DD HH:MM`; all-day events use 00:00 and end on
+  // their last day.
+  db.run(`CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            category TEXT NOT NULL,
+            location TEXT NOT NULL DEFAULT '',
+            starts_at TEXT NOT NULL,
+            ends_at TEXT,
+            all_day INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL
+          )`);
+
+  db.run(`CREATE INDEX IF NOT EXISTS events_starts_at ON events (starts_at)`);
+
+  db.run(`CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY,
+            created_at TEXT NOT NULL,
+            actor TEXT NOT NULL,
+            action TEXT NOT NULL,
+            entity TEXT NOT NULL,
+            entity_id TEXT NOT NULL DEFAULT '',
+            before_state TEXT NOT NULL DEFAULT '',
+            after_state TEXT NOT NULL DEFAULT '',
+            ip_address TEXT NOT NULL DEFAULT ''
+          )`);
+
+  // Only a SHA-256 of each reset token is stored, so a leaked database does
+  // not hand out working reset links.
+  db.run(`CREATE TABLE IF NOT EXISTS password_reset_tokens (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id)
+          )`);
+
+  // Tokens for the write API. Like reset tokens, only a SHA-256 of the
+  // secret is kept.
+  db.run(`CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            token_prefix TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            expires_at TEXT,
+            last_used_at TEXT,
+            revoked_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id)
+          )`);
+
+  // E-mail subscriptions to new announcements. Nothing is sent before the
+  // address is confirmed through the opt-in mail. The token in every link
+  // manages the subscription, so it is kept as is rather than hashed.
+  // `categories` holds one category per line; empty means all of them.
+  db.run(`CREATE TABLE IF NOT EXISTS subscriptions (
+            id INTEGER PRIMARY KEY,
+            email TEXT NOT NULL COLLATE NOCASE UNIQUE,
+            frequency TEXT NOT NULL,
+            categories TEXT NOT NULL DEFAULT '',
+            token TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL,
+            confirmed_at TEXT,
+            last_announcement_id INTEGER NOT NULL DEFAULT 0,
+            last_sent_at TEXT
+          )`);
+
+  // Browser push subscriptions; the browser's permission prompt is the
+  // opt-in.
+  db.run(`CREATE TABLE IF NOT EXISTS push_subscriptions (
+            id INTEGER PRIMARY KEY,
+            endpoint TEXT NOT NULL UNIQUE,
+            p256dh TEXT NOT NULL,
+            auth TEXT NOT NULL,
+            categories TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL
+          )`);
+
+  // Background work such as notification delivery. `payload` is JSON.
+  // Failed attempts are retried at a later `run_at`; jobs out of attempts
+  // stay `failed` until someone retries or deletes them.
+  db.run(`CREATE TABLE IF NOT EXISTS jobs (
+            id INTEGER PRIMARY KEY,
+            kind TEXT NOT NULL,
+            payload TEXT NOT NULL DEFAULT '{}',
+            status TEXT NOT NULL DEFAULT 'queued',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            run_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            started_at TEXT,
+            finished_at TEXT,
+            last_error TEXT
           )`);
 
+  db.run(`CREATE INDEX IF NOT EXISTS jobs_status_run_at ON jobs (status, run_at)`);
+  db.run(`CREATE INDEX IF NOT EXISTS jobs_kind_created_at ON jobs (kind, created_at)`);
+
+  // The audit log is append-only; only the IP retention policy may touch
+  // existing rows.
+  db.run(`CREATE TRIGGER IF NOT EXISTS audit_log_no_update
+          BEFORE UPDATE OF created_at, actor, action, entity, entity_id, before_state, after_state ON audit_log
+          BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+          END`);
+
+  db.run(`CREATE TRIGGER IF NOT EXISTS audit_log_no_delete
+          BEFORE DELETE ON audit_log
+          BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+          END`);
+
+  db.run(
+    `INSERT INTO users (username, password, name, is_admin) VALUES ('root', '$2a$12$emHZ1nzkcNjDE/fKV5Ali.xX8TyU8gMRRKH4j35QIrVz5Eozd1.Fa', 'root', 1)`
+  );
+
+  db.run(
+    `INSERT INTO documents (title, category, academic_year, version, filename, original_name, upload_date) VALUES
+      ('Akademik Takvim', 'Ders Programı', '2023-2024', '1', 'akademik_takvim.docx', 'akademik_takvim.docx', '21-04-2024'),
+      ('Proje Yazım Kuralları', 'Kılavuz', '2023-2024', '1', 'proje_yazim_kurallari.docx', 'proje_yazim_kurallari.docx', '21-04-2024'),
+      ('C.Ü Yönetmeliği', 'Yönetmelik', '2023-2024', '1', 'cu_yonetmeligi.docx', 'cu_yonetmeligi.docx', '21-04-2024'),
+      ('YÖK Yönetmeliği', 'Yönetmelik', '2023-2024', '1', 'yok_yonetmeligi.docx', 'yok_yonetmeligi.docx', '21-04-2024')`
+  );
+
+  db.run(
+    `INSERT INTO staff (name, program, display_order) VALUES
+      ('Volkan Göreke', 'Bilgisayar Programcılığı', 1),
+      ('Hasan Hüseyin Baş', 'Bilgisayar Programcılığı', 2),
+      ('Serap Begdaş', 'Bilgisayar Programcılığı', 3)`
+  );
+
   db.run(
-    `INSERT INTO users (username, password, name) VALUES ('root', '$2a$12$emHZ1nzkcNjDE/fKV5Ali.xX8TyU8gMRRKH4j35QIrVz5Eozd1.Fa', 'root')`
+    `INSERT INTO contact_categories (name, display_order) VALUES
+      ('Öğrenci İşleri', 1),
+      ('Staj Bürosu', 2),
+      ('Bilgisayar Programcılığı', 3)`
   );
 });
 
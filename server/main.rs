@@ -1,18 +1,65 @@
 mod src;
 
 use crate::src::db;
-use actix_cors::Cors;
 use actix_files::Files;
 use actix_session::{storage::CookieSessionStore, Session, SessionMiddleware};
 use actix_web::cookie::{Cookie, Key, SameSite};
-use actix_web::{middleware, web, App, HttpServer};
+use actix_web::dev::Service;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::Method;
+use actix_web::{web, App, HttpServer};
 // use env_logger::Env;
 use std::io::Result;
 
+/// Pages visitors read, served in Turkish at the root and in English under
+/// `/en`.
+fn public_pages(cfg: &mut web::ServiceConfig) {
+    cfg.route("/", web::get().to(src::index::handler))
+        .route("/main", web::get().to(src::main_content::handler))
+        .route(
+            "/announcements/{page}",
+            web::get().to(src::announcements::announcements_handler),
+        )
+        .route(
+            "/announcement/{id}",
+            web::get().to(src::announcements::announcement_detail_handler),
+        )
+        .route(
+            "/articles/{page}",
+            web::get().to(src::articles::articles_handler),
+        )
+        .route(
+            "/article/{id}",
+            web::get().to(src::articles::article_detail_handler),
+        )
+        .route("/contact", web::get().to(src::contact::handler))
+        .route("/contact", web::post().to(src::contact::post_handler))
+        .route("/duyurular", web::get().to(src::announcements::handler))
+        .route("/makaleler", web::get().to(src::articles::handler))
+        .route("/dokumanlar", web::get().to(src::docs::handler))
+        .route("/etkinlikler", web::get().to(src::events::handler))
+        .route("/personel", web::get().to(src::personel::handler))
+        .route("/gizlilik", web::get().to(src::privacy::handler))
+        .service(
+            web::resource("/bildirimler")
+                .route(web::get().to(src::notify::handler))
+                .route(web::post().to(src::notify::subscribe_handler)),
+        )
+        .service(
+            web::resource("/dokumanlar/{filename}/onizleme")
+                .route(web::get().to(src::docs::preview_handler)),
+        );
+}
+
 #[actix_web::main]
 async fn main() -> Result<()> {
     // env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
+    if let Err(e) = src::docs::backfill_document_text() {
+        eprintln!("Failed to index document text: {}", e);
+    }
+    src::jobs::start_workers();
+
     let secret_key = Key::generate();
     let ip_address = "192.168.1.6";
     let port = "1907";
@@ -28,7 +75,13 @@ async fn main() -> Result<()> {
                     // .cookie_domain(Some(ip_address.to_string()))
                     .build(),
             )
-            .route("/", web::get().to(src::index::handler))
+            .configure(public_pages)
+            .service(
+                web::scope("/en")
+                    .route("", web::get().to(src::index::handler))
+                    .configure(public_pages),
+            )
+            .route("/dil/{code}", web::get().to(src::i18n::switch_handler))
             .route("/dashboard", web::get().to(src::admin::handler))
             .route("/login", web::post().to(src::admin::login_handler))
             .route("/logout", web::get().to(src::admin::logout_handler))
@@ -36,24 +89,7 @@ async fn main() -> Result<()> {
                 "/admin/dashboard",
                 web::get().to(src::admin::admin_dashboard_handler),
             )
-            .route("/main", web::get().to(src::main_content::handler))
             .route("/slider", web::get().to(src::slider::handler))
-            .route(
-                "/announcements/{page}",
-                web::get().to(src::announcements::announcements_handler),
-            )
-            .route(
-                "/announcement/{id}",
-                web::get().to(src::announcements::announcement_detail_handler),
-            )
-            .route(
-                "/articles/{page}",
-                web::get().to(src::articles::articles_handler),
-            )
-            .route(
-                "/article/{id}",
-                web::get().to(src::articles::article_detail_handler),
-            )
             .route("/admin/user", web::get().to(src::admin::admin_user_handler))
             .route(
                 "/admin/announcements",
@@ -79,12 +115,201 @@ async fn main() -> Result<()> {
                 "/admin/messages/delete/{id}",
                 web::delete().to(src::admin::delete_message_handler),
             )
-            .route("/contact", web::get().to(src::contact::handler))
-            .route("/contact", web::post().to(src::contact::post_handler))
-            .route("/duyurular", web::get().to(src::announcements::handler))
-            .route("/makaleler", web::get().to(src::articles::handler))
-            .route("/dokumanlar", web::get().to(src::docs::handler))
-            .route("/personel", web::get().to(src::personel::handler))
+            .route(
+                "/admin/messages/unread-count",
+                web::get().to(src::admin::unread_messages_count_handler),
+            )
+            .route(
+                "/admin/messages/bulk",
+                web::post().to(src::admin::bulk_messages_handler),
+            )
+            .route(
+                "/admin/messages/{id}",
+                web::get().to(src::admin::message_detail_handler),
+            )
+            .route(
+                "/admin/messages/{id}",
+                web::post().to(src::admin::update_message_handler),
+            )
+            .route(
+                "/admin/messages/{id}/reply",
+                web::post().to(src::admin::reply_message_handler),
+            )
+            .route(
+                "/admin/messages/{id}/unread",
+                web::post().to(src::admin::mark_message_unread_handler),
+            )
+            .route(
+                "/admin/messages/{id}/attachment",
+                web::get().to(src::admin::message_attachment_handler),
+            )
+            .service(
+                web::resource("/admin/account")
+                    .route(web::get().to(src::admin::admin_account_handler))
+                    .route(web::post().to(src::admin::update_account_handler)),
+            )
+            .service(
+                web::resource("/admin/tokens")
+                    .route(web::get().to(src::admin::admin_tokens_handler))
+                    .route(web::post().to(src::admin::create_token_handler)),
+            )
+            .route(
+                "/admin/tokens/{id}/revoke",
+                web::post().to(src::admin::revoke_token_handler),
+            )
+            .route("/admin/jobs", web::get().to(src::admin::admin_jobs_handler))
+            .route(
+                "/admin/jobs/{id}/retry",
+                web::post().to(src::admin::retry_job_handler),
+            )
+            .route(
+                "/admin/jobs/{id}",
+                web::delete().to(src::admin::delete_job_handler),
+            )
+            .route("/admin/audit", web::get().to(src::admin::admin_audit_handler))
+            .route(
+                "/admin/audit/export",
+                web::get().to(src::admin::export_audit_handler),
+            )
+            .route(
+                "/admin/privacy",
+                web::get().to(src::admin::admin_privacy_handler),
+            )
+            .route(
+                "/admin/privacy/export",
+                web::get().to(src::admin::export_personal_data_handler),
+            )
+            .route(
+                "/admin/privacy/anonymize",
+                web::post().to(src::admin::anonymize_personal_data_handler),
+            )
+            .route(
+                "/admin/privacy/erase",
+                web::post().to(src::admin::erase_personal_data_handler),
+            )
+            .route(
+                "/admin/contact-form",
+                web::get().to(src::admin::admin_contact_form_handler),
+            )
+            .route(
+                "/admin/contact-form/categories",
+                web::post().to(src::admin::add_contact_category_handler),
+            )
+            .route(
+                "/admin/contact-form/categories/{id}",
+                web::post().to(src::admin::edit_contact_category_handler),
+            )
+            .route(
+                "/admin/contact-form/categories/{id}/delete",
+                web::post().to(src::admin::delete_contact_category_handler),
+            )
+            .route(
+                "/admin/contact-form/fields",
+                web::post().to(src::admin::add_contact_field_handler),
+            )
+            .route(
+                "/admin/contact-form/fields/{id}",
+                web::post().to(src::admin::edit_contact_field_handler),
+            )
+            .route(
+                "/admin/contact-form/fields/{id}/delete",
+                web::post().to(src::admin::delete_contact_field_handler),
+            )
+            .route("/etkinlikler.ics", web::get().to(src::events::ics_handler))
+            .route("/duyurular/rss.xml", web::get().to(src::feed::announcements_rss))
+            .route("/duyurular/atom.xml", web::get().to(src::feed::announcements_atom))
+            .route("/makaleler/rss.xml", web::get().to(src::feed::articles_rss))
+            .route("/makaleler/atom.xml", web::get().to(src::feed::articles_atom))
+            .route("/dokumanlar/rss.xml", web::get().to(src::feed::documents_rss))
+            .route("/dokumanlar/atom.xml", web::get().to(src::feed::documents_atom))
+            .route("/sitemap.xml", web::get().to(src::seo::sitemap_handler))
+            .route("/robots.txt", web::get().to(src::seo::robots_handler))
+            .route("/api/openapi.json", web::get().to(src::openapi::spec_handler))
+            .route("/api/docs", web::get().to(src::openapi::docs_handler))
+            .service(
+                web::scope("/api/v1")
+                    .app_data(web::QueryConfig::default().error_handler(src::api::query_error))
+                    .app_data(web::PathConfig::default().error_handler(src::api::path_error))
+                    .app_data(
+                        web::JsonConfig::default()
+                            .limit(1_048_576)
+                            .error_handler(src::api::json_error),
+                    )
+                    // Reads are public, so any site may fetch them. Writes are
+                    // same-origin or server-to-server only: preflight requests
+                    // are not answered, so browsers on other sites cannot send
+                    // `Authorization: Bearer`.
+                    .wrap_fn(|req, srv| {
+                        let read = matches!(*req.method(), Method::GET | Method::HEAD);
+                        let response = srv.call(req);
+                        async move {
+                            let mut response = response.await?;
+                            if read {
+                                response.headers_mut().insert(
+                                    header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                                    HeaderValue::from_static("*"),
+                                );
+                            }
+                            Ok(response)
+                        }
+                    })
+                    .service(
+                        web::resource("/announcements")
+                            .route(web::get().to(src::api::announcements_handler))
+                            .route(web::post().to(src::api::create_announcement_handler)),
+                    )
+                    .service(
+                        web::resource("/announcements/{id}")
+                            .route(web::get().to(src::api::announcement_handler))
+                            .route(web::put().to(src::api::update_announcement_handler))
+                            .route(web::delete().to(src::api::delete_announcement_handler)),
+                    )
+                    .service(
+                        web::resource("/articles")
+                            .route(web::get().to(src::api::articles_handler))
+                            .route(web::post().to(src::api::create_article_handler)),
+                    )
+                    .service(
+                        web::resource("/articles/{id}")
+                            .route(web::get().to(src::api::article_handler))
+                            .route(web::put().to(src::api::update_article_handler))
+                            .route(web::delete().to(src::api::delete_article_handler)),
+                    )
+                    .route("/images", web::post().to(src::api::upload_image_handler))
+                    .route("/documents", web::get().to(src::api::documents_handler))
+                    .route("/documents/{id}", web::get().to(src::api::document_handler))
+                    .route("/staff", web::get().to(src::api::staff_handler))
+                    .route("/staff/{id}", web::get().to(src::api::staff_member_handler))
+                    .default_service(web::to(src::api::not_found_handler)),
+            )
+            .route(
+                "/bildirimler/push",
+                web::post().to(src::notify::push_subscribe_handler),
+            )
+            .route(
+                "/bildirimler/push/iptal",
+                web::post().to(src::notify::push_unsubscribe_handler),
+            )
+            .service(
+                web::resource("/bildirimler/{token}")
+                    .route(web::get().to(src::notify::manage_handler))
+                    .route(web::post().to(src::notify::update_handler)),
+            )
+            .service(
+                web::resource("/bildirimler/{token}/iptal")
+                    .route(web::get().to(src::notify::unsubscribe_page))
+                    .route(web::post().to(src::notify::unsubscribe_handler)),
+            )
+            .service(
+                web::resource("/password-reset")
+                    .route(web::get().to(src::account::reset_request_page))
+                    .route(web::post().to(src::account::reset_request_handler)),
+            )
+            .service(
+                web::resource("/password-reset/{token}")
+                    .route(web::get().to(src::account::reset_form_handler))
+                    .route(web::post().to(src::account::reset_password_handler)),
+            )
             .service(
                 web::resource("/dokumanlar/{filename}")
                     .route(web::get().to(src::docs::doc_handler)),
@@ -142,17 +367,83 @@ async fn main() -> Result<()> {
                     .route(web::post().to(src::admin::add_user_handler)),
             )
             .service(
-                web::resource("/admin/user/edit/form/{username}")
+                web::resource("/admin/user/edit/form/{id}")
                     .route(web::get().to(src::admin::edit_user_form_handler)),
             )
             .service(
-                web::resource("/admin/user/edit/{username}")
+                web::resource("/admin/user/edit/{id}")
                     .route(web::post().to(src::admin::edit_user_handler)),
             )
             .service(
-                web::resource("/admin/user/delete/{username}")
+                web::resource("/admin/user/delete/{id}")
                     .route(web::delete().to(src::admin::delete_user_handler)),
             )
+            .route(
+                "/admin/documents",
+                web::get().to(src::admin::admin_documents_handler),
+            )
+            .service(
+                web::resource("/admin/documents/add/form")
+                    .route(web::get().to(src::admin::add_document_form_handler)),
+            )
+            .service(
+                web::resource("/admin/documents/add")
+                    .route(web::post().to(src::admin::add_document_handler)),
+            )
+            .service(
+                web::resource("/admin/document/edit/form/{id}")
+                    .route(web::get().to(src::admin::edit_document_form_handler)),
+            )
+            .service(
+                web::resource("/admin/document/edit")
+                    .route(web::post().to(src::admin::edit_document_handler)),
+            )
+            .service(
+                web::resource("/admin/documents/delete/{id}")
+                    .route(web::post().to(src::admin::delete_document_handler)),
+            )
+            .route("/admin/events", web::get().to(src::admin::admin_events_handler))
+            .route(
+                "/admin/events/add/form",
+                web::get().to(src::admin::add_event_form_handler),
+            )
+            .route("/admin/events/add", web::post().to(src::admin::add_event_handler))
+            .route(
+                "/admin/events/edit/form/{id}",
+                web::get().to(src::admin::edit_event_form_handler),
+            )
+            .route(
+                "/admin/events/edit/{id}",
+                web::post().to(src::admin::edit_event_handler),
+            )
+            .route(
+                "/admin/events/delete/{id}",
+                web::post().to(src::admin::delete_event_handler),
+            )
+            .route(
+                "/admin/staff",
+                web::get().to(src::admin::admin_staff_handler),
+            )
+            .service(
+                web::resource("/admin/staff/add/form")
+                    .route(web::get().to(src::admin::add_staff_form_handler)),
+            )
+            .service(
+                web::resource("/admin/staff/add")
+                    .route(web::post().to(src::admin::add_staff_handler)),
+            )
+            .service(
+                web::resource("/admin/staff/edit/form/{id}")
+                    .route(web::get().to(src::admin::edit_staff_form_handler)),
+            )
+            .service(
+                web::resource("/admin/staff/edit")
+                    .route(web::post().to(src::admin::edit_staff_handler)),
+            )
+            .service(
+                web::resource("/admin/staff/delete/{id}")
+                    .route(web::post().to(src::admin::delete_staff_handler)),
+            )
             .service(
                 web::resource("/admin/image/list")
                     .route(web::get().to(src::admin::admin_image_handler)),
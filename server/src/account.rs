@@ -0,0 +1,355 @@
+use crate::src::audit;
+use crate::src::client_ip;
+use crate::src::config;
+use crate::src::db;
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::mailer;
+use crate::src::spam;
+use actix_session::Session;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use rand::RngCore;
+use serde::Serialize;
+use serde_derive::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Session key holding the signed-in user's id. Usernames can change or be
+/// reused after a deletion, ids cannot.
+pub const SESSION_KEY: &str = "user_id";
+
+const RESET_REQUEST_LIMIT: usize = 5;
+const RESET_REQUEST_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// A dashboard user, without the password hash.
+#[derive(Clone, Serialize)]
+pub struct Account {
+    pub id: i32,
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    /// Admins manage the other users; there is always at least one.
+    pub is_admin: bool,
+}
+
+/// Why a user could not be saved or deleted.
+#[derive(Debug)]
+pub enum UserError {
+    DuplicateUsername,
+    LastAdmin,
+}
+
+impl UserError {
+    pub fn message(&self, lang: Lang) -> &'static str {
+        match self {
+            UserError::DuplicateUsername => i18n::t(lang, "admin.users.duplicate_username"),
+            UserError::LastAdmin => i18n::t(lang, "admin.users.last_admin"),
+        }
+    }
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Lang::Tr))
+    }
+}
+
+impl std::error::Error for UserError {}
+
+/// The rules from `[password]` a new password fails to meet, as catalogue
+/// keys.
+#[derive(Debug)]
+pub struct PasswordPolicyError(Vec<&'static str>);
+
+impl PasswordPolicyError {
+    pub fn message(&self, lang: Lang) -> String {
+        let min_length = config::get().password.min_length.to_string();
+        let failures: Vec<String> = self
+            .0
+            .iter()
+            .map(|rule| i18n::t(lang, rule).replace("{min}", &min_length))
+            .collect();
+        i18n::t(lang, "password.error").replace("{failures}", &failures.join(", "))
+    }
+}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Lang::Tr))
+    }
+}
+
+impl std::error::Error for PasswordPolicyError {}
+
+/// Checks a new password against the `[password]` policy.
+pub fn check_password(password: &str) -> Result<(), PasswordPolicyError> {
+    let policy = &config::get().password;
+    let mut failures = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        failures.push("password.rule.length");
+    }
+    if policy.require_mixed_case
+        && !(password.chars().any(char::is_uppercase) && password.chars().any(char::is_lowercase))
+    {
+        failures.push("password.rule.mixed_case");
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        failures.push("password.rule.digit");
+    }
+    if policy.require_symbol && password.chars().all(char::is_alphanumeric) {
+        failures.push("password.rule.symbol");
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(PasswordPolicyError(failures))
+    }
+}
+
+/// The policy in words, shown next to password fields.
+pub fn password_hint(lang: Lang) -> String {
+    let policy = &config::get().password;
+    let mut rules = vec![
+        i18n::t(lang, "password.hint.length").replace("{min}", &policy.min_length.to_string())
+    ];
+    if policy.require_mixed_case {
+        rules.push(i18n::t(lang, "password.hint.mixed_case").to_string());
+    }
+    if policy.require_digit {
+        rules.push(i18n::t(lang, "password.hint.digit").to_string());
+    }
+    if policy.require_symbol {
+        rules.push(i18n::t(lang, "password.hint.symbol").to_string());
+    }
+    rules.join(", ")
+}
+
+/// The account signed in on `session`, or `None` when nobody is or the
+/// account has been deleted since.
+pub fn signed_in(session: &Session) -> rusqlite::Result<Option<Account>> {
+    match session.get::<i32>(SESSION_KEY).ok().flatten() {
+        Some(id) => db::get_account_by_id(id),
+        None => Ok(None),
+    }
+}
+
+/// Reset links carry the token itself; only its hash is stored.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn now() -> chrono::DateTime<chrono::Local> {
+    chrono::Local::now()
+}
+
+fn format_time(time: chrono::DateTime<chrono::Local>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Creates a single-use reset token for `account`, replacing any earlier
+/// unused ones, and returns it.
+pub fn issue_reset_token(account: &Account) -> rusqlite::Result<String> {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let created_at = now();
+    let minutes = config::get().password.reset_token_minutes;
+    let expires_at = created_at + chrono::Duration::minutes(minutes.into());
+    db::add_password_reset_token(
+        account.id,
+        &hash_token(&token),
+        &format_time(created_at),
+        &format_time(expires_at),
+    )?;
+
+    Ok(token)
+}
+
+async fn send_reset_link(account: &Account, token: &str) -> Result<(), mailer::MailError> {
+    let mail = &config::get().mail;
+    let body = format!(
+        "Merhaba {},\n\nYönetim paneli şifrenizi sıfırlamak için aşağıdaki bağlantıyı açın. \
+        Bağlantı {} dakika geçerlidir ve yalnızca bir kez kullanılabilir.\n\n{}/password-reset/{}\n\n\
+        Bu isteği siz yapmadıysanız bu e-postayı dikkate almayın; şifreniz değişmeyecek.",
+        account.name,
+        config::get().password.reset_token_minutes,
+        mail.site_url,
+        token
+    );
+    mailer::send(&account.email, None, "Şifre sıfırlama", &body).await
+}
+
+async fn page(content: &str) -> Result<HttpResponse> {
+    let path: PathBuf = "../public/pages/password_reset.html".parse().unwrap();
+    let template = tokio::fs::read_to_string(path).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(template.replace("{{content}}", content)))
+}
+
+fn notice(text: &str, color: &str) -> String {
+    format!("<p class='text-sm text-{}-700'>{}</p>", color, text)
+}
+
+fn request_form(message: &str) -> String {
+    format!(
+        "<h1 class='text-xl font-bold text-gray-900'>Şifremi unuttum</h1>
+        <p class='text-sm text-gray-600'>Kullanıcı adınızı veya hesabınıza kayıtlı e-posta adresini girin; size bir sıfırlama bağlantısı gönderelim.</p>
+        {}
+        <form class='space-y-4' action='/password-reset' method='post'>
+            <input type='text' name='login' required placeholder='Kullanıcı adı veya e-posta'
+                class='bg-gray-50 border border-gray-300 text-gray-900 sm:text-sm rounded-lg block w-full p-2.5'>
+            <button type='submit' class='w-full text-white bg-gray-500 hover:bg-gray-700 font-medium rounded-lg text-sm px-5 py-2.5'>Bağlantı gönder</button>
+        </form>
+        <a href='/dashboard' class='block text-sm text-blue-600 underline'>Girişe dön</a>",
+        message
+    )
+}
+
+fn reset_form(token: &str, message: &str) -> String {
+    format!(
+        "<h1 class='text-xl font-bold text-gray-900'>Yeni şifre belirleyin</h1>
+        <p class='text-sm text-gray-600'>{}.</p>
+        {}
+        <form class='space-y-4' action='/password-reset/{}' method='post'>
+            <input type='password' name='password' required placeholder='Yeni şifre' autocomplete='new-password'
+                class='bg-gray-50 border border-gray-300 text-gray-900 sm:text-sm rounded-lg block w-full p-2.5'>
+            <input type='password' name='password_confirm' required placeholder='Yeni şifre (tekrar)' autocomplete='new-password'
+                class='bg-gray-50 border border-gray-300 text-gray-900 sm:text-sm rounded-lg block w-full p-2.5'>
+            <button type='submit' class='w-full text-white bg-gray-500 hover:bg-gray-700 font-medium rounded-lg text-sm px-5 py-2.5'>Şifreyi değiştir</button>
+        </form>",
+        password_hint(Lang::Tr),
+        message,
+        escape_html(token)
+    )
+}
+
+fn invalid_link() -> String {
+    format!(
+        "<h1 class='text-xl font-bold text-gray-900'>Bağlantı geçersiz</h1>
+        {}
+        <a href='/password-reset' class='block text-sm text-blue-600 underline'>Yeni bağlantı iste</a>",
+        notice(
+            "Bu sıfırlama bağlantısı daha önce kullanılmış ya da süresi dolmuş.",
+            "red"
+        )
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ResetRequestForm {
+    login: String,
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordForm {
+    password: String,
+    password_confirm: String,
+}
+
+pub async fn reset_request_page() -> Result<HttpResponse> {
+    page(&request_form("")).await
+}
+
+/// Mails a reset link to the account matching a username or e-mail address.
+/// The answer is the same whether or not such an account exists.
+pub async fn reset_request_handler(
+    req: HttpRequest,
+    form: web::Form<ResetRequestForm>,
+) -> Result<HttpResponse> {
+    if !spam::password_reset_limiter().check(
+        &client_ip::client_ip(&req),
+        RESET_REQUEST_LIMIT,
+        RESET_REQUEST_WINDOW,
+    ) {
+        return page(&request_form(&notice(
+            "Kısa sürede çok fazla istek gönderdiniz. Lütfen daha sonra tekrar deneyin.",
+            "red",
+        )))
+        .await;
+    }
+
+    let account = db::find_account(form.login.trim())
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .filter(|account| !account.email.is_empty());
+
+    if let Some(account) = account {
+        let token = issue_reset_token(&account)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        // Sent in the background so the response time does not reveal
+        // whether the account exists.
+        actix_web::rt::spawn(async move {
+            if let Err(e) = send_reset_link(&account, &token).await {
+                eprintln!(
+                    "Failed to send password reset mail to {}: {}",
+                    account.username, e
+                );
+            }
+        });
+    }
+
+    page(&request_form(&notice(
+        "Bu bilgilere kayıtlı bir hesap varsa şifre sıfırlama bağlantısı e-posta adresine gönderildi.",
+        "green",
+    )))
+    .await
+}
+
+fn token_account(token: &str) -> Result<Option<Account>> {
+    db::get_password_reset_account(&hash_token(token), &format_time(now()))
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))
+}
+
+pub async fn reset_form_handler(token: web::Path<String>) -> Result<HttpResponse> {
+    match token_account(&token)? {
+        Some(_) => page(&reset_form(&token, "")).await,
+        None => page(&invalid_link()).await,
+    }
+}
+
+pub async fn reset_password_handler(
+    req: HttpRequest,
+    token: web::Path<String>,
+    form: web::Form<ResetPasswordForm>,
+) -> Result<HttpResponse> {
+    if token_account(&token)?.is_none() {
+        return page(&invalid_link()).await;
+    }
+    if form.password != form.password_confirm {
+        return page(&reset_form(
+            &token,
+            &notice("Şifreler birbiriyle eşleşmiyor.", "red"),
+        ))
+        .await;
+    }
+
+    match db::reset_password(&hash_token(&token), &format_time(now()), &form.password) {
+        Ok(Some(account)) => {
+            audit::record(
+                &req,
+                "password_reset",
+                "user",
+                account.id,
+                None,
+                Some(audit::summary(&account)),
+            );
+            page(&format!(
+                "<h1 class='text-xl font-bold text-gray-900'>Şifreniz değiştirildi</h1>
+                {}
+                <a href='/dashboard' class='block text-sm text-blue-600 underline'>Giriş yap</a>",
+                notice("Yeni şifrenizle giriş yapabilirsiniz.", "green")
+            ))
+            .await
+        }
+        Ok(None) => page(&invalid_link()).await,
+        Err(e) => match e.downcast_ref::<PasswordPolicyError>() {
+            Some(policy) => page(&reset_form(&token, &notice(&policy.to_string(), "red"))).await,
+            None => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+        },
+    }
+}
@@ -1,8 +1,29 @@
+use crate::src::account::{self, PasswordPolicyError};
+use crate::src::api_token;
+use crate::src::audit::{self, AuditFilter};
+use crate::src::config;
+use crate::src::contact::{
+    self, ContactCategory, ContactField, Message, MessageFilter, MessageReply, MessageStatus,
+};
 use crate::src::db;
+use crate::src::docs;
+use crate::src::events::EventForm;
+use crate::src::html;
+use crate::src::i18n::{self, Lang, Translation};
+use crate::src::images;
+use crate::src::jobs;
+use crate::src::mailer;
+use crate::src::notify;
+use crate::src::personel::{self, Staff};
+use crate::src::privacy;
+use crate::src::safe_path;
+use crate::src::seo::Overrides;
+use crate::src::upload::{self, FormUpload};
+use actix_files::NamedFile;
 use actix_multipart::{Field, Multipart};
 use actix_session::{storage::CookieSessionStore, Session, SessionMiddleware};
 use actix_web::cookie::Key;
-use actix_web::http::header::ContentDisposition;
+use actix_web::http::header::DispositionType;
 use actix_web::web::BytesMut;
 use actix_web::web::Query;
 use actix_web::web::{self, Bytes};
@@ -12,6 +33,7 @@ use image::imageops::resize;
 use image::imageops::FilterType;
 use image::GenericImageView;
 use serde_derive::Deserialize;
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs;
 use std::io::Write;
@@ -32,7 +54,20 @@ pub struct LoginForm {
 pub struct User {
     name: String,
     username: String,
+    #[serde(default)]
+    email: String,
     password: String,
+    /// Checkbox; present only when ticked.
+    is_admin: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AccountForm {
+    name: String,
+    email: String,
+    current_password: String,
+    new_password: String,
+    new_password_confirm: String,
 }
 
 #[derive(Deserialize)]
@@ -115,55 +150,58 @@ pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().content_type("text/html").body(content))
 }
 
-pub async fn login_handler(form: web::Form<LoginForm>, mut session: Session) -> impl Responder {
+pub async fn login_handler(
+    req: HttpRequest,
+    form: web::Form<LoginForm>,
+    mut session: Session,
+) -> impl Responder {
     let login_form = form.into_inner();
 
     match db::authenticate_user(&login_form.username, &login_form.password) {
-        Ok((is_authenticated, name)) => {
-            if is_authenticated {
-                match session.insert("user_id", login_form.username) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        return HttpResponse::InternalServerError()
-                            .body(format!("Failed to set session: {}", e))
-                    }
-                }
-                if let Some(name) = name {
-                    session.insert("user_name", name).unwrap();
+        Ok(Some(account)) => {
+            match session.insert(account::SESSION_KEY, account.id) {
+                Ok(_) => (),
+                Err(e) => {
+                    return HttpResponse::InternalServerError()
+                        .body(format!("Failed to set session: {}", e))
                 }
             }
+            session.insert("user_name", account.name).unwrap();
         }
+        Ok(None) => (),
         Err(_) => {
             return HttpResponse::InternalServerError().body("Authentication failed");
         }
     }
 
-    match session.get::<String>("user_id") {
-        Ok(user_id_option) => {
-            if user_id_option.is_some() {
-                let path: PathBuf = "../public/pages/dashboard.html".parse().unwrap();
-                let content = tokio::fs::read_to_string(path).await.unwrap();
-                HttpResponse::Ok().header("HX-Redirect", "/admin/dashboard").body(content)
-            } else {
-                HttpResponse::Unauthorized().content_type("text/html").body("<h1 class='mt-4 font-bold text-2xl text-center text-red-500'>Login Failed</h1>")
-            }
+    match signed_in_account(&session) {
+        Ok(_) => {
+            let path: PathBuf = "../public/pages/dashboard.html".parse().unwrap();
+            let content = tokio::fs::read_to_string(path).await.unwrap();
+            HttpResponse::Ok()
+                .header("HX-Redirect", "/admin/dashboard")
+                .body(i18n::translate(&content, Lang::preferred(&req)))
         }
-        Err(_) => HttpResponse::InternalServerError().body("Failed to get session"),
+        Err(_) => HttpResponse::Unauthorized()
+            .content_type("text/html")
+            .body("<h1 class='mt-4 font-bold text-2xl text-center text-red-500'>Login Failed</h1>"),
     }
 }
 
-pub async fn logout_handler(mut session: Session) -> Result<HttpResponse> {
-    session.remove("user_id");
+pub async fn logout_handler(session: Session) -> Result<HttpResponse> {
+    session.remove(account::SESSION_KEY);
     Ok(HttpResponse::Ok().finish())
 }
 
-pub async fn admin_dashboard_handler(session: Session) -> Result<HttpResponse> {
-    match session.get::<String>("user_id") {
-        Ok(user_id_option) => {
-            if let Some(_) = user_id_option {
+pub async fn admin_dashboard_handler(req: HttpRequest, session: Session) -> Result<HttpResponse> {
+    match account::signed_in(&session) {
+        Ok(account) => {
+            if account.is_some() {
                 let path: PathBuf = "../public/pages/dashboard.html".parse().unwrap();
                 let content = tokio::fs::read_to_string(path).await?;
-                Ok(HttpResponse::Ok().content_type("text/html").body(content))
+                Ok(HttpResponse::Ok()
+                    .content_type("text/html")
+                    .body(i18n::translate(&content, Lang::preferred(&req))))
             } else {
                 Ok(HttpResponse::Found().header("Location", "/").finish())
             }
@@ -175,8 +213,10 @@ pub async fn admin_dashboard_handler(session: Session) -> Result<HttpResponse> {
 }
 
 pub async fn admin_announcements_handler(
+    req: HttpRequest,
     Query(pagination): Query<Pagination>,
 ) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
     let page: i32 = pagination.page.unwrap_or(1).try_into().unwrap();
     let page_size: i32 = pagination.page_size.unwrap_or(3).try_into().unwrap();
 
@@ -185,14 +225,16 @@ pub async fn admin_announcements_handler(
 
     let total_pages = (total_announcements as f32 / page_size as f32).ceil() as i32;
 
-    let mut content = String::from("
+    let mut content = format!("
     <div class='flex justify-center'>
-        <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/announcements/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>           Yeni Duyuru Ekle           </button>
+        <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/announcements/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>           {}           </button>
     </div>
-    ");
+    ", i18n::t(lang, "admin.add_announcement"));
     for announcement in &announcements {
-        let mut announcement_content =
-            tokio::fs::read_to_string("../public/pages/announcements.html").await?;
+        let mut announcement_content = i18n::translate(
+            &tokio::fs::read_to_string("../public/pages/announcements.html").await?,
+            lang,
+        );
         announcement_content = announcement_content.replace("{image}", &announcement.image);
         announcement_content = announcement_content.replace("{title}", &announcement.title);
         announcement_content = announcement_content.replace("{date}", &announcement.date);
@@ -204,16 +246,18 @@ pub async fn admin_announcements_handler(
     content.push_str("<div class='flex justify-center items-center mt-4 mb-4 space-x-2'>");
     if page > 1 {
         content.push_str(&format!(
-            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/announcements?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>Önceki Sayfa</button>",
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/announcements?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>",
             page - 1,
-            page_size
+            page_size,
+            i18n::t(lang, "list.previous_page")
         ));
     }
     if page < total_pages {
         content.push_str(&format!(
-            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/announcements?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>Sonraki Sayfa</button>",
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/announcements?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>",
             page + 1,
-            page_size
+            page_size,
+            i18n::t(lang, "list.next_page")
         ));
     }
     content.push_str("</div>");
@@ -222,14 +266,20 @@ pub async fn admin_announcements_handler(
 }
 
 pub async fn add_announcement_handler(
+    req: HttpRequest,
     mut payload: Multipart,
     session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
     let mut image: Option<Bytes> = None;
     let mut title: Option<String> = None;
     let mut content: Option<String> = None;
     let mut date: Option<String> = None;
     let mut author: Option<String> = None;
+    let mut seo = Overrides::default();
+    let mut translation = Translation::default();
+    let mut category = String::new();
     let mut image_path: Option<String> = None;
 
     while let Ok(Some(mut field)) = payload.try_next().await {
@@ -274,16 +324,61 @@ pub async fn add_announcement_handler(
                 }
                 content = Some(String::from_utf8(bytes.to_vec()).unwrap());
             }
+            "title_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "content_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.content = String::from_utf8(bytes.to_vec()).unwrap();
+            }
+            "seo_title" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "seo_description" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.description = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "category" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                category = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
             "author" => {
-                match session.get::<String>("user_id") {
-                    Ok(user_id_option) => {
-                        if let Some(username) = user_id_option {
-                            author = Some(username);
-                        }
-                    }
-                    Err(_) => {
-                        // Handle case where getting user from session failed
-                    }
+                if let Ok(account) = signed_in_account(&session) {
+                    author = Some(account.username);
                 }
             }
             "date" => {
@@ -327,32 +422,82 @@ pub async fn add_announcement_handler(
         &form.content,
         &form.date,
         &form.author,
+        &category,
+        &seo,
     ) {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(id) => {
+            if db::save_translation("announcement", id, "en", &translation).is_err() {
+                return Ok(HttpResponse::InternalServerError().finish());
+            }
+            audit::created(&req, "announcement", id, &db::get_announcement(id).ok());
+            notify::announcement_published(id);
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
     }
 }
 
 
-pub async fn add_announcement_form_handler() -> Result<HttpResponse, actix_web::Error> {
+async fn announcement_category_options() -> Result<String, actix_web::Error> {
+    let categories = db::get_announcement_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(categories
+        .iter()
+        .map(|category| format!("<option value='{}'></option>", html::escape_html(category)))
+        .collect())
+}
+
+pub async fn add_announcement_form_handler(
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
     let path: PathBuf = "../public/pages/add_announcement.html".parse().unwrap();
-    let form = tokio::fs::read_to_string(path).await?;
+    let mut form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
+    form = form.replace("{{categories}}", &announcement_category_options().await?);
     Ok(HttpResponse::Ok().content_type("text/html").body(form))
 }
 
 pub async fn edit_announcement_form_handler(
+    req: HttpRequest,
     id: web::Path<i32>,
 ) -> Result<HttpResponse, actix_web::Error> {
     match db::get_announcement(id.into_inner()) {
         Ok(announcement) => {
             let path: PathBuf = "../public/pages/edit_announcement.html".parse().unwrap();
-            let mut form = tokio::fs::read_to_string(path).await?;
+            let mut form = i18n::translate(
+                &tokio::fs::read_to_string(path).await?,
+                Lang::preferred(&req),
+            );
             form = form.replace("{{announcement.id}}", &announcement.id.to_string());
             form = form.replace("{{announcement.image}}", &announcement.image);
             form = form.replace("{{announcement.title}}", &announcement.title);
             form = form.replace("{{announcement.date}}", &announcement.date);
             form = form.replace("{{announcement.content}}", &announcement.content);
             form = form.replace("{{announcement.author}}", &announcement.author);
+            let translation = db::get_translation("announcement", announcement.id, "en")
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+                .unwrap_or_default();
+            form = form.replace(
+                "{{announcement.title_en}}",
+                &html::escape_html(&translation.title),
+            );
+            form = form.replace("{{announcement.content_en}}", &translation.content);
+            form = form.replace(
+                "{{announcement.seo_title}}",
+                &html::escape_html(&announcement.seo_title),
+            );
+            form = form.replace(
+                "{{announcement.seo_description}}",
+                &html::escape_html(&announcement.seo_description),
+            );
+            form = form.replace(
+                "{{announcement.category}}",
+                &html::escape_html(&announcement.category),
+            );
+            form = form.replace("{{categories}}", &announcement_category_options().await?);
             Ok(HttpResponse::Ok().content_type("text/html").body(form))
         }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
@@ -360,15 +505,21 @@ pub async fn edit_announcement_form_handler(
 }
 
 pub async fn edit_announcement_handler(
+    req: HttpRequest,
     mut payload: Multipart,
     session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
     let mut id: Option<i32> = None;
     let mut image: Option<Bytes> = None;
     let mut title: Option<String> = None;
     let mut content: Option<String> = None;
     let mut date: Option<String> = None;
     let mut author: Option<String> = None;
+    let mut seo = Overrides::default();
+    let mut translation = Translation::default();
+    let mut category = String::new();
     let mut image_path: Option<String> = None;
 
     while let Ok(Some(mut field)) = payload.try_next().await {
@@ -423,16 +574,61 @@ pub async fn edit_announcement_handler(
                 }
                 content = Some(String::from_utf8(bytes.to_vec()).unwrap());
             }
+            "title_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "content_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.content = String::from_utf8(bytes.to_vec()).unwrap();
+            }
+            "seo_title" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "seo_description" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.description = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "category" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                category = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
             "author" => {
-                match session.get::<String>("user_id") {
-                    Ok(user_id_option) => {
-                        if let Some(username) = user_id_option {
-                            author = Some(username);
-                        }
-                    }
-                    Err(_) => {
-                        // Handle case where getting user from session failed
-                    }
+                if let Ok(account) = signed_in_account(&session) {
+                    author = Some(account.username);
                 }
             }
             "date" => {
@@ -472,6 +668,7 @@ pub async fn edit_announcement_handler(
         author,
     };
 
+    let before = db::get_announcement(id).ok();
     match db::edit_announcement(
         id,
         &form.image_path,
@@ -479,13 +676,27 @@ pub async fn edit_announcement_handler(
         &form.content,
         &form.date,
         &form.author,
+        &category,
+        &seo,
     ) {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(_) => {
+            if db::save_translation("announcement", id, "en", &translation).is_err() {
+                return Ok(HttpResponse::InternalServerError().finish());
+            }
+            let after = db::get_announcement(id).ok();
+            audit::updated(&req, "announcement", id, &before, &after);
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
     }
 }
 
-pub async fn delete_announcement_handler(req: HttpRequest) -> Result<HttpResponse> {
+pub async fn delete_announcement_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse> {
+    require_login(&session)?;
+
     let id: i32 = req
         .match_info()
         .get("id")
@@ -493,13 +704,19 @@ pub async fn delete_announcement_handler(req: HttpRequest) -> Result<HttpRespons
         .parse()
         .unwrap_or(0);
 
+    let before = db::get_announcement(id).ok();
     db::delete_announcement(id)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "announcement", id, &before);
 
     Ok(HttpResponse::Ok().content_type("text/html").body(""))
 }
 
-pub async fn admin_articles_handler(Query(pagination): Query<Pagination>) -> Result<HttpResponse> {
+pub async fn admin_articles_handler(
+    req: HttpRequest,
+    Query(pagination): Query<Pagination>,
+) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
     let page: i32 = pagination.page.unwrap_or(1).try_into().unwrap();
     let page_size: i32 = pagination.page_size.unwrap_or(3).try_into().unwrap();
 
@@ -508,14 +725,16 @@ pub async fn admin_articles_handler(Query(pagination): Query<Pagination>) -> Res
 
     let total_pages = (total_articles as f32 / page_size as f32).ceil() as i32;
 
-    let mut content = String::from("
+    let mut content = format!("
     <div class='flex justify-center'>
-        <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/articles/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>           Yeni Makale Ekle           </button>
+        <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/articles/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>           {}           </button>
     </div>
-    ");
+    ", i18n::t(lang, "admin.add_article"));
     for article in &articles {
-        let mut article_content =
-            tokio::fs::read_to_string("../public/pages/articles.html").await?;
+        let mut article_content = i18n::translate(
+            &tokio::fs::read_to_string("../public/pages/articles.html").await?,
+            lang,
+        );
         article_content = article_content.replace("{image}", &article.image);
         article_content = article_content.replace("{title}", &article.title);
         article_content = article_content.replace("{date}", &article.date);
@@ -527,16 +746,18 @@ pub async fn admin_articles_handler(Query(pagination): Query<Pagination>) -> Res
     content.push_str("<div class='flex justify-center items-center mt-4 mb-4 space-x-2'>");
     if page > 1 {
         content.push_str(&format!(
-            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/articles?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>Önceki Sayfa</button>",
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/articles?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>",
             page - 1,
-            page_size
+            page_size,
+            i18n::t(lang, "list.previous_page")
         ));
     }
     if page < total_pages {
         content.push_str(&format!(
-            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/articles?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>Sonraki Sayfa</button>",
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/articles?page={}&page_size={}' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>",
             page + 1,
-            page_size
+            page_size,
+            i18n::t(lang, "list.next_page")
         ));
     }
     content.push_str("</div>");
@@ -545,14 +766,19 @@ pub async fn admin_articles_handler(Query(pagination): Query<Pagination>) -> Res
 }
 
 pub async fn add_article_handler(
+    req: HttpRequest,
     mut payload: Multipart,
     session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
     let mut image: Option<Bytes> = None;
     let mut title: Option<String> = None;
     let mut content: Option<String> = None;
     let mut date: Option<String> = None;
     let mut author: Option<String> = None;
+    let mut seo = Overrides::default();
+    let mut translation = Translation::default();
     let mut image_path: Option<String> = None;
 
     while let Ok(Some(mut field)) = payload.try_next().await {
@@ -597,16 +823,50 @@ pub async fn add_article_handler(
                 }
                 content = Some(String::from_utf8(bytes.to_vec()).unwrap());
             }
+            "title_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "content_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.content = String::from_utf8(bytes.to_vec()).unwrap();
+            }
+            "seo_title" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "seo_description" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.description = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
             "author" => {
-                match session.get::<String>("user_id") {
-                    Ok(user_id_option) => {
-                        if let Some(username) = user_id_option {
-                            author = Some(username);
-                        }
-                    }
-                    Err(_) => {
-                        // Handle case where getting user from session failed
-                    }
+                if let Ok(account) = signed_in_account(&session) {
+                    author = Some(account.username);
                 }
             }
             "date" => {
@@ -650,32 +910,62 @@ pub async fn add_article_handler(
         &form.content,
         &form.date,
         &form.author,
+        &seo,
     ) {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(id) => {
+            if db::save_translation("article", id, "en", &translation).is_err() {
+                return Ok(HttpResponse::InternalServerError().finish());
+            }
+            audit::created(&req, "article", id, &db::get_article(id).ok());
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
     }
 }
 
 
-pub async fn add_article_form_handler() -> Result<HttpResponse, actix_web::Error> {
+pub async fn add_article_form_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
     let path: PathBuf = "../public/pages/add_article.html".parse().unwrap();
-    let form = tokio::fs::read_to_string(path).await?;
+    let form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
     Ok(HttpResponse::Ok().content_type("text/html").body(form))
 }
 
 pub async fn edit_article_form_handler(
+    req: HttpRequest,
     id: web::Path<i32>,
 ) -> Result<HttpResponse, actix_web::Error> {
     match db::get_article(id.into_inner()) {
         Ok(article) => {
             let path: PathBuf = "../public/pages/edit_article.html".parse().unwrap();
-            let mut form = tokio::fs::read_to_string(path).await?;
+            let mut form = i18n::translate(
+                &tokio::fs::read_to_string(path).await?,
+                Lang::preferred(&req),
+            );
             form = form.replace("{{article.id}}", &article.id.to_string());
             form = form.replace("{{article.image}}", &article.image);
             form = form.replace("{{article.title}}", &article.title);
             form = form.replace("{{article.date}}", &article.date);
             form = form.replace("{{article.content}}", &article.content);
             form = form.replace("{{article.author}}", &article.author);
+            let translation = db::get_translation("article", article.id, "en")
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+                .unwrap_or_default();
+            form = form.replace(
+                "{{article.title_en}}",
+                &html::escape_html(&translation.title),
+            );
+            form = form.replace("{{article.content_en}}", &translation.content);
+            form = form.replace(
+                "{{article.seo_title}}",
+                &html::escape_html(&article.seo_title),
+            );
+            form = form.replace(
+                "{{article.seo_description}}",
+                &html::escape_html(&article.seo_description),
+            );
             Ok(HttpResponse::Ok().content_type("text/html").body(form))
         }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
@@ -683,15 +973,20 @@ pub async fn edit_article_form_handler(
 }
 
 pub async fn edit_article_handler(
+    req: HttpRequest,
     mut payload: Multipart,
     session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
     let mut id: Option<i32> = None;
     let mut image: Option<Bytes> = None;
     let mut title: Option<String> = None;
     let mut content: Option<String> = None;
     let mut date: Option<String> = None;
     let mut author: Option<String> = None;
+    let mut seo = Overrides::default();
+    let mut translation = Translation::default();
     let mut image_path: Option<String> = None;
 
     while let Ok(Some(mut field)) = payload.try_next().await {
@@ -746,16 +1041,50 @@ pub async fn edit_article_handler(
                 }
                 content = Some(String::from_utf8(bytes.to_vec()).unwrap());
             }
+            "title_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "content_en" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                translation.content = String::from_utf8(bytes.to_vec()).unwrap();
+            }
+            "seo_title" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.title = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
+            "seo_description" => {
+                let mut bytes = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.unwrap();
+                    bytes.extend_from_slice(&data);
+                }
+                seo.description = String::from_utf8(bytes.to_vec())
+                    .unwrap()
+                    .trim()
+                    .to_string();
+            }
             "author" => {
-                match session.get::<String>("user_id") {
-                    Ok(user_id_option) => {
-                        if let Some(username) = user_id_option {
-                            author = Some(username);
-                        }
-                    }
-                    Err(_) => {
-                        // Handle case where getting user from session failed
-                    }
+                if let Ok(account) = signed_in_account(&session) {
+                    author = Some(account.username);
                 }
             }
             "date" => {
@@ -795,6 +1124,7 @@ pub async fn edit_article_handler(
         author,
     };
 
+    let before = db::get_article(id).ok();
     match db::edit_article(
         id,
         &form.image_path,
@@ -802,13 +1132,23 @@ pub async fn edit_article_handler(
         &form.content,
         &form.date,
         &form.author,
+        &seo,
     ) {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(_) => {
+            if db::save_translation("article", id, "en", &translation).is_err() {
+                return Ok(HttpResponse::InternalServerError().finish());
+            }
+            let after = db::get_article(id).ok();
+            audit::updated(&req, "article", id, &before, &after);
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(_) => Ok(HttpResponse::InternalServerError().finish()),
     }
 }
 
-pub async fn delete_article_handler(req: HttpRequest) -> Result<HttpResponse> {
+pub async fn delete_article_handler(req: HttpRequest, session: Session) -> Result<HttpResponse> {
+    require_login(&session)?;
+
     let id: i32 = req
         .match_info()
         .get("id")
@@ -816,157 +1156,2701 @@ pub async fn delete_article_handler(req: HttpRequest) -> Result<HttpResponse> {
         .parse()
         .unwrap_or(0);
 
+    let before = db::get_article(id).ok();
     db::delete_article(id)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "article", id, &before);
 
     Ok(HttpResponse::Ok().content_type("text/html").body(""))
 }
 
-pub async fn admin_user_handler(_req: HttpRequest) -> Result<HttpResponse> {
+/// Only admins may manage users.
+fn require_admin(
+    req: &HttpRequest,
+    session: &Session,
+) -> Result<account::Account, actix_web::Error> {
+    let account = signed_in_account(session)?;
+    if account.is_admin {
+        Ok(account)
+    } else {
+        Err(actix_web::error::ErrorForbidden(i18n::t(
+            Lang::preferred(req),
+            "admin.admin_only",
+        )))
+    }
+}
+
+pub async fn admin_user_handler(req: HttpRequest, session: Session) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
+    if require_admin(&req, &session).is_err() {
+        return Ok(HttpResponse::Ok().content_type("text/html").body(format!(
+            "<p class='mt-10 text-center text-gray-600'>{}</p>",
+            i18n::t(lang, "admin.users.admin_only")
+        )));
+    }
+
     let path: PathBuf = "../public/pages/users.html".parse().unwrap();
     let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(i18n::translate(&content, lang)))
 }
 
-pub async fn add_user_handler(form: web::Form<User>) -> Result<HttpResponse, actix_web::Error> {
-    let user = form.into_inner();
+/// Audit summary of a user account; the password hash is left out.
+fn user_summary(id: i32) -> Option<serde_json::Value> {
+    db::get_account_by_id(id)
+        .ok()
+        .flatten()
+        .map(|account| serde_json::json!(account))
+}
 
-    match db::add_user(&user.name, &user.username, &user.password) {
-        Ok(_) => {
-            let users = db::get_users().unwrap();
-            let user_list_html = render_user_list(&users).await.unwrap();
+/// Shows why a user could not be saved inside the open user form instead of
+/// replacing the list.
+fn user_form_error(message: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(("HX-Retarget", "#user-form-error"))
+        .insert_header(("HX-Reswap", "innerHTML"))
+        .body(format!(
+            "<p class='mb-4 text-sm text-red-700'>{}</p>",
+            html::escape_html(message)
+        ))
+}
+
+/// The message for errors users can fix themselves, `None` for the rest.
+fn user_error_message(lang: Lang, error: &(dyn std::error::Error + 'static)) -> Option<String> {
+    if let Some(policy) = error.downcast_ref::<PasswordPolicyError>() {
+        return Some(policy.message(lang));
+    }
+    error
+        .downcast_ref::<account::UserError>()
+        .map(|e| e.message(lang).to_string())
+}
+
+async fn user_list_response(lang: Lang, notice: &str) -> Result<HttpResponse, actix_web::Error> {
+    let users =
+        db::get_users().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let user_list_html = render_user_list(lang, &users, notice)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(user_list_html))
+}
+
+fn user_id(req: &HttpRequest) -> Result<i32, actix_web::Error> {
+    req.match_info()
+        .query("id")
+        .parse()
+        .map_err(|_| actix_web::error::ErrorBadRequest("Invalid user id"))
+}
+
+pub async fn add_user_handler(
+    req: HttpRequest,
+    session: Session,
+    form: web::Form<User>,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+    let user = form.into_inner();
 
-            let mut response = HttpResponse::Ok();
-            response.header("HX-Trigger", "refreshUserList");
-            Ok(response.body(user_list_html))
+    match db::add_user(
+        user.name.trim(),
+        user.username.trim(),
+        user.email.trim(),
+        &user.password,
+        user.is_admin.is_some(),
+    ) {
+        Ok(id) => {
+            audit::created(&req, "user", id, &user_summary(id));
+            user_list_response(Lang::preferred(&req), "").await
         }
-        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+        Err(e) => match user_error_message(Lang::preferred(&req), e.as_ref()) {
+            Some(message) => Ok(user_form_error(&message)),
+            None => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+        },
     }
 }
 
-pub async fn add_user_form_handler() -> Result<HttpResponse, actix_web::Error> {
+pub async fn add_user_form_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+    let lang = Lang::preferred(&req);
     let path: PathBuf = "../public/pages/add_user.html".parse().unwrap();
-    let form = tokio::fs::read_to_string(path).await?;
+    let mut form = i18n::translate(&tokio::fs::read_to_string(path).await?, lang);
+    form = form.replace("{{password_hint}}", &account::password_hint(lang));
     Ok(HttpResponse::Ok().content_type("text/html").body(form))
 }
 
-pub async fn edit_user_form_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
-    let username: String = req.match_info().query("username").parse().unwrap();
-    let user = db::get_user(&username).unwrap();
+pub async fn edit_user_form_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+    let user = db::get_account_by_id(user_id(&req)?)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("User not found"))?;
 
+    let lang = Lang::preferred(&req);
     let path: PathBuf = "../public/pages/edit_user.html".parse().unwrap();
-    let mut form = tokio::fs::read_to_string(path).await?;
+    let mut form = i18n::translate(&tokio::fs::read_to_string(path).await?, lang);
 
-    form = form.replace("{{name}}", &user.0);
-    form = form.replace("{{username}}", &user.1);
+    form = form.replace("{{id}}", &user.id.to_string());
+    form = form.replace("{{name}}", &html::escape_html(&user.name));
+    form = form.replace("{{username}}", &html::escape_html(&user.username));
+    form = form.replace("{{email}}", &html::escape_html(&user.email));
+    form = form.replace("{{is_admin}}", if user.is_admin { "checked" } else { "" });
+    form = form.replace("{{password_hint}}", &account::password_hint(lang));
 
     Ok(HttpResponse::Ok().content_type("text/html").body(form))
 }
 
 pub async fn edit_user_handler(
     req: HttpRequest,
+    session: Session,
     form: web::Form<User>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let username: String = req.match_info().query("username").parse().unwrap();
+    let current = require_admin(&req, &session)?;
+    let lang = Lang::preferred(&req);
+    let id = user_id(&req)?;
     let user = form.into_inner();
 
-    match db::edit_user(&username, &user.name, &user.username, &user.password) {
-        Ok(_) => {
-            let users = db::get_users().unwrap();
-            let user_list_html = render_user_list(&users).await.unwrap();
+    if id == current.id && user.is_admin.is_none() {
+        return Ok(user_form_error(i18n::t(lang, "admin.users.own_admin")));
+    }
 
-            let mut response = HttpResponse::Ok();
-            response.header("HX-Trigger", "refreshUserList");
-            Ok(response.body(user_list_html))
+    let before = user_summary(id);
+    match db::edit_user(
+        id,
+        user.name.trim(),
+        user.username.trim(),
+        user.email.trim(),
+        &user.password,
+        user.is_admin.is_some(),
+    ) {
+        Ok(_) => {
+            let mut after = user_summary(id);
+            if let Some(after) = after.as_mut().filter(|_| !user.password.is_empty()) {
+                after["password_changed"] = serde_json::Value::Bool(true);
+            }
+            audit::updated(&req, "user", id, &before, &after);
+            if id == current.id {
+                let _ = session.insert("user_name", user.name.trim());
+            }
+            user_list_response(lang, "").await
         }
-        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+        Err(e) => match user_error_message(lang, e.as_ref()) {
+            Some(message) => Ok(user_form_error(&message)),
+            None => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+        },
     }
 }
 
-pub async fn delete_user_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
-    let username: String = req.match_info().query("username").parse().unwrap();
+pub async fn delete_user_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    let current = require_admin(&req, &session)?;
+    let lang = Lang::preferred(&req);
+    let id = user_id(&req)?;
+
+    let error = |message: &str| {
+        format!(
+            "<p class='mt-4 text-center text-red-700'>{}</p>",
+            html::escape_html(message)
+        )
+    };
+    if id == current.id {
+        return user_list_response(lang, &error(i18n::t(lang, "admin.users.own_account"))).await;
+    }
 
-    match db::delete_user(&username) {
+    let before = user_summary(id);
+    match db::delete_user(id) {
         Ok(_) => {
-            let users = db::get_users().unwrap();
-
-            let user_list_html = render_user_list(&users).await.unwrap();
-
-            let mut response = HttpResponse::Ok();
-            response.header("HX-Trigger", "refreshUserList");
-            Ok(response.body(user_list_html))
+            audit::deleted(&req, "user", id, &before);
+            user_list_response(lang, "").await
         }
-        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+        Err(e) => match user_error_message(lang, e.as_ref()) {
+            Some(message) => user_list_response(lang, &error(&message)).await,
+            None => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+        },
     }
 }
 
-pub async fn render_user_list(users: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+pub async fn render_user_list(
+    lang: Lang,
+    users: &[account::Account],
+    notice: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
     let path: PathBuf = "../public/pages/user_list.html".parse().unwrap();
-    let mut template = tokio::fs::read_to_string(path).await?;
+    let mut template = i18n::translate(&tokio::fs::read_to_string(path).await?, lang);
 
     let user_rows = users
         .iter()
         .map(|user| {
-            let parts: Vec<&str> = user.split(" (").collect();
-            let name = parts[0];
-            let username = parts[1].trim_end_matches(')');
             format!(
                 "<tr class=\"border\">\n
                 <td class=\"border px-6 py-4 whitespace-nowrap\">{}</td>\n
                 <td class=\"border px-6 py-4 whitespace-nowrap\">{}</td>\n
+                <td class=\"border px-6 py-4 whitespace-nowrap\">{}</td>\n
                 <td class=\"border px-6 py-4 whitespace-nowrap\">
-                <button hx-get=\"/admin/user/edit/form/{}\" hx-target=\"#modal-content .space-y-4\" hx-trigger=\"click\" class=\"px-4 py-2 text-white bg-blue-500 rounded\">Edit</button>
+                <button hx-get=\"/admin/user/edit/form/{}\" hx-target=\"#modal-content .space-y-4\" hx-trigger=\"click\" class=\"px-4 py-2 text-white bg-blue-500 rounded\">{}</button>
                 <button class=\"px-4 py-2 text-white bg-red-500 rounded\"
                 hx-delete=\"/admin/user/delete/{}\" hx-swap=\"innerHTML\" hx-target=\"#user-list\"
-                hx-confirm=\"Are you sure you want to delete this user?\">Delete</button>
+                hx-confirm=\"{}\">{}</button>
                 </td>\n
                 </tr>\n",
-                name, username, username, username
+                html::escape_html(&user.name),
+                html::escape_html(&user.username),
+                if user.is_admin {
+                    i18n::t(lang, "admin.users.role.admin")
+                } else {
+                    i18n::t(lang, "admin.users.role.editor")
+                },
+                user.id,
+                i18n::t(lang, "admin.edit"),
+                user.id,
+                i18n::t(lang, "admin.users.delete_confirm"),
+                i18n::t(lang, "admin.delete")
             )
         })
         .collect::<Vec<String>>()
         .join("");
 
+    template = template.replace("{{notice}}", notice);
     template = template.replace("{{users}}", &user_rows);
 
     Ok(template)
 }
 
-pub async fn get_user_list_handler() -> Result<HttpResponse, actix_web::Error> {
-    let users =
-        db::get_users().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+pub async fn get_user_list_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+    user_list_response(Lang::preferred(&req), "").await
+}
 
-    let rendered = render_user_list(&users)
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+fn render_account(lang: Lang, account: &account::Account, notice: &str) -> String {
+    let input = "border rounded w-full py-2 px-3 text-gray-700";
+    format!(
+        "<div class='w-3/4 mx-auto mt-10 max-w-xl'>
+            <h2 class='text-2xl font-bold mb-2'>{heading}</h2>
+            <p class='text-gray-600 mb-6'>{username_label}: <strong>{username}</strong>. {intro}</p>
+            {notice}
+            <form hx-post='/admin/account' hx-target='#dashboard-container' class='space-y-4'>
+                <div>
+                    <label class='block text-gray-700 text-sm font-bold mb-2' for='account-name'>{name_label}</label>
+                    <input id='account-name' name='name' value='{name}' required class='{input}'>
+                </div>
+                <div>
+                    <label class='block text-gray-700 text-sm font-bold mb-2' for='account-email'>{email_label}</label>
+                    <input id='account-email' type='email' name='email' value='{email}' class='{input}'>
+                    <p class='text-xs text-gray-500 mt-1'>{email_hint}</p>
+                </div>
+                <div>
+                    <label class='block text-gray-700 text-sm font-bold mb-2' for='account-new-password'>{new_password}</label>
+                    <input id='account-new-password' type='password' name='new_password' autocomplete='new-password' class='{input}'>
+                    <p class='text-xs text-gray-500 mt-1'>{new_password_hint} {hint}.</p>
+                </div>
+                <div>
+                    <label class='block text-gray-700 text-sm font-bold mb-2' for='account-new-password-confirm'>{new_password_confirm}</label>
+                    <input id='account-new-password-confirm' type='password' name='new_password_confirm' autocomplete='new-password' class='{input}'>
+                </div>
+                <div>
+                    <label class='block text-gray-700 text-sm font-bold mb-2' for='account-current-password'>{current_password}</label>
+                    <input id='account-current-password' type='password' name='current_password' required autocomplete='current-password' class='{input}'>
+                </div>
+                <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{save}</button>
+            </form>
+        </div>",
+        heading = i18n::t(lang, "admin.nav.account"),
+        username_label = i18n::t(lang, "admin.account.username"),
+        username = html::escape_html(&account.username),
+        intro = i18n::t(lang, "admin.account.intro"),
+        notice = notice,
+        name_label = i18n::t(lang, "admin.column.name"),
+        name = html::escape_html(&account.name),
+        email_label = i18n::t(lang, "admin.column.email"),
+        email = html::escape_html(&account.email),
+        email_hint = i18n::t(lang, "admin.account.email_hint"),
+        new_password = i18n::t(lang, "admin.account.new_password"),
+        new_password_hint = i18n::t(lang, "admin.account.new_password_hint"),
+        hint = account::password_hint(lang),
+        new_password_confirm = i18n::t(lang, "admin.account.new_password_confirm"),
+        current_password = i18n::t(lang, "admin.account.current_password"),
+        save = i18n::t(lang, "admin.save"),
+        input = input,
+    )
+}
 
-    Ok(HttpResponse::Ok().content_type("text/html").body(rendered))
+/// The signed-in account, loaded fresh so that a deleted account loses access
+/// at once.
+fn signed_in_account(session: &Session) -> Result<account::Account, actix_web::Error> {
+    account::signed_in(session)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("Unauthorized"))
 }
 
-pub async fn admin_inbox_handler() -> Result<HttpResponse, actix_web::Error> {
-    let path: PathBuf = "../public/pages/messages.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+/// The signed-in user's own name, e-mail and password.
+pub async fn admin_account_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    let account = signed_in_account(&session)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_account(Lang::preferred(&req), &account, "")))
+}
+
+pub async fn update_account_handler(
+    req: HttpRequest,
+    session: Session,
+    form: web::Form<AccountForm>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let account = signed_in_account(&session)?;
+    let lang = Lang::preferred(&req);
+    let form = form.into_inner();
+    let error = |message: &str| {
+        Ok(HttpResponse::Ok()
+            .content_type("text/html")
+            .body(render_account(
+                lang,
+                &account,
+                &format!(
+                    "<p class='mb-4 text-red-700'>{}</p>",
+                    html::escape_html(message)
+                ),
+            )))
+    };
+
+    let authenticated = db::authenticate_user(&account.username, &form.current_password)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    if authenticated.is_none() {
+        return error(i18n::t(lang, "admin.account.wrong_password"));
+    }
+    if form.name.trim().is_empty() {
+        return error(i18n::t(lang, "admin.account.name_required"));
+    }
+    let change_password = !form.new_password.is_empty();
+    if change_password && form.new_password != form.new_password_confirm {
+        return error(i18n::t(lang, "admin.account.password_mismatch"));
+    }
+
+    if change_password {
+        if let Err(e) = db::set_password(account.id, &form.new_password) {
+            return match e.downcast_ref::<PasswordPolicyError>() {
+                Some(policy) => error(&policy.message(lang)),
+                None => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+            };
+        }
+    }
+    db::update_account(account.id, form.name.trim(), form.email.trim())
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let mut after = user_summary(account.id);
+    if let Some(after) = after.as_mut().filter(|_| change_password) {
+        after["password_changed"] = serde_json::Value::Bool(true);
+    }
+    audit::updated(
+        &req,
+        "user",
+        account.id,
+        &Some(serde_json::json!(account)),
+        &after,
+    );
+    let _ = session.insert("user_name", form.name.trim());
+
+    let updated = signed_in_account(&session)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_account(
+            lang,
+            &updated,
+            &format!(
+                "<p class='mb-4 text-green-700'>{}</p>",
+                i18n::t(lang, "admin.account.saved")
+            ),
+        )))
 }
 
-pub async fn get_messages_handler() -> Result<HttpResponse, actix_web::Error> {
-    let messages = db::get_messages()
+fn render_api_tokens(
+    lang: Lang,
+    current: &account::Account,
+    notice: &str,
+) -> Result<String, actix_web::Error> {
+    // Admins see and can revoke everyone's tokens.
+    let tokens = db::get_api_tokens((!current.is_admin).then_some(current.id))
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
-    let message_rows = messages
+    let rows: String = tokens
         .iter()
-        .map(|message| {
+        .map(|token| {
+            let status = if token.revoked_at.is_some() {
+                format!(
+                    "<span class='text-red-700'>{}</span>",
+                    i18n::t(lang, "admin.tokens.status.revoked")
+                )
+            } else if !token.is_active(&now) {
+                format!(
+                    "<span class='text-gray-500'>{}</span>",
+                    i18n::t(lang, "admin.tokens.status.expired")
+                )
+            } else {
+                format!(
+                    "<span class='text-green-700'>{}</span>",
+                    i18n::t(lang, "admin.tokens.status.active")
+                )
+            };
+            let action = if token.revoked_at.is_none() {
+                format!(
+                    "<button hx-post='/admin/tokens/{}/revoke' hx-target='#dashboard-container'
+                        hx-confirm='{}'
+                        class='bg-red-500 hover:bg-red-700 text-white font-bold py-1 px-3 rounded'>{}</button>",
+                    token.id,
+                    i18n::t(lang, "admin.tokens.revoke_confirm"),
+                    i18n::t(lang, "admin.tokens.revoke")
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                "<tr>
+                    <td class='border px-4 py-2'>{}<div class='text-xs text-gray-500 font-mono'>{}…</div></td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2 text-sm'>{}</td>
+                    <td class='border px-4 py-2 text-sm'>{}</td>
+                    <td class='border px-4 py-2 text-sm'>{}</td>
+                    <td class='border px-4 py-2 text-sm'>{}</td>
+                    <td class='border px-4 py-2 text-center'>{}</td>
+                </tr>",
+                html::escape_html(&token.name),
+                html::escape_html(&token.token_prefix),
+                html::escape_html(&token.username),
+                token.scopes.join("<br>"),
+                token
+                    .expires_at
+                    .as_deref()
+                    .unwrap_or(i18n::t(lang, "admin.tokens.no_expiry")),
+                token.last_used_at.as_deref().unwrap_or("-"),
+                status,
+                action
+            )
+        })
+        .collect();
+
+    let scopes: String = api_token::SCOPES
+        .iter()
+        .map(|(scope, _)| {
+            format!(
+                "<label class='block'><input type='checkbox' name='scopes' value='{0}' class='mr-2'>{1} <span class='text-xs text-gray-500 font-mono'>{0}</span></label>",
+                scope,
+                i18n::t(lang, &format!("admin.tokens.scope.{}", scope))
+            )
+        })
+        .collect();
+
+    let intro = format!(
+        "{}
+                {}
+                {}",
+        i18n::t(lang, "admin.tokens.intro").replace("{api}", "<code>/api/v1</code>"),
+        i18n::t(lang, "admin.tokens.intro_header").replace(
+            "{header}",
+            &format!(
+                "<code>Authorization: Bearer &lt;{}&gt;</code>",
+                i18n::t(lang, "admin.tokens.key")
+            )
+        ),
+        i18n::t(lang, "admin.tokens.intro_docs").replace(
+            "{link}",
+            &format!(
+                "<a href='/api/docs' target='_blank' class='text-blue-600 underline'>{}</a>",
+                i18n::t(lang, "admin.tokens.docs_link")
+            )
+        )
+    );
+
+    Ok(format!(
+        "<div class='w-3/4 mx-auto mt-10'>
+            <h2 class='text-2xl font-bold mb-2'>{}</h2>
+            <p class='text-gray-600 mb-6'>{}</p>
+            {}
+            <form hx-post='/admin/tokens' hx-target='#dashboard-container' class='mb-8 p-4 bg-gray-100 rounded space-y-3'>
+                <div class='flex gap-2'>
+                    <input name='name' required placeholder='{}' class='border rounded py-2 px-3 flex-1'>
+                    <input name='expires_in_days' type='number' min='1' placeholder='{}' class='border rounded py-2 px-3 w-64'>
+                </div>
+                <div>{}</div>
+                <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+            </form>
+            <table class='table-auto w-full'>
+                <thead><tr>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2'></th>
+                </tr></thead>
+                <tbody>{}</tbody>
+            </table>
+        </div>",
+        i18n::t(lang, "admin.nav.tokens"),
+        intro,
+        notice,
+        i18n::t(lang, "admin.tokens.name_placeholder"),
+        i18n::t(lang, "admin.tokens.expiry_placeholder"),
+        scopes,
+        i18n::t(lang, "admin.tokens.create"),
+        i18n::t(lang, "admin.tokens.name"),
+        i18n::t(lang, "admin.audit.user"),
+        i18n::t(lang, "admin.tokens.scopes"),
+        i18n::t(lang, "admin.tokens.expires"),
+        i18n::t(lang, "admin.tokens.last_used"),
+        i18n::t(lang, "admin.column.status"),
+        rows
+    ))
+}
+
+pub async fn admin_tokens_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    let current = signed_in_account(&session)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_api_tokens(Lang::preferred(&req), &current, "")?))
+}
+
+pub async fn create_token_handler(
+    req: HttpRequest,
+    session: Session,
+    form: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let current = signed_in_account(&session)?;
+    let lang = Lang::preferred(&req);
+
+    let mut name = String::new();
+    let mut scopes = Vec::new();
+    let mut expires_in_days = None;
+    for (key, value) in form.into_inner() {
+        match key.as_str() {
+            "name" => name = value.trim().to_string(),
+            "scopes" if api_token::SCOPES.iter().any(|(scope, _)| *scope == value) => {
+                scopes.push(value)
+            }
+            "expires_in_days" => expires_in_days = value.trim().parse::<u32>().ok(),
+            _ => (),
+        }
+    }
+
+    let error = |message: &str| -> Result<HttpResponse, actix_web::Error> {
+        Ok(HttpResponse::Ok()
+            .content_type("text/html")
+            .body(render_api_tokens(
+                lang,
+                &current,
+                &format!("<p class='mb-4 text-red-700'>{}</p>", message),
+            )?))
+    };
+    if name.is_empty() {
+        return error(i18n::t(lang, "admin.tokens.name_required"));
+    }
+    if scopes.is_empty() {
+        return error(i18n::t(lang, "admin.tokens.scope_required"));
+    }
+
+    let (id, token) = api_token::issue(current.id, &name, &scopes, expires_in_days)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::created(&req, "api_token", id, &db::get_api_token(id).ok());
+
+    let notice = format!(
+        "<div class='mb-6 p-4 bg-green-100 rounded'>
+            <p class='font-bold mb-2'>{}</p>
+            <input readonly value='{}' onclick='this.select()' class='w-full font-mono border rounded py-2 px-3'>
+        </div>",
+        i18n::t(lang, "admin.tokens.created"),
+        token
+    );
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_api_tokens(lang, &current, &notice)?))
+}
+
+pub async fn revoke_token_handler(
+    req: HttpRequest,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let current = signed_in_account(&session)?;
+    let before = db::get_api_token(id.into_inner())
+        .map_err(|_| actix_web::error::ErrorNotFound("Token not found"))?;
+    if before.user_id != current.id && !current.is_admin {
+        return Err(actix_web::error::ErrorForbidden("Forbidden"));
+    }
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    db::revoke_api_token(before.id, &now)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::record(
+        &req,
+        "revoke",
+        "api_token",
+        before.id,
+        Some(audit::summary(&before)),
+        db::get_api_token(before.id)
+            .ok()
+            .map(|after| audit::summary(&after)),
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_api_tokens(
+            Lang::preferred(&req),
+            &current,
+            &format!(
+                "<p class='mb-4 text-green-700'>{}</p>",
+                i18n::t(Lang::preferred(&req), "admin.tokens.revoked")
+            ),
+        )?))
+}
+
+/// How many jobs of each status the Jobs panel lists.
+const JOBS_PANEL_SIZE: i32 = 50;
+
+fn render_job_rows(lang: Lang, jobs: &[jobs::Job], actions: bool) -> String {
+    if jobs.is_empty() {
+        return format!(
+            "<tr><td colspan='6' class='border px-4 py-2 text-center text-gray-500'>{}</td></tr>",
+            i18n::t(lang, "admin.jobs.none")
+        );
+    }
+
+    jobs.iter()
+        .map(|job| {
+            let action = if actions {
+                format!(
+                    "<button hx-post='/admin/jobs/{0}/retry' hx-target='#dashboard-container'
+                        class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-1 px-3 rounded'>{1}</button>
+                    <button hx-delete='/admin/jobs/{0}' hx-target='#dashboard-container'
+                        hx-confirm='{2}'
+                        class='bg-red-500 hover:bg-red-700 text-white font-bold py-1 px-3 rounded'>{3}</button>",
+                    job.id,
+                    i18n::t(lang, "admin.jobs.retry"),
+                    i18n::t(lang, "admin.jobs.delete_confirm"),
+                    i18n::t(lang, "admin.delete")
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                "<tr class='align-top'>
+                    <td class='border px-4 py-2'>{}<div class='text-xs text-gray-500 font-mono'>#{}</div></td>
+                    <td class='border px-4 py-2 whitespace-nowrap'>{}</td>
+                    <td class='border px-4 py-2 whitespace-nowrap'>{}</td>
+                    <td class='border px-4 py-2 text-center'>{}</td>
+                    <td class='border px-4 py-2 break-all text-red-700'>{}</td>
+                    <td class='border px-4 py-2 text-center whitespace-nowrap'>{}</td>
+                </tr>",
+                job.label(lang),
+                job.id,
+                format_received_at(Some(&job.created_at)),
+                match job.status.as_str() {
+                    "running" => format_received_at(job.started_at.as_deref()),
+                    "failed" => format_received_at(job.finished_at.as_deref()),
+                    _ => format_received_at(Some(&job.run_at)),
+                },
+                job.attempts,
+                html::escape_html(job.last_error.as_deref().unwrap_or("")),
+                action
+            )
+        })
+        .collect()
+}
+
+fn render_jobs(lang: Lang, notice: &str) -> Result<String, actix_web::Error> {
+    let to_http = |e: rusqlite::Error| actix_web::error::ErrorInternalServerError(e.to_string());
+    let counts = db::count_jobs_by_status().map_err(to_http)?;
+    let count = |status: &str| counts.get(status).copied().unwrap_or(0);
+    let running = db::get_jobs("running", JOBS_PANEL_SIZE).map_err(to_http)?;
+    let queued = db::get_jobs("queued", JOBS_PANEL_SIZE).map_err(to_http)?;
+    let failed = db::get_jobs("failed", JOBS_PANEL_SIZE).map_err(to_http)?;
+
+    let table = |title: &str, when: &str, rows: String| {
+        format!(
+            "<h3 class='text-xl font-bold mt-8 mb-2'>{}</h3>
+            <table class='table-auto w-full text-sm'>
+                <thead><tr>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2'></th>
+                </tr></thead>
+                <tbody>{}</tbody>
+            </table>",
+            i18n::t(lang, title),
+            i18n::t(lang, "admin.jobs.job"),
+            i18n::t(lang, "admin.jobs.added"),
+            i18n::t(lang, when),
+            i18n::t(lang, "admin.jobs.attempts"),
+            i18n::t(lang, "admin.jobs.last_error"),
+            rows
+        )
+    };
+    let stat = |label: &str, value: i64, color: &str| {
+        format!(
+            "<div class='p-4 bg-gray-100 rounded text-center'>
+                <div class='text-3xl font-bold {}'>{}</div>
+                <div class='text-sm text-gray-600'>{}</div>
+            </div>",
+            color,
+            value,
+            i18n::t(lang, label)
+        )
+    };
+
+    Ok(format!(
+        "<div class='w-3/4 mx-auto mt-10'>
+            <div class='flex justify-between items-center mb-2'>
+                <h2 class='text-2xl font-bold'>{}</h2>
+                <button hx-get='/admin/jobs' hx-target='#dashboard-container'
+                    class='bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+            </div>
+            <p class='text-gray-600 mb-6'>{}</p>
+            {}
+            <div class='grid grid-cols-4 gap-4'>{}{}{}{}</div>
+            {}{}{}
+        </div>",
+        i18n::t(lang, "admin.nav.jobs"),
+        i18n::t(lang, "admin.jobs.refresh"),
+        i18n::t(lang, "admin.jobs.intro")
+            .replace("{attempts}", &config::get().jobs.max_attempts.to_string())
+            .replace("{days}", &jobs::DONE_RETENTION_DAYS.to_string()),
+        notice,
+        stat("admin.jobs.queued", count("queued"), "text-blue-700"),
+        stat("admin.jobs.running", count("running"), "text-yellow-600"),
+        stat("admin.jobs.failed", count("failed"), "text-red-700"),
+        stat("admin.jobs.done", count("done"), "text-green-700"),
+        table(
+            "admin.jobs.running",
+            "admin.jobs.started",
+            render_job_rows(lang, &running, false)
+        ),
+        table(
+            "admin.jobs.queued",
+            "admin.jobs.run_at",
+            render_job_rows(lang, &queued, false)
+        ),
+        table(
+            "admin.jobs.failed",
+            "admin.jobs.last_attempt",
+            render_job_rows(lang, &failed, true)
+        ),
+    ))
+}
+
+pub async fn admin_jobs_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_jobs(Lang::preferred(&req), "")?))
+}
+
+/// The Jobs panel with a `(colour class, catalogue key)` notice above it.
+fn render_jobs_with_notice(
+    req: &HttpRequest,
+    (colour, key): (&str, &str),
+) -> Result<String, actix_web::Error> {
+    let lang = Lang::preferred(req);
+    render_jobs(
+        lang,
+        &format!("<p class='mb-4 {}'>{}</p>", colour, i18n::t(lang, key)),
+    )
+}
+
+pub async fn retry_job_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let id = id.into_inner();
+    let before = db::get_job(id).map_err(|_| actix_web::error::ErrorNotFound("Job not found"))?;
+    let requeued =
+        jobs::retry(id).map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let notice = if requeued {
+        audit::record(
+            &req,
+            "retry",
+            "job",
+            id,
+            Some(audit::summary(&before)),
+            db::get_job(id).ok().map(|after| audit::summary(&after)),
+        );
+        ("text-green-700", "admin.jobs.requeued")
+    } else {
+        ("text-red-700", "admin.jobs.retry_failed_only")
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_jobs_with_notice(&req, notice)?))
+}
+
+pub async fn delete_job_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let id = id.into_inner();
+    let before = db::get_job(id).map_err(|_| actix_web::error::ErrorNotFound("Job not found"))?;
+    let deleted = db::delete_failed_job(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let notice = if deleted {
+        audit::deleted(&req, "job", id, &before);
+        ("text-green-700", "admin.jobs.deleted")
+    } else {
+        ("text-red-700", "admin.jobs.delete_failed_only")
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_jobs_with_notice(&req, notice)?))
+}
+
+pub async fn admin_inbox_handler(session: Session) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let path: PathBuf = "../public/pages/messages.html".parse().unwrap();
+    let content = tokio::fs::read_to_string(path).await?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+}
+
+fn format_received_at(received_at: Option<&str>) -> String {
+    received_at
+        .and_then(|value| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok())
+        .map(|value| value.format("%d-%m-%Y %H:%M").to_string())
+        .unwrap_or_else(|| String::from("-"))
+}
+
+fn staff_names() -> Result<HashMap<i32, String>, actix_web::Error> {
+    let staff =
+        db::get_staff().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(staff
+        .into_iter()
+        .map(|member| (member.id, member.full_name()))
+        .collect())
+}
+
+fn status_options(
+    lang: Lang,
+    selected: Option<MessageStatus>,
+    empty_label: Option<&str>,
+) -> String {
+    let mut options = match empty_label {
+        Some(label) => format!("<option value=''>{}</option>", label),
+        None => String::new(),
+    };
+    for status in MessageStatus::ALL {
+        options.push_str(&format!(
+            "<option value='{}'{}>{}</option>",
+            status.as_str(),
+            if selected == Some(status) {
+                " selected"
+            } else {
+                ""
+            },
+            status.label(lang)
+        ));
+    }
+    options
+}
+
+fn staff_options(names: &HashMap<i32, String>, selected: Option<i32>, empty_label: &str) -> String {
+    let mut staff: Vec<(&i32, &String)> = names.iter().collect();
+    staff.sort_by(|a, b| a.1.cmp(b.1));
+
+    let mut options = format!("<option value=''>{}</option>", empty_label);
+    for (id, name) in staff {
+        options.push_str(&format!(
+            "<option value='{}'{}>{}</option>",
+            id,
+            if selected == Some(*id) {
+                " selected"
+            } else {
+                ""
+            },
+            html::escape_html(name)
+        ));
+    }
+    options
+}
+
+fn status_badge(lang: Lang, status: MessageStatus) -> String {
+    let colour = match status {
+        MessageStatus::New => "bg-blue-100 text-blue-800",
+        MessageStatus::InProgress => "bg-yellow-100 text-yellow-800",
+        MessageStatus::Answered => "bg-green-100 text-green-800",
+        MessageStatus::Spam => "bg-gray-200 text-gray-700",
+    };
+    format!(
+        "<span class='px-2 py-1 text-xs font-semibold rounded {}'>{}</span>",
+        colour,
+        status.label(lang)
+    )
+}
+
+fn excerpt(text: &str, length: usize) -> String {
+    if text.chars().count() > length {
+        format!("{}…", text.chars().take(length).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Tells the dashboard to refresh anything that depends on the inbox, such as the unread counter.
+fn inbox_response(body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html")
+        .insert_header(("HX-Trigger", "messages-changed"))
+        .body(body)
+}
+
+fn render_inbox(lang: Lang, filter: &MessageFilter) -> Result<String, actix_web::Error> {
+    let messages = db::get_messages(filter)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let names = staff_names()?;
+
+    let message_rows = messages
+        .iter()
+        .map(|message| {
+            format!(
+                "<tr class=\"{} border-b dark:bg-gray-800 dark:border-gray-700\">
+                <td class=\"px-4 py-4\"><input type='checkbox' name='ids' value='{}'></td>
+                <td class=\"px-4 py-4 whitespace-nowrap\">{}</td>
+                <th scope=\"row\" class=\"px-4 py-4 text-gray-900 dark:text-white\">{}<br><span class=\"text-xs text-gray-500\">{}</span></th>
+                <td class=\"px-4 py-4 text-left\">
+                    <a href='' hx-get='/admin/messages/{}' hx-target='#dashboard-container' class='underline'>{}</a>
+                </td>
+                <td class=\"px-4 py-4\">{}</td>
+                <td class=\"px-4 py-4\">{}</td>
+                <td class=\"px-4 py-4\">
+                    <button type='button' hx-delete='/admin/messages/delete/{}' hx-confirm='{}' hx-swap='outerHTML' hx-target='closest tr' class='text-red-500'>{}</button>
+                </td>
+                </tr>",
+                if message.is_read { "bg-white" } else { "bg-blue-50 font-semibold" },
+                message.id,
+                format_received_at(message.received_at.as_deref()),
+                html::escape_html(&message.name),
+                html::escape_html(&message.email),
+                message.id,
+                html::escape_html(&excerpt(&message.message, 80)),
+                status_badge(lang, message.status),
+                message
+                    .assigned_staff_id
+                    .and_then(|id| names.get(&id))
+                    .map(|name| html::escape_html(name))
+                    .unwrap_or_else(|| String::from("-")),
+                message.id,
+                i18n::t(lang, "admin.inbox.delete_confirm"),
+                i18n::t(lang, "admin.delete")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("");
+
+    let spam_count = db::count_spam_messages()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let assigned = filter.assigned_staff_id();
+    let mut bulk_actions = format!(
+        "<option value='read'>{}</option>
+        <option value='unread'>{}</option>",
+        i18n::t(lang, "admin.inbox.mark_read"),
+        i18n::t(lang, "admin.inbox.mark_unread")
+    );
+    for status in MessageStatus::ALL {
+        bulk_actions.push_str(&format!(
+            "<option value='status:{}'>{}: {}</option>",
+            status.as_str(),
+            i18n::t(lang, "admin.column.status"),
+            status.label(lang)
+        ));
+    }
+    bulk_actions.push_str(&format!(
+        "<option value='delete'>{}</option>",
+        i18n::t(lang, "admin.delete")
+    ));
+
+    Ok(format!(
+        "
+        <div class=\"w-3/4 mx-auto mt-10 justify-center items-center text-center\">
+            <form id='message-filters' hx-get='/admin/messages' hx-target='#dashboard-container' hx-trigger='change, submit'
+                class='flex flex-wrap items-center justify-center gap-2 mb-4'>
+                <select name='status' class='border rounded py-2 px-3 text-gray-700'>{}</select>
+                <select name='assigned' class='border rounded py-2 px-3 text-gray-700'>{}</select>
+                <label class='text-gray-700'><input type='checkbox' name='unread' value='1'{}> {}</label>
+                <input type='search' name='q' value='{}' placeholder='{}' class='border rounded py-2 px-3 text-gray-700'>
+                <button type='button' hx-get='/admin/messages?status=spam' hx-target='#dashboard-container'
+                    class='bg-gray-200 hover:bg-gray-300 text-gray-800 py-2 px-4 rounded'>{} ({})</button>
+            </form>
+            <form id='message-bulk' hx-post='/admin/messages/bulk' hx-target='#dashboard-container' hx-include='#message-filters'
+                hx-confirm='{}'>
+                <div class='flex items-center justify-start gap-2 mb-2'>
+                    <select name='action' class='border rounded py-2 px-3 text-gray-700'>{}</select>
+                    <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+                </div>
+                <div class=\"relative overflow-x-auto\">
+                    <table id=\"message-table\" class=\"w-full text-sm text-center rtl:text-right text-gray-500 dark:text-gray-400\">
+                        <thead class=\"text-xs text-gray-700 uppercase bg-gray-50 dark:bg-gray-700 dark:text-gray-400\">
+                            <tr>
+                                <th scope=\"col\" class=\"px-4 py-3\"></th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                                <th scope=\"col\" class=\"px-4 py-3\">{}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {}
+                        </tbody>
+                    </table>
+                </div>
+            </form>
+        </div>",
+        status_options(
+            lang,
+            filter.status(),
+            Some(i18n::t(lang, "admin.inbox.all_statuses"))
+        ),
+        staff_options(&names, assigned, i18n::t(lang, "admin.inbox.all_assignees")),
+        if filter.unread_only() { " checked" } else { "" },
+        i18n::t(lang, "admin.inbox.unread_only"),
+        html::escape_html(filter.query().unwrap_or("")),
+        i18n::t(lang, "admin.inbox.search"),
+        i18n::t(lang, "admin.inbox.spam"),
+        spam_count,
+        i18n::t(lang, "admin.inbox.bulk_confirm"),
+        bulk_actions,
+        i18n::t(lang, "admin.inbox.apply"),
+        i18n::t(lang, "admin.column.date"),
+        i18n::t(lang, "admin.inbox.sender"),
+        i18n::t(lang, "admin.inbox.message"),
+        i18n::t(lang, "admin.column.status"),
+        i18n::t(lang, "admin.inbox.assignee"),
+        i18n::t(lang, "admin.column.actions"),
+        if message_rows.is_empty() {
+            format!(
+                "<tr><td colspan='7' class='px-4 py-6'>{}</td></tr>",
+                i18n::t(lang, "admin.inbox.none")
+            )
+        } else {
+            message_rows
+        }
+    ))
+}
+
+pub async fn get_messages_handler(
+    req: HttpRequest,
+    filter: Query<MessageFilter>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let table = render_inbox(Lang::preferred(&req), &filter)?;
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(table))
+}
+
+fn render_replies(replies: &[MessageReply]) -> String {
+    replies
+        .iter()
+        .map(|reply| {
+            format!(
+                "<div class='bg-gray-50 border-l-4 border-blue-400 rounded px-6 py-4 mb-2'>
+                    <div class='flex justify-between text-sm text-gray-600 mb-2'>
+                        <span>{}</span>
+                        <span>{}</span>
+                    </div>
+                    <p class='font-semibold mb-1'>{}</p>
+                    <p class='whitespace-pre-wrap text-gray-800'>{}</p>
+                </div>",
+                html::escape_html(&reply.author),
+                format_received_at(Some(&reply.sent_at)),
+                html::escape_html(&reply.subject),
+                html::escape_html(&reply.body)
+            )
+        })
+        .collect()
+}
+
+/// Category, extra form fields and attachment sent along with the message.
+fn render_message_extras(lang: Lang, message: &Message) -> Result<String, actix_web::Error> {
+    let mut rows = Vec::new();
+
+    if let Some(category_id) = message.category_id {
+        let categories = db::get_contact_categories()
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        if let Some(category) = categories
+            .iter()
+            .find(|category| category.id == category_id)
+        {
+            rows.push((
+                i18n::t(lang, "contact.topic").to_string(),
+                html::escape_html(&category.name),
+            ));
+        }
+    }
+
+    for field in &message.extra_fields {
+        rows.push((
+            html::escape_html(&field.label),
+            html::escape_html(&field.value),
+        ));
+    }
+
+    if let Some(name) = &message.attachment_name {
+        rows.push((
+            i18n::t(lang, "admin.inbox.attachment").to_string(),
+            format!(
+                "<a href='/admin/messages/{}/attachment' class='text-blue-600 underline'>{}</a>",
+                message.id,
+                html::escape_html(name)
+            ),
+        ));
+    }
+
+    if rows.is_empty() {
+        return Ok(String::new());
+    }
+
+    let rows: String = rows
+        .iter()
+        .map(|(label, value)| {
+            format!(
+                "<dt class='font-semibold text-gray-700'>{}</dt><dd class='text-gray-800 whitespace-pre-wrap'>{}</dd>",
+                label, value
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "<dl class='grid grid-cols-[max-content_1fr] gap-x-4 gap-y-1 border-t mt-4 pt-4 text-sm'>{}</dl>",
+        rows
+    ))
+}
+
+/// `notice` is shown above the reply form, e.g. the result of sending a reply.
+fn render_message_detail(
+    lang: Lang,
+    message: &Message,
+    notice: &str,
+) -> Result<String, actix_web::Error> {
+    let names = staff_names()?;
+    let replies = db::get_message_replies(message.id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(format!(
+        "<div class='w-1/2 mx-auto mt-10'>
+            <button hx-get='/admin/messages' hx-target='#dashboard-container' class='text-blue-500 mb-4'>&larr; {}</button>
+            <div class='bg-white shadow-md rounded px-8 pt-6 pb-8 mb-4'>
+                <div class='flex justify-between items-start mb-4'>
+                    <div>
+                        <h2 class='text-xl font-bold'>{}</h2>
+                        <a href='mailto:{}' class='text-blue-600 underline'>{}</a>
+                    </div>
+                    <div class='text-right text-sm text-gray-600'>
+                        <div>{}</div>
+                        <div>IP: {}</div>
+                        <div>{}: {}</div>
+                        <div class='mt-1'>{}</div>
+                    </div>
+                </div>
+                <p class='whitespace-pre-wrap text-gray-800 border-t pt-4'>{}</p>
+                {}
+            </div>
+            <form class='bg-white shadow-md rounded px-8 pt-6 pb-8 mb-4' hx-post='/admin/messages/{}' hx-target='#dashboard-container'>
+                <div class='flex gap-4 mb-4'>
+                    <label class='flex-1 text-gray-700 text-sm font-bold'>{}
+                        <select name='status' class='block w-full border rounded py-2 px-3 mt-1 font-normal'>{}</select>
+                    </label>
+                    <label class='flex-1 text-gray-700 text-sm font-bold'>{}
+                        <select name='assigned' class='block w-full border rounded py-2 px-3 mt-1 font-normal'>{}</select>
+                    </label>
+                </div>
+                <label class='block text-gray-700 text-sm font-bold mb-4'>{}
+                    <textarea name='notes' rows='4' class='block w-full border rounded py-2 px-3 mt-1 font-normal'>{}</textarea>
+                </label>
+                <div class='flex items-center justify-between'>
+                    <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+                    <button type='button' hx-post='/admin/messages/{}/unread' hx-target='#dashboard-container'
+                        class='bg-gray-300 hover:bg-gray-400 text-gray-800 font-bold py-2 px-4 rounded'>{}</button>
+                </div>
+            </form>
+            <div class='mb-4'>{}</div>
+            {}
+            <form class='bg-white shadow-md rounded px-8 pt-6 pb-8 mb-4' hx-post='/admin/messages/{}/reply' hx-target='#dashboard-container'
+                hx-confirm='{}'>
+                <h3 class='text-lg font-bold mb-4'>{}</h3>
+                <input type='text' name='subject' value='{}' required
+                    class='block w-full border rounded py-2 px-3 mb-4 text-gray-700'>
+                <textarea name='body' rows='6' required placeholder='{}'
+                    class='block w-full border rounded py-2 px-3 mb-4 text-gray-700'></textarea>
+                <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+            </form>
+        </div>",
+        i18n::t(lang, "admin.nav.inbox"),
+        html::escape_html(&message.name),
+        html::escape_html(&message.email),
+        html::escape_html(&message.email),
+        format_received_at(message.received_at.as_deref()),
+        html::escape_html(&message.ip_address),
+        i18n::t(lang, "admin.inbox.spam_score"),
+        message.spam_score,
+        status_badge(lang, message.status),
+        html::escape_html(&message.message),
+        render_message_extras(lang, message)?,
+        message.id,
+        i18n::t(lang, "admin.column.status"),
+        status_options(lang, Some(message.status), None),
+        i18n::t(lang, "admin.inbox.assignee"),
+        staff_options(
+            &names,
+            message.assigned_staff_id,
+            i18n::t(lang, "admin.inbox.unassigned")
+        ),
+        i18n::t(lang, "admin.inbox.notes"),
+        html::escape_html(&message.notes),
+        i18n::t(lang, "admin.save"),
+        message.id,
+        i18n::t(lang, "admin.inbox.mark_unread"),
+        render_replies(&replies),
+        notice,
+        message.id,
+        i18n::t(lang, "admin.inbox.reply_confirm"),
+        i18n::t(lang, "admin.inbox.reply"),
+        i18n::t(lang, "admin.inbox.reply_subject"),
+        i18n::t(lang, "admin.inbox.reply_placeholder"),
+        i18n::t(lang, "admin.inbox.send_reply"),
+    ))
+}
+
+pub async fn message_detail_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let mut message =
+        db::get_message(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    if !message.is_read {
+        db::set_message_read(id, true)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        message.is_read = true;
+    }
+
+    Ok(inbox_response(render_message_detail(
+        Lang::preferred(&req),
+        &message,
+        "",
+    )?))
+}
+
+#[derive(Deserialize)]
+pub struct MessageWorkflowForm {
+    status: String,
+    assigned: String,
+    notes: String,
+}
+
+/// Audit summary of a message. Only workflow fields are kept: the audit log
+/// cannot be edited, and the sender's personal data must stay erasable.
+fn message_summary(message: &Message) -> serde_json::Value {
+    serde_json::json!({
+        "status": message.status.as_str(),
+        "assigned_staff_id": message.assigned_staff_id,
+        "is_read": message.is_read,
+    })
+}
+
+pub async fn update_message_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    form: web::Form<MessageWorkflowForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let status = form
+        .status
+        .parse()
+        .map_err(|_| actix_web::error::ErrorBadRequest("Invalid status"))?;
+
+    let previous =
+        db::get_message(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    let assigned = form.assigned.parse().ok();
+
+    db::update_message_workflow(id, status, assigned, form.notes.trim())
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    if assigned.is_some() && assigned != previous.assigned_staff_id {
+        mailer::notify_message_in_background(id);
+    }
+
+    let message =
+        db::get_message(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    audit::updated(
+        &req,
+        "message",
+        id,
+        &message_summary(&previous),
+        &message_summary(&message),
+    );
+    Ok(inbox_response(render_message_detail(
+        Lang::preferred(&req),
+        &message,
+        "",
+    )?))
+}
+
+#[derive(Deserialize)]
+pub struct MessageReplyForm {
+    subject: String,
+    body: String,
+}
+
+/// Emails a reply to the sender and keeps a copy in the message's thread.
+pub async fn reply_message_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    form: web::Form<MessageReplyForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let lang = Lang::preferred(&req);
+    let id = id.into_inner();
+    let message =
+        db::get_message(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    let subject = form.subject.trim();
+    let body = form.body.trim();
+    if subject.is_empty() || body.is_empty() {
+        return Ok(inbox_response(render_message_detail(
+            lang,
+            &message,
+            &format!(
+                "<p class='text-red-500'>{}</p>",
+                i18n::t(lang, "admin.inbox.reply_required")
+            ),
+        )?));
+    }
+
+    if let Err(e) = mailer::send(&message.email, None, subject, body).await {
+        return Ok(inbox_response(render_message_detail(
+            lang,
+            &message,
+            &format!(
+                "<p class='text-red-500'>{}</p>",
+                i18n::t(lang, "admin.inbox.reply_failed")
+                    .replace("{error}", &html::escape_html(&e.to_string()))
+            ),
+        )?));
+    }
+
+    let author = signed_in_account(&session)
+        .map(|account| account.username)
+        .unwrap_or_default();
+    db::add_message_reply(&MessageReply {
+        id: 0,
+        message_id: id,
+        author,
+        subject: subject.to_string(),
+        body: body.to_string(),
+        sent_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    db::set_message_status(id, MessageStatus::Answered)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::record(
+        &req,
+        "reply",
+        "message",
+        id,
+        Some(audit::summary(&message_summary(&message))),
+        Some(audit::summary(&serde_json::json!({ "subject": subject }))),
+    );
+
+    let message =
+        db::get_message(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    Ok(inbox_response(render_message_detail(
+        lang,
+        &message,
+        &format!(
+            "<p class='text-green-600'>{}</p>",
+            i18n::t(lang, "admin.inbox.reply_sent")
+        ),
+    )?))
+}
+
+pub async fn mark_message_unread_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    db::set_message_read(id, false)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::record(&req, "mark_unread", "message", id, None, None);
+
+    Ok(inbox_response(render_inbox(
+        Lang::preferred(&req),
+        &MessageFilter::default(),
+    )?))
+}
+
+/// Applies one action to every checked message. The filter fields are posted
+/// along with the selection so the list comes back the way it was.
+pub async fn bulk_messages_handler(
+    req: HttpRequest,
+    form: web::Form<Vec<(String, String)>>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let mut ids = Vec::new();
+    let mut action = String::new();
+    let mut filter = MessageFilter::default();
+    for (key, value) in form.into_inner() {
+        match key.as_str() {
+            "ids" => ids.extend(value.parse::<i32>().ok()),
+            "action" => action = value,
+            "status" => filter.status = Some(value),
+            "assigned" => filter.assigned = Some(value),
+            "unread" => filter.unread = Some(value),
+            "q" => filter.q = Some(value),
+            _ => (),
+        }
+    }
+
+    for id in &ids {
+        let id = *id;
+        let result = match action.as_str() {
+            "read" => db::set_message_read(id, true),
+            "unread" => db::set_message_read(id, false),
+            "delete" => contact::delete_message_with_attachment(id),
+            other => match other.strip_prefix("status:").and_then(|s| s.parse().ok()) {
+                Some(status) => db::set_message_status(id, status),
+                None => return Err(actix_web::error::ErrorBadRequest("Invalid action")),
+            },
+        };
+        result.map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    }
+
+    if !ids.is_empty() {
+        let ids: Vec<String> = ids.iter().map(i32::to_string).collect();
+        audit::record(
+            &req,
+            "bulk",
+            "message",
+            ids.join(","),
+            None,
+            Some(audit::summary(&serde_json::json!({ "action": action }))),
+        );
+    }
+
+    let lang = Lang::preferred(&req);
+    Ok(inbox_response(render_inbox(lang, &filter)?))
+}
+
+pub async fn unread_messages_count_handler(
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let count = db::count_unread_messages()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let badge = if count > 0 {
+        format!(
+            "<span class='inline-flex items-center justify-center px-2 ms-3 text-sm font-medium text-white bg-red-500 rounded-full'>{}</span>",
+            count
+        )
+    } else {
+        String::new()
+    };
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(badge))
+}
+
+pub async fn delete_message_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id: i32 = req
+        .match_info()
+        .get("id")
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing id parameter"))?
+        .parse()
+        .map_err(|_| actix_web::error::ErrorBadRequest("Invalid id parameter"))?;
+
+    let before = db::get_message(id)
+        .ok()
+        .map(|message| message_summary(&message));
+    contact::delete_message_with_attachment(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "message", id, &before);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Rejects requests without a signed-in account that still exists.
+fn require_login(session: &Session) -> Result<(), actix_web::Error> {
+    signed_in_account(session).map(|_| ())
+}
+
+/// Sends a contact form attachment to a signed-in admin under the name it
+/// was uploaded with.
+pub async fn message_attachment_handler(
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<NamedFile, actix_web::Error> {
+    require_login(&session)?;
+
+    let message = db::get_message(id.into_inner())
+        .map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    let (filename, name) = match (message.attachment_filename, message.attachment_name) {
+        (Some(filename), Some(name)) => (filename, name),
+        _ => return Err(actix_web::error::ErrorNotFound("Message has no attachment")),
+    };
+
+    let path = safe_path::resolve(contact::ATTACHMENTS_DIR, &filename)
+        .map_err(safe_path::to_http_error)?;
+    let file = NamedFile::open_async(path).await?;
+
+    Ok(file.set_content_disposition(docs::content_disposition(
+        DispositionType::Attachment,
+        &name,
+    )))
+}
+
+fn field_type_options(lang: Lang, selected: &str) -> String {
+    contact::FIELD_TYPES
+        .iter()
+        .map(|(value, label)| {
+            format!(
+                "<option value='{}'{}>{}</option>",
+                value,
+                if *value == selected { " selected" } else { "" },
+                i18n::t(lang, label)
+            )
+        })
+        .collect()
+}
+
+fn render_contact_form_settings(lang: Lang) -> Result<String, actix_web::Error> {
+    let names = staff_names()?;
+    let categories = db::get_contact_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let fields = db::get_contact_fields()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let input_class = "border rounded py-1 px-2 w-full";
+    let unassigned = i18n::t(lang, "admin.inbox.unassigned");
+
+    let mut category_rows = String::new();
+    for category in &categories {
+        category_rows.push_str(&format!(
+            "<tr>
+                <td class='border px-2 py-2'><input form='category-{0}' name='name' value='{1}' required class='{2}'></td>
+                <td class='border px-2 py-2'><select form='category-{0}' name='staff' class='{2}'>{3}</select></td>
+                <td class='border px-2 py-2 w-24'><input form='category-{0}' type='number' name='display_order' value='{4}' class='{2}'></td>
+                <td class='border px-2 py-2 whitespace-nowrap'>
+                    <form id='category-{0}' class='inline' hx-post='/admin/contact-form/categories/{0}' hx-target='#dashboard-container'>
+                        <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-1 px-3 rounded'>{5}</button>
+                    </form>
+                    <button hx-post='/admin/contact-form/categories/{0}/delete' hx-target='#dashboard-container' hx-confirm='{6}'
+                        class='bg-red-500 hover:bg-red-700 text-white font-bold py-1 px-3 rounded'>{7}</button>
+                </td>
+            </tr>",
+            category.id,
+            html::escape_html(&category.name),
+            input_class,
+            staff_options(&names, category.staff_id, unassigned),
+            category.display_order,
+            i18n::t(lang, "admin.save"),
+            i18n::t(lang, "admin.contact_form.topic_delete_confirm"),
+            i18n::t(lang, "admin.delete")
+        ));
+    }
+
+    let mut field_rows = String::new();
+    for field in &fields {
+        field_rows.push_str(&format!(
+            "<tr>
+                <td class='border px-2 py-2'><input form='field-{0}' name='label' value='{1}' required class='{2}'></td>
+                <td class='border px-2 py-2'><select form='field-{0}' name='field_type' class='{2}'>{3}</select></td>
+                <td class='border px-2 py-2 text-center'><input form='field-{0}' type='checkbox' name='required'{4}></td>
+                <td class='border px-2 py-2'><input form='field-{0}' name='options' value='{5}' placeholder='{7}' class='{2}'></td>
+                <td class='border px-2 py-2 w-24'><input form='field-{0}' type='number' name='display_order' value='{6}' class='{2}'></td>
+                <td class='border px-2 py-2 whitespace-nowrap'>
+                    <form id='field-{0}' class='inline' hx-post='/admin/contact-form/fields/{0}' hx-target='#dashboard-container'>
+                        <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-1 px-3 rounded'>{8}</button>
+                    </form>
+                    <button hx-post='/admin/contact-form/fields/{0}/delete' hx-target='#dashboard-container' hx-confirm='{9}'
+                        class='bg-red-500 hover:bg-red-700 text-white font-bold py-1 px-3 rounded'>{10}</button>
+                </td>
+            </tr>",
+            field.id,
+            html::escape_html(&field.label),
+            input_class,
+            field_type_options(lang, &field.field_type),
+            if field.required { " checked" } else { "" },
+            html::escape_html(&field.options),
+            field.display_order,
+            i18n::t(lang, "admin.contact_form.options_placeholder"),
+            i18n::t(lang, "admin.save"),
+            i18n::t(lang, "admin.contact_form.field_delete_confirm"),
+            i18n::t(lang, "admin.delete")
+        ));
+    }
+
+    Ok(format!(
+        "<div class='w-3/4 mx-auto mt-10'>
+            <h2 class='text-2xl font-bold mb-2'>{}</h2>
+            <p class='text-gray-600 mb-6'>{}</p>
+            <h3 class='text-xl font-bold mb-2'>{}</h3>
+            <table class='table-auto w-full mb-4'>
+                <thead><tr>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2'></th>
+                </tr></thead>
+                <tbody>{}</tbody>
+            </table>
+            <form class='flex gap-2 mb-10' hx-post='/admin/contact-form/categories' hx-target='#dashboard-container'>
+                <input name='name' placeholder='{}' required class='{}'>
+                <select name='staff' class='{}'>{}</select>
+                <input type='number' name='display_order' value='0' class='{} w-24'>
+                <button type='submit' class='bg-green-500 hover:bg-green-700 text-white font-bold py-1 px-4 rounded whitespace-nowrap'>{}</button>
+            </form>
+            <h3 class='text-xl font-bold mb-2'>{}</h3>
+            <table class='table-auto w-full mb-4'>
+                <thead><tr>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2'>{}</th>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2 text-left'>{}</th>
+                    <th class='px-2 py-2'></th>
+                </tr></thead>
+                <tbody>{}</tbody>
+            </table>
+            <form class='flex gap-2 items-center' hx-post='/admin/contact-form/fields' hx-target='#dashboard-container'>
+                <input name='label' placeholder='{}' required class='{}'>
+                <select name='field_type' class='{}'>{}</select>
+                <label class='whitespace-nowrap'><input type='checkbox' name='required'> {}</label>
+                <input name='options' placeholder='{}' class='{}'>
+                <input type='number' name='display_order' value='0' class='{} w-24'>
+                <button type='submit' class='bg-green-500 hover:bg-green-700 text-white font-bold py-1 px-4 rounded whitespace-nowrap'>{}</button>
+            </form>
+        </div>",
+        i18n::t(lang, "admin.nav.contact_form"),
+        i18n::t(lang, "admin.contact_form.intro"),
+        i18n::t(lang, "admin.contact_form.topics"),
+        i18n::t(lang, "contact.topic"),
+        i18n::t(lang, "admin.inbox.assignee"),
+        i18n::t(lang, "admin.column.order"),
+        category_rows,
+        i18n::t(lang, "admin.contact_form.new_topic"),
+        input_class,
+        input_class,
+        staff_options(&names, None, unassigned),
+        input_class,
+        i18n::t(lang, "admin.contact_form.add_topic"),
+        i18n::t(lang, "admin.contact_form.fields"),
+        i18n::t(lang, "admin.contact_form.label"),
+        i18n::t(lang, "admin.contact_form.type"),
+        i18n::t(lang, "admin.contact_form.required"),
+        i18n::t(lang, "admin.contact_form.options"),
+        i18n::t(lang, "admin.column.order"),
+        field_rows,
+        i18n::t(lang, "admin.contact_form.new_field"),
+        input_class,
+        input_class,
+        field_type_options(lang, "text"),
+        i18n::t(lang, "admin.contact_form.required"),
+        i18n::t(lang, "admin.contact_form.new_options"),
+        input_class,
+        input_class,
+        i18n::t(lang, "admin.contact_form.add_field")
+    ))
+}
+
+fn contact_form_response(lang: Lang) -> Result<HttpResponse, actix_web::Error> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_contact_form_settings(lang)?))
+}
+
+pub async fn admin_contact_form_handler(
+    req: HttpRequest,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+#[derive(Deserialize)]
+pub struct ContactCategoryForm {
+    name: String,
+    staff: String,
+    display_order: String,
+}
+
+impl ContactCategoryForm {
+    fn into_category(self, id: i32) -> ContactCategory {
+        ContactCategory {
+            id,
+            name: self.name.trim().to_string(),
+            staff_id: self.staff.parse().ok(),
+            display_order: self.display_order.parse().unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ContactFieldForm {
+    label: String,
+    field_type: String,
+    required: Option<String>,
+    #[serde(default)]
+    options: String,
+    display_order: String,
+}
+
+impl ContactFieldForm {
+    fn into_field(self, id: i32) -> Result<ContactField, actix_web::Error> {
+        if !contact::FIELD_TYPES
+            .iter()
+            .any(|(value, _)| *value == self.field_type)
+        {
+            return Err(actix_web::error::ErrorBadRequest("Invalid field type"));
+        }
+
+        Ok(ContactField {
+            id,
+            label: self.label.trim().to_string(),
+            field_type: self.field_type,
+            required: self.required.is_some(),
+            options: self.options.trim().to_string(),
+            display_order: self.display_order.parse().unwrap_or(0),
+        })
+    }
+}
+
+pub async fn add_contact_category_handler(
+    req: HttpRequest,
+    form: web::Form<ContactCategoryForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let mut category = form.into_inner().into_category(0);
+    category.id = db::add_contact_category(&category)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::created(&req, "contact_category", category.id, &category);
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+fn find_contact_category(id: i32) -> Option<ContactCategory> {
+    db::get_contact_categories()
+        .ok()?
+        .into_iter()
+        .find(|category| category.id == id)
+}
+
+pub async fn edit_contact_category_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    form: web::Form<ContactCategoryForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let before = find_contact_category(id);
+    let category = form.into_inner().into_category(id);
+    db::edit_contact_category(&category)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::updated(&req, "contact_category", id, &before, &Some(category));
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+pub async fn delete_contact_category_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let before = find_contact_category(id);
+    db::delete_contact_category(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "contact_category", id, &before);
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+pub async fn add_contact_field_handler(
+    req: HttpRequest,
+    form: web::Form<ContactFieldForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let mut field = form.into_inner().into_field(0)?;
+    field.id = db::add_contact_field(&field)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::created(&req, "contact_field", field.id, &field);
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+fn find_contact_field(id: i32) -> Option<ContactField> {
+    db::get_contact_fields()
+        .ok()?
+        .into_iter()
+        .find(|field| field.id == id)
+}
+
+pub async fn edit_contact_field_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    form: web::Form<ContactFieldForm>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let before = find_contact_field(id);
+    let field = form.into_inner().into_field(id)?;
+    db::edit_contact_field(&field)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::updated(&req, "contact_field", id, &before, &Some(field));
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+pub async fn delete_contact_field_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let before = find_contact_field(id);
+    db::delete_contact_field(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "contact_field", id, &before);
+
+    contact_form_response(Lang::preferred(&req))
+}
+
+#[derive(Deserialize)]
+pub struct PersonalDataQuery {
+    #[serde(default)]
+    email: String,
+}
+
+fn render_privacy_tools(lang: Lang, email: &str, notice: &str) -> Result<String, actix_web::Error> {
+    let email = email.trim();
+    let results = if email.is_empty() {
+        String::new()
+    } else {
+        let data = privacy::find_personal_data(email)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+        if data.messages.is_empty() && data.subscription.is_none() {
+            format!(
+                "<p class='text-gray-600'>{}</p>",
+                i18n::t(lang, "admin.privacy.not_found")
+                    .replace("{email}", &html::escape_html(email))
+            )
+        } else {
+            let rows: String = data
+                .messages
+                .iter()
+                .map(|record| {
+                    format!(
+                        "<tr>
+                            <td class='border px-4 py-2'>{}</td>
+                            <td class='border px-4 py-2'>{}</td>
+                            <td class='border px-4 py-2'>{}</td>
+                            <td class='border px-4 py-2'>{}</td>
+                            <td class='border px-4 py-2 text-center'>{}</td>
+                        </tr>",
+                        format_received_at(record.message.received_at.as_deref()),
+                        html::escape_html(&record.message.name),
+                        html::escape_html(&excerpt(&record.message.message, 80)),
+                        status_badge(lang, record.message.status),
+                        record.replies.len()
+                    )
+                })
+                .collect();
+
+            format!(
+                "<p class='mb-2'>{1}</p>
+                {3}
+                <table class='table-auto w-full mb-4'>
+                    <thead><tr>
+                        <th class='px-4 py-2 text-left'>{4}</th>
+                        <th class='px-4 py-2 text-left'>{5}</th>
+                        <th class='px-4 py-2 text-left'>{6}</th>
+                        <th class='px-4 py-2 text-left'>{7}</th>
+                        <th class='px-4 py-2'>{8}</th>
+                    </tr></thead>
+                    <tbody>{2}</tbody>
+                </table>
+                <div class='flex gap-2'>
+                    <form action='/admin/privacy/export' method='get'>
+                        <input type='hidden' name='email' value='{0}'>
+                        <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{9}</button>
+                    </form>
+                    <form hx-post='/admin/privacy/anonymize' hx-target='#dashboard-container'
+                        hx-confirm='{10}'>
+                        <input type='hidden' name='email' value='{0}'>
+                        <button type='submit' class='bg-yellow-500 hover:bg-yellow-700 text-white font-bold py-2 px-4 rounded'>{11}</button>
+                    </form>
+                    <form hx-post='/admin/privacy/erase' hx-target='#dashboard-container'
+                        hx-confirm='{12}'>
+                        <input type='hidden' name='email' value='{0}'>
+                        <button type='submit' class='bg-red-500 hover:bg-red-700 text-white font-bold py-2 px-4 rounded'>{13}</button>
+                    </form>
+                </div>",
+                html::escape_html(email),
+                i18n::t(lang, "admin.privacy.found")
+                    .replace(
+                        "{email}",
+                        &format!("<strong>{}</strong>", html::escape_html(email))
+                    )
+                    .replace("{count}", &data.messages.len().to_string()),
+                rows,
+                data.subscription
+                    .as_ref()
+                    .map(|subscription| format!(
+                        "<p class='mb-2'>{}</p>",
+                        i18n::t(lang, "admin.privacy.subscription")
+                            .replace("{date}", &html::escape_html(&subscription.created_at))
+                    ))
+                    .unwrap_or_default(),
+                i18n::t(lang, "admin.column.date"),
+                i18n::t(lang, "admin.column.name"),
+                i18n::t(lang, "admin.inbox.message"),
+                i18n::t(lang, "admin.column.status"),
+                i18n::t(lang, "admin.privacy.replies"),
+                i18n::t(lang, "admin.privacy.export"),
+                i18n::t(lang, "admin.privacy.anonymize_confirm"),
+                i18n::t(lang, "admin.privacy.anonymize"),
+                i18n::t(lang, "admin.privacy.erase_confirm"),
+                i18n::t(lang, "admin.privacy.erase")
+            )
+        }
+    };
+
+    let privacy = &config::get().privacy;
+
+    Ok(format!(
+        "<div class='w-3/4 mx-auto mt-10'>
+            <h2 class='text-2xl font-bold mb-2'>{}</h2>
+            <p class='text-gray-600 mb-6'>{}</p>
+            <form class='flex gap-2 mb-6' hx-get='/admin/privacy' hx-target='#dashboard-container'>
+                <input type='email' name='email' value='{}' placeholder='{}' required class='border rounded py-2 px-3 flex-1'>
+                <button type='submit' class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+            </form>
+            {}
+            {}
+        </div>",
+        i18n::t(lang, "admin.nav.privacy"),
+        i18n::t(lang, "admin.privacy.intro")
+            .replace("{messages}", &privacy.message_retention_days.to_string())
+            .replace("{ips}", &privacy.ip_retention_days.to_string()),
+        html::escape_html(email),
+        i18n::t(lang, "notifications.email_placeholder"),
+        i18n::t(lang, "documents.search"),
+        notice,
+        results
+    ))
+}
+
+pub async fn admin_privacy_handler(
+    req: HttpRequest,
+    query: web::Query<PersonalDataQuery>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_privacy_tools(
+            Lang::preferred(&req),
+            &query.email,
+            "",
+        )?))
+}
+
+/// Downloads everything stored about an e-mail address as JSON.
+pub async fn export_personal_data_handler(
+    req: HttpRequest,
+    query: web::Query<PersonalDataQuery>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let data = privacy::find_personal_data(&query.email)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let json = serde_json::to_string_pretty(&data)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(docs::content_disposition(
+            DispositionType::Attachment,
+            &format!("kisisel-veriler-{}.json", data.email),
+        ))
+        .body(json))
+}
+
+fn record_personal_data_change(req: &HttpRequest, action: &str, ids: &[i32]) {
+    if ids.is_empty() {
+        return;
+    }
+    // The address itself is personal data, so only the message ids are kept.
+    let id_list: Vec<String> = ids.iter().map(i32::to_string).collect();
+    audit::record(
+        req,
+        action,
+        "personal_data",
+        id_list.join(","),
+        None,
+        Some(audit::summary(
+            &serde_json::json!({ "messages": ids.len() }),
+        )),
+    );
+}
+
+pub async fn anonymize_personal_data_handler(
+    req: HttpRequest,
+    form: web::Form<PersonalDataQuery>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let ids = privacy::anonymize_personal_data(&form.email)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    record_personal_data_change(&req, "anonymize", &ids);
+
+    let lang = Lang::preferred(&req);
+    let notice = format!(
+        "<p class='mb-4 text-green-700'>{}</p>",
+        i18n::t(lang, "admin.privacy.anonymized").replace("{count}", &ids.len().to_string())
+    );
+    Ok(inbox_response(render_privacy_tools(lang, "", &notice)?))
+}
+
+pub async fn erase_personal_data_handler(
+    req: HttpRequest,
+    form: web::Form<PersonalDataQuery>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let ids = privacy::erase_personal_data(&form.email)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    record_personal_data_change(&req, "erase", &ids);
+
+    let lang = Lang::preferred(&req);
+    let notice = format!(
+        "<p class='mb-4 text-green-700'>{}</p>",
+        i18n::t(lang, "admin.privacy.erased").replace("{count}", &ids.len().to_string())
+    );
+    Ok(inbox_response(render_privacy_tools(lang, "", &notice)?))
+}
+
+
+const AUDIT_PAGE_SIZE: i32 = 50;
+
+fn audit_select(
+    name: &str,
+    empty_label: &str,
+    options: &[(&str, &str)],
+    selected: Option<&str>,
+) -> String {
+    let options: String = options
+        .iter()
+        .map(|(value, label)| {
+            format!(
+                "<option value='{}'{}>{}</option>",
+                html::escape_html(value),
+                if selected == Some(*value) {
+                    " selected"
+                } else {
+                    ""
+                },
+                html::escape_html(label)
+            )
+        })
+        .collect();
+
+    format!(
+        "<select name='{}' class='border rounded py-2 px-3'><option value=''>{}</option>{}</select>",
+        name, empty_label, options
+    )
+}
+
+fn render_audit_state(state: &str) -> String {
+    if state.is_empty() {
+        return String::from("-");
+    }
+    let pretty = serde_json::from_str::<serde_json::Value>(state)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| state.to_string());
+    format!(
+        "<details><summary class='cursor-pointer text-blue-600'>{}</summary><pre class='text-xs whitespace-pre-wrap break-all'>{}</pre></details>",
+        html::escape_html(&excerpt(state, 40)),
+        html::escape_html(&pretty)
+    )
+}
+
+fn render_audit_log(lang: Lang, filter: &AuditFilter) -> Result<String, actix_web::Error> {
+    let (entries, total) = db::get_audit_entries(filter, Some(AUDIT_PAGE_SIZE))
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let actors = db::get_audit_actors()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let actors: Vec<(&str, &str)> = actors
+        .iter()
+        .map(|actor| (actor.as_str(), actor.as_str()))
+        .collect();
+    let translated = |labels: &[(&'static str, &'static str)]| -> Vec<(&str, &str)> {
+        labels
+            .iter()
+            .map(|(value, key)| (*value, i18n::t(lang, key)))
+            .collect()
+    };
+
+    let rows: String = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "<tr class='align-top'>
+                    <td class='border px-4 py-2 whitespace-nowrap'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2 break-all'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                    <td class='border px-4 py-2'>{}</td>
+                </tr>",
+                format_received_at(Some(&entry.created_at)),
+                html::escape_html(&entry.actor),
+                entry.action_label(lang),
+                entry.entity_label(lang),
+                html::escape_html(&entry.entity_id),
+                render_audit_state(&entry.before_state),
+                render_audit_state(&entry.after_state),
+                html::escape_html(&entry.ip_address)
+            )
+        })
+        .collect();
+
+    let page = filter.page();
+    let total_pages = (total + AUDIT_PAGE_SIZE - 1) / AUDIT_PAGE_SIZE;
+    let mut pagination = String::new();
+    if page > 1 {
+        pagination.push_str(&format!(
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/audit' hx-include='#audit-filters' hx-vals='{{\"page\": {}}}' hx-target='#dashboard-container'>{}</button>",
+            page - 1,
+            i18n::t(lang, "list.previous_page")
+        ));
+    }
+    if page < total_pages {
+        pagination.push_str(&format!(
+            "<button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded' hx-get='/admin/audit' hx-include='#audit-filters' hx-vals='{{\"page\": {}}}' hx-target='#dashboard-container'>{}</button>",
+            page + 1,
+            i18n::t(lang, "list.next_page")
+        ));
+    }
+
+    Ok(format!(
+        "<div class='w-3/4 mx-auto mt-10'>
+            <h2 class='text-2xl font-bold mb-4'>{}</h2>
+            <form id='audit-filters' action='/admin/audit/export' method='get' class='flex flex-wrap gap-2 mb-4 items-center'
+                hx-get='/admin/audit' hx-target='#dashboard-container' hx-trigger='change'>
+                {}
+                {}
+                {}
+                <input type='date' name='from' value='{}' class='border rounded py-2 px-3' title='{}'>
+                <input type='date' name='to' value='{}' class='border rounded py-2 px-3' title='{}'>
+                <button type='submit' class='bg-green-500 hover:bg-green-700 text-white font-bold py-2 px-4 rounded'>{}</button>
+            </form>
+            <p class='text-sm text-gray-600 mb-2'>{}</p>
+            <table class='table-auto w-full text-sm'>
+                <thead><tr>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>{}</th>
+                    <th class='px-4 py-2 text-left'>IP</th>
+                </tr></thead>
+                <tbody>{}</tbody>
+            </table>
+            <div class='flex justify-center items-center mt-4 mb-4 space-x-2'>{}</div>
+        </div>",
+        i18n::t(lang, "admin.audit.heading"),
+        audit_select("actor", i18n::t(lang, "admin.audit.all_users"), &actors, filter.actor()),
+        audit_select(
+            "action",
+            i18n::t(lang, "admin.audit.all_actions"),
+            &translated(&audit::ACTIONS),
+            filter.action()
+        ),
+        audit_select(
+            "entity",
+            i18n::t(lang, "admin.audit.all_entities"),
+            &translated(&audit::ENTITIES),
+            filter.entity()
+        ),
+        html::escape_html(filter.from().unwrap_or("")),
+        i18n::t(lang, "admin.audit.from"),
+        html::escape_html(filter.to().unwrap_or("")),
+        i18n::t(lang, "admin.audit.to"),
+        i18n::t(lang, "admin.audit.export"),
+        i18n::t(lang, "admin.audit.count").replace("{count}", &total.to_string()),
+        i18n::t(lang, "admin.audit.time"),
+        i18n::t(lang, "admin.audit.user"),
+        i18n::t(lang, "admin.audit.action"),
+        i18n::t(lang, "admin.audit.entity"),
+        i18n::t(lang, "admin.audit.record"),
+        i18n::t(lang, "admin.audit.before"),
+        i18n::t(lang, "admin.audit.after"),
+        rows,
+        pagination
+    ))
+}
+
+pub async fn admin_audit_handler(
+    req: HttpRequest,
+    filter: web::Query<AuditFilter>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_audit_log(Lang::preferred(&req), &filter)?))
+}
+
+/// Every audit entry matching the filters, as a CSV download.
+pub async fn export_audit_handler(
+    req: HttpRequest,
+    filter: web::Query<AuditFilter>,
+    session: Session,
+) -> Result<HttpResponse, actix_web::Error> {
+    require_admin(&req, &session)?;
+
+    let (entries, _) = db::get_audit_entries(&filter, None)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let filename = format!(
+        "islem-kayitlari-{}.csv",
+        chrono::Local::now().format("%Y-%m-%d")
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(docs::content_disposition(
+            DispositionType::Attachment,
+            &filename,
+        ))
+        .body(audit::to_csv(&entries)))
+}
+
+pub async fn admin_documents_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let lang = Lang::preferred(&req);
+    let documents = db::get_documents(None, None)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let document_rows = documents
+        .iter()
+        .map(|document| {
+            format!(
+                "<tr id=\"document-{}\" class=\"bg-white border-b dark:bg-gray-800 dark:border-gray-700\">
+                <th scope=\"row\" class=\"px-6 py-4 font-medium text-gray-900 whitespace-nowrap dark:text-white\">
+                    <a href=\"/dokumanlar/{}\" class=\"underline\">{}</a>
+                </th>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">
+                    <button hx-get='/admin/document/edit/form/{}' hx-target='#dashboard-container' class='text-blue-500 mr-2'>{}</button>
+                    <button hx-post='/admin/documents/delete/{}' hx-confirm='{}' hx-swap='outerHTML' hx-target='closest tr' class='text-red-500'>{}</button>
+                </td>
+                </tr>",
+                document.id,
+                html::escape_html(&document.filename),
+                html::escape_html(&document.title),
+                html::escape_html(&document.category),
+                html::escape_html(&document.academic_year),
+                html::escape_html(&document.version),
+                html::escape_html(&document.upload_date),
+                document.id,
+                i18n::t(lang, "admin.edit"),
+                document.id,
+                i18n::t(lang, "admin.document.delete_confirm"),
+                i18n::t(lang, "admin.delete")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("");
+
+    let table = format!(
+        "
+        <div class='flex justify-center'>
+            <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/documents/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>
+        </div>
+        <div class=\"w-3/4 mx-auto mt-10 justify-center items-center text-center\">
+            <div class=\"relative overflow-x-auto\">
+                <table class=\"w-full text-sm text-center rtl:text-right text-gray-500 dark:text-gray-400\">
+                    <thead class=\"text-xs text-gray-700 uppercase bg-gray-50 dark:bg-gray-700 dark:text-gray-400\">
+                        <tr>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {}
+                    </tbody>
+                </table>
+            </div>
+        </div>",
+        i18n::t(lang, "admin.add_document"),
+        i18n::t(lang, "admin.column.title"),
+        i18n::t(lang, "admin.column.category"),
+        i18n::t(lang, "admin.column.academic_year"),
+        i18n::t(lang, "admin.column.version"),
+        i18n::t(lang, "admin.column.uploaded"),
+        i18n::t(lang, "admin.column.actions"),
+        document_rows
+    );
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(table))
+}
+
+async fn document_category_options() -> Result<String, actix_web::Error> {
+    let categories = db::get_document_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(categories
+        .iter()
+        .map(|category| format!("<option value='{}'></option>", html::escape_html(category)))
+        .collect())
+}
+
+pub async fn add_document_form_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let path: PathBuf = "../public/pages/add_document.html".parse().unwrap();
+    let mut form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
+    form = form.replace("{{categories}}", &document_category_options().await?);
+    Ok(HttpResponse::Ok().content_type("text/html").body(form))
+}
+
+pub async fn edit_document_form_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match db::get_document(id.into_inner()) {
+        Ok(document) => {
+            let lang = Lang::preferred(&req);
+            let path: PathBuf = "../public/pages/edit_document.html".parse().unwrap();
+            let mut form = i18n::translate(&tokio::fs::read_to_string(path).await?, lang);
+            form = form.replace("{{categories}}", &document_category_options().await?);
+            form = form.replace("{{document.id}}", &document.id.to_string());
+            form = form.replace("{{document.title}}", &html::escape_html(&document.title));
+            form = form.replace("{{document.category}}", &html::escape_html(&document.category));
+            form = form.replace("{{document.academic_year}}", &html::escape_html(&document.academic_year));
+            form = form.replace("{{document.version}}", &html::escape_html(&document.version));
+            form = form.replace("{{document.original_name}}", &html::escape_html(&document.original_name));
+            form = form.replace("{{document.upload_date}}", &html::escape_html(&document.upload_date));
+            form = form.replace("{{document.change_note}}", &html::escape_html(&document.change_note));
+            let versions = db::get_document_versions(document.id)
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+            form = form.replace(
+                "{{versions}}",
+                &docs::render_version_history(lang, &versions),
+            );
+            Ok(HttpResponse::Ok().content_type("text/html").body(form))
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+async fn store_document_file(
+    lang: Lang,
+    original_name: &str,
+    bytes: &Bytes,
+) -> Result<String, HttpResponse> {
+    let extension = docs::document_extension(original_name).ok_or_else(|| {
+        HttpResponse::UnsupportedMediaType()
+            .content_type("text/html")
+            .body(format!(
+                "<p class='text-center text-red-500 mt-4'>{}</p>",
+                i18n::t(lang, "admin.document.unsupported")
+            ))
+    })?;
+
+    docs::save_document_file(&extension, bytes)
+        .await
+        .map_err(|_| HttpResponse::InternalServerError().finish())
+}
+
+pub async fn add_document_handler(
+    req: HttpRequest,
+    payload: Multipart,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let lang = Lang::preferred(&req);
+    let upload = upload::read_form_upload(payload, None).await?;
+
+    let (original_name, bytes) = match &upload.file {
+        Some(file) => file,
+        None => {
+            return Ok(HttpResponse::BadRequest()
+                .body(i18n::t(lang, "admin.document.no_file").to_string()))
+        }
+    };
+    let filename = match store_document_file(lang, original_name, bytes).await {
+        Ok(filename) => filename,
+        Err(response) => return Ok(response),
+    };
+
+    let document = docs::Document {
+        id: 0,
+        title: upload.field("title"),
+        category: upload.field("category"),
+        academic_year: upload.field("academic_year"),
+        version: upload.field("version"),
+        filename,
+        original_name: original_name.clone(),
+        upload_date: chrono::Local::now().format("%d-%m-%Y").to_string(),
+        change_note: String::new(),
+    };
+
+    match db::add_document(&document) {
+        Ok(id) => {
+            let _ = docs::index_document_text(id, &document.filename);
+            audit::created(&req, "document", id, &db::get_document(id).ok());
+            admin_documents_handler(req).await
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+pub async fn edit_document_handler(
+    req: HttpRequest,
+    payload: Multipart,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let upload = upload::read_form_upload(payload, None).await?;
+
+    let id: i32 = upload
+        .field("id")
+        .parse()
+        .map_err(|_| actix_web::error::ErrorBadRequest("Invalid id parameter"))?;
+    let mut document =
+        db::get_document(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    let before = document.clone();
+
+    let file_replaced = upload.file.is_some();
+    let mut previous = None;
+    if let Some((original_name, bytes)) = &upload.file {
+        let lang = Lang::preferred(&req);
+        let filename = match store_document_file(lang, original_name, bytes).await {
+            Ok(filename) => filename,
+            Err(response) => return Ok(response),
+        };
+        // The previous file stays on disk so its URL keeps working.
+        previous = Some(docs::DocumentVersion::from_document(&document));
+
+        document.filename = filename;
+        document.original_name = original_name.clone();
+        document.upload_date = chrono::Local::now().format("%d-%m-%Y").to_string();
+    }
+
+    document.title = upload.field("title");
+    document.category = upload.field("category");
+    document.academic_year = upload.field("academic_year");
+    document.version = upload.field("version");
+    document.change_note = upload.field("change_note");
+
+    match db::edit_document(&document, previous.as_ref()) {
+        Ok(_) => {
+            audit::updated(&req, "document", id, &before, &document);
+            if file_replaced {
+                let _ = docs::index_document_text(document.id, &document.filename);
+                if upload.field("announce") == "on" {
+                    announce_document_version(&req, &document, &session)?;
+                }
+            }
+            admin_documents_handler(req).await
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+fn announce_document_version(
+    req: &HttpRequest,
+    document: &docs::Document,
+    session: &Session,
+) -> Result<(), Error> {
+    let author = signed_in_account(session)
+        .map(|account| account.username)
+        .unwrap_or_default();
+    // Announcement titles and content are rendered as HTML, so the
+    // editor-supplied document fields are escaped here.
+    let document_title = html::escape_html(&document.title);
+    let title = format!("{} güncellendi", document_title);
+    let mut content = format!(
+        "<p>{} dokümanının {} sürümü yayımlandı.</p>",
+        document_title,
+        html::escape_html(&document.version)
+    );
+    if !document.change_note.is_empty() {
+        content.push_str(&format!(
+            "<p>Değişenler: {}</p>",
+            html::escape_html(&document.change_note)
+        ));
+    }
+    content.push_str(&format!(
+        "<p><a href='/dokumanlar/{}' class='text-blue-600 underline'>Dokümanı indir</a></p>",
+        html::escape_html(&document.filename)
+    ));
+
+    let id = db::add_announcement(
+        "/assets/image/duyuru_default.png",
+        &title,
+        &content,
+        &document.upload_date,
+        &author,
+        "",
+        &Overrides::default(),
+    )
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::created(req, "announcement", id, &db::get_announcement(id).ok());
+    notify::announcement_published(id);
+
+    Ok(())
+}
+
+pub async fn delete_document_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let document =
+        db::get_document(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    let versions = db::get_document_versions(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    db::delete_document(id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "document", id, &document);
+    let _ = fs::remove_file(Path::new(docs::DOCS_DIR).join(document.filename));
+    for version in versions {
+        let _ = fs::remove_file(Path::new(docs::DOCS_DIR).join(version.filename));
+    }
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(""))
+}
+
+pub async fn admin_staff_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let lang = Lang::preferred(&req);
+    let staff =
+        db::get_staff().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let staff_rows = staff
+        .iter()
+        .map(|member| {
             format!(
-                "<tr class=\"bg-white border-b dark:bg-gray-800 dark:border-gray-700\">
+                "<tr id=\"staff-{}\" class=\"bg-white border-b dark:bg-gray-800 dark:border-gray-700\">
+                <td class=\"px-6 py-4\"><img src=\"{}\" alt=\"{}\" class=\"w-12 h-12 rounded-full mx-auto\"></td>
                 <th scope=\"row\" class=\"px-6 py-4 font-medium text-gray-900 whitespace-nowrap dark:text-white\">{}</th>
                 <td class=\"px-6 py-4\">{}</td>
                 <td class=\"px-6 py-4\">{}</td>
                 <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
                 <td class=\"px-6 py-4\">
-                    <button hx-delete='/admin/messages/delete/{}' hx-confirm='Are you sure you want to delete this message?' hx-swap='outerHTML' hx-target='closest tr' class='text-red-500'>Delete</button>
+                    <button hx-get='/admin/staff/edit/form/{}' hx-target='#dashboard-container' class='text-blue-500 mr-2'>{}</button>
+                    <button hx-post='/admin/staff/delete/{}' hx-confirm='{}' hx-swap='outerHTML' hx-target='closest tr' class='text-red-500'>{}</button>
                 </td>
                 </tr>",
-                message.1, message.2, message.3, message.4, message.0
+                member.id,
+                html::escape_html(&member.photo),
+                html::escape_html(&member.name),
+                html::escape_html(&member.full_name()),
+                html::escape_html(&member.program),
+                html::escape_html(&member.office),
+                html::escape_html(&member.email),
+                member.display_order,
+                member.id,
+                i18n::t(lang, "admin.edit"),
+                member.id,
+                i18n::t(lang, "admin.staff.delete_confirm"),
+                i18n::t(lang, "admin.delete")
             )
         })
         .collect::<Vec<String>>()
@@ -974,16 +3858,21 @@ pub async fn get_messages_handler() -> Result<HttpResponse, actix_web::Error> {
 
     let table = format!(
         "
-        <div class=\"w-1/2 mx-auto mt-10 justify-center items-center text-center\">
+        <div class='flex justify-center'>
+            <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/staff/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>
+        </div>
+        <div class=\"w-3/4 mx-auto mt-10 justify-center items-center text-center\">
             <div class=\"relative overflow-x-auto\">
-                <table id=\"message-table\" class=\"w-full text-sm text-center rtl:text-right text-gray-500 dark:text-gray-400\">
+                <table class=\"w-full text-sm text-center rtl:text-right text-gray-500 dark:text-gray-400\">
                     <thead class=\"text-xs text-gray-700 uppercase bg-gray-50 dark:bg-gray-700 dark:text-gray-400\">
                         <tr>
-                            <th scope=\"col\" class=\"px-6 py-3\">İsim</th>
-                            <th scope=\"col\" class=\"px-6 py-3\">Email</th>
-                            <th scope=\"col\" class=\"px-6 py-3\">Mesaj</th>
-                            <th scope=\"col\" class=\"px-6 py-3\">Ip</th>
-                            <th scope=\"col\" class=\"px-6 py-3\">İşlem</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
                         </tr>
                     </thead>
                     <tbody>
@@ -992,36 +3881,398 @@ pub async fn get_messages_handler() -> Result<HttpResponse, actix_web::Error> {
                 </table>
             </div>
         </div>",
-        message_rows
+        i18n::t(lang, "admin.add_staff"),
+        i18n::t(lang, "admin.column.photo"),
+        i18n::t(lang, "admin.column.name"),
+        i18n::t(lang, "admin.column.program"),
+        i18n::t(lang, "staff.office"),
+        i18n::t(lang, "admin.column.email"),
+        i18n::t(lang, "admin.column.order"),
+        i18n::t(lang, "admin.column.actions"),
+        staff_rows
     );
 
     Ok(HttpResponse::Ok().content_type("text/html").body(table))
 }
 
-pub async fn delete_message_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
-    let id: i32 = req
-        .match_info()
-        .get("id")
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing id parameter"))?
+async fn staff_program_options() -> Result<String, actix_web::Error> {
+    let programs = db::get_staff_programs()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(programs
+        .iter()
+        .map(|program| format!("<option value='{}'></option>", html::escape_html(program)))
+        .collect())
+}
+
+pub async fn add_staff_form_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let path: PathBuf = "../public/pages/add_staff.html".parse().unwrap();
+    let mut form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
+    form = form.replace("{{programs}}", &staff_program_options().await?);
+    Ok(HttpResponse::Ok().content_type("text/html").body(form))
+}
+
+pub async fn edit_staff_form_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match db::get_staff_member(id.into_inner()) {
+        Ok(member) => {
+            let path: PathBuf = "../public/pages/edit_staff.html".parse().unwrap();
+            let mut form = i18n::translate(
+                &tokio::fs::read_to_string(path).await?,
+                Lang::preferred(&req),
+            );
+            form = form.replace("{{programs}}", &staff_program_options().await?);
+            form = form.replace("{{staff.id}}", &member.id.to_string());
+            form = form.replace("{{staff.name}}", &html::escape_html(&member.name));
+            form = form.replace("{{staff.title}}", &html::escape_html(&member.title));
+            form = form.replace("{{staff.program}}", &html::escape_html(&member.program));
+            form = form.replace("{{staff.office}}", &html::escape_html(&member.office));
+            form = form.replace("{{staff.phone}}", &html::escape_html(&member.phone));
+            form = form.replace("{{staff.email}}", &html::escape_html(&member.email));
+            form = form.replace("{{staff.photo}}", &html::escape_html(&member.photo));
+            form = form.replace("{{staff.office_hours}}", &html::escape_html(&member.office_hours));
+            form = form.replace("{{staff.display_order}}", &member.display_order.to_string());
+            Ok(HttpResponse::Ok().content_type("text/html").body(form))
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+/// Runs an uploaded staff photo through the image pipeline and returns its public URL.
+async fn store_staff_photo(lang: Lang, bytes: &Bytes) -> Result<String, HttpResponse> {
+    let bytes = bytes.clone();
+    let saved = web::block(move || images::save_staff_photo(&bytes))
+        .await
+        .map_err(|_| HttpResponse::InternalServerError().finish())?;
+
+    match saved {
+        Ok(name) => Ok(format!("{}/{}", images::STAFF_PHOTO_URL, name)),
+        Err(image::ImageError::Unsupported(_)) | Err(image::ImageError::Decoding(_)) => {
+            Err(HttpResponse::UnsupportedMediaType()
+                .content_type("text/html")
+                .body(format!(
+                    "<p class='text-center text-red-500 mt-4'>{}</p>",
+                    i18n::t(lang, "admin.image.unsupported")
+                )))
+        }
+        Err(_) => Err(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+fn staff_from_upload(upload: &FormUpload, id: i32, photo: String) -> Staff {
+    Staff {
+        id,
+        name: upload.field("name"),
+        title: upload.field("title"),
+        program: upload.field("program"),
+        office: upload.field("office"),
+        phone: upload.field("phone"),
+        email: upload.field("email"),
+        photo,
+        office_hours: upload.field("office_hours"),
+        display_order: upload.field("display_order").parse().unwrap_or(0),
+    }
+}
+
+pub async fn add_staff_handler(
+    req: HttpRequest,
+    payload: Multipart,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let upload = upload::read_form_upload(payload, None).await?;
+
+    let photo = match &upload.file {
+        Some((_, bytes)) => match store_staff_photo(Lang::preferred(&req), bytes).await {
+            Ok(photo) => photo,
+            Err(response) => return Ok(response),
+        },
+        None => personel::DEFAULT_PHOTO.to_string(),
+    };
+
+    let mut staff = staff_from_upload(&upload, 0, photo);
+    match db::add_staff(&staff) {
+        Ok(id) => {
+            staff.id = id;
+            audit::created(&req, "staff", id, &staff);
+            admin_staff_handler(req).await
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+pub async fn edit_staff_handler(
+    req: HttpRequest,
+    payload: Multipart,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let upload = upload::read_form_upload(payload, None).await?;
+
+    let id: i32 = upload
+        .field("id")
         .parse()
         .map_err(|_| actix_web::error::ErrorBadRequest("Invalid id parameter"))?;
+    let current =
+        db::get_staff_member(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    let photo = match &upload.file {
+        Some((_, bytes)) => match store_staff_photo(Lang::preferred(&req), bytes).await {
+            Ok(photo) => {
+                images::delete_staff_photo(&current.photo);
+                photo
+            }
+            Err(response) => return Ok(response),
+        },
+        None => current.photo.clone(),
+    };
+
+    let staff = staff_from_upload(&upload, id, photo);
+    match db::edit_staff(&staff) {
+        Ok(_) => {
+            audit::updated(&req, "staff", id, &current, &staff);
+            admin_staff_handler(req).await
+        }
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+pub async fn delete_staff_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let member =
+        db::get_staff_member(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    db::delete_staff(id).map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "staff", id, &member);
+    images::delete_staff_photo(&member.photo);
 
-    db::delete_message(id)
+    Ok(HttpResponse::Ok().content_type("text/html").body(""))
+}
+
+pub async fn admin_events_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let lang = Lang::preferred(&req);
+    let mut events = db::get_events(None, None, None)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    events.reverse();
 
-    Ok(HttpResponse::Ok().finish())
+    let event_rows = events
+        .iter()
+        .map(|event| {
+            format!(
+                "<tr id=\"event-{}\" class=\"bg-white border-b dark:bg-gray-800 dark:border-gray-700\">
+                <th scope=\"row\" class=\"px-6 py-4 font-medium text-gray-900 dark:text-white\">{}</th>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">{}</td>
+                <td class=\"px-6 py-4\">
+                    <button hx-get='/admin/events/edit/form/{}' hx-target='#dashboard-container' class='text-blue-500 mr-2'>{}</button>
+                    <button hx-post='/admin/events/delete/{}' hx-confirm='{}' hx-swap='outerHTML' hx-target='closest tr' class='text-red-500'>{}</button>
+                </td>
+                </tr>",
+                event.id,
+                html::escape_html(&event.title),
+                html::escape_html(&event.category),
+                event.when(lang),
+                html::escape_html(&event.location),
+                event.id,
+                i18n::t(lang, "admin.edit"),
+                event.id,
+                i18n::t(lang, "admin.event.delete_confirm"),
+                i18n::t(lang, "admin.delete")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("");
+
+    let table = format!(
+        "
+        <div class='flex justify-center'>
+            <button class='bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 mt-4 rounded' hx-get='/admin/events/add/form' hx-swap='innerHTML' hx-target='#dashboard-container'>{}</button>
+        </div>
+        <div class=\"w-3/4 mx-auto mt-10 justify-center items-center text-center\">
+            <div class=\"relative overflow-x-auto\">
+                <table class=\"w-full text-sm text-center rtl:text-right text-gray-500 dark:text-gray-400\">
+                    <thead class=\"text-xs text-gray-700 uppercase bg-gray-50 dark:bg-gray-700 dark:text-gray-400\">
+                        <tr>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                            <th scope=\"col\" class=\"px-6 py-3\">{}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {}
+                    </tbody>
+                </table>
+            </div>
+        </div>",
+        i18n::t(lang, "admin.add_event"),
+        i18n::t(lang, "admin.column.title"),
+        i18n::t(lang, "admin.column.category"),
+        i18n::t(lang, "admin.column.date"),
+        i18n::t(lang, "admin.column.location"),
+        i18n::t(lang, "admin.column.actions"),
+        event_rows
+    );
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(table))
+}
+
+async fn event_category_options() -> Result<String, actix_web::Error> {
+    let categories = db::get_event_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(categories
+        .iter()
+        .map(|category| format!("<option value='{}'></option>", html::escape_html(category)))
+        .collect())
+}
+
+fn event_form_error(message: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(("HX-Retarget", "#event-form-error"))
+        .insert_header(("HX-Reswap", "innerHTML"))
+        .body(format!(
+            "<p class='mb-4 text-sm text-red-700'>{}</p>",
+            html::escape_html(message)
+        ))
+}
+
+pub async fn add_event_form_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let path: PathBuf = "../public/pages/add_event.html".parse().unwrap();
+    let mut form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
+    form = form.replace("{{categories}}", &event_category_options().await?);
+    Ok(HttpResponse::Ok().content_type("text/html").body(form))
+}
+
+pub async fn edit_event_form_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let event = db::get_event(id.into_inner())
+        .map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    let path: PathBuf = "../public/pages/edit_event.html".parse().unwrap();
+    let mut form = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
+    form = form.replace("{{categories}}", &event_category_options().await?);
+    form = form.replace("{{event.id}}", &event.id.to_string());
+    form = form.replace("{{event.title}}", &html::escape_html(&event.title));
+    form = form.replace("{{event.category}}", &html::escape_html(&event.category));
+    form = form.replace("{{event.location}}", &html::escape_html(&event.location));
+    form = form.replace(
+        "{{event.description}}",
+        &html::escape_html(&event.description),
+    );
+
+    // All-day events keep only their dates; the time fields stay empty.
+    let format = |time: Option<chrono::NaiveDateTime>, pattern: &str| match time {
+        Some(_) if event.all_day && pattern == "%H:%M" => String::new(),
+        Some(time) => time.format(pattern).to_string(),
+        None => String::new(),
+    };
+    form = form.replace(
+        "{{event.start_date}}",
+        &format(Some(event.start()), "%Y-%m-%d"),
+    );
+    form = form.replace(
+        "{{event.start_time}}",
+        &format(Some(event.start()), "%H:%M"),
+    );
+    form = form.replace("{{event.end_date}}", &format(event.end(), "%Y-%m-%d"));
+    form = form.replace("{{event.end_time}}", &format(event.end(), "%H:%M"));
+    form = form.replace(
+        "{{all_day_checked}}",
+        if event.all_day { "checked" } else { "" },
+    );
+    Ok(HttpResponse::Ok().content_type("text/html").body(form))
+}
+
+pub async fn add_event_handler(
+    req: HttpRequest,
+    form: web::Form<EventForm>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let mut event = match form.to_event(0) {
+        Ok(event) => event,
+        Err(message) => return Ok(event_form_error(&message)),
+    };
+
+    event.id = db::add_event(&event)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::created(&req, "event", event.id, &event);
+    admin_events_handler(req).await
+}
+
+pub async fn edit_event_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    form: web::Form<EventForm>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let current = db::get_event(id.into_inner())
+        .map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+    let event = match form.to_event(current.id) {
+        Ok(event) => event,
+        Err(message) => return Ok(event_form_error(&message)),
+    };
+
+    db::edit_event(&event)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::updated(&req, "event", event.id, &current, &event);
+    admin_events_handler(req).await
 }
 
+pub async fn delete_event_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let id = id.into_inner();
+    let event = db::get_event(id).map_err(|e| actix_web::error::ErrorNotFound(e.to_string()))?;
+
+    db::delete_event(id).map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    audit::deleted(&req, "event", id, &event);
+
+    Ok(HttpResponse::Ok().content_type("text/html").body(""))
+}
 
-pub async fn admin_gallery_handler() -> Result<HttpResponse, actix_web::Error> {
+pub async fn admin_gallery_handler(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
     let path: PathBuf = "../public/pages/gallery.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
+    let content = i18n::translate(
+        &tokio::fs::read_to_string(path).await?,
+        Lang::preferred(&req),
+    );
     Ok(HttpResponse::Ok().content_type("text/html").body(content))
 }
 
 async fn get_image_files() -> Result<Vec<String>, std::io::Error> {
-    let image_folder = "../public/assets/slider";
-    let mut entries = fs::read_dir(image_folder)?
+    let mut entries = fs::read_dir(images::SLIDER_DIR)?
         .map(|res| res.map(|e| e.path()))
         .collect::<Result<Vec<_>, std::io::Error>>()?;
 
@@ -1061,14 +4312,19 @@ pub async fn admin_image_handler(
 }
 
 pub async fn delete_image_handler(
+    req: HttpRequest,
     info: web::Path<(String,)>,
     web::Query(pagination): web::Query<Pagination>,
+    session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
     let image_name = &info.0;
-    let image_path = format!("../public/assets/slider/{}", image_name);
+    safe_path::resolve(images::SLIDER_DIR, image_name).map_err(safe_path::to_http_error)?;
 
-    match fs::remove_file(&image_path) {
+    match images::delete_slide(image_name) {
         Ok(_) => {
+            audit::deleted(&req, "image", image_name, image_name);
             let image_files = get_image_files().await?;
             let paginated_images = paginate(image_files, pagination.clone());
 
@@ -1087,26 +4343,104 @@ pub async fn count_images_handler() -> Result<HttpResponse, Error> {
 }
 
 pub async fn admin_upload_handler(
+    req: HttpRequest,
     mut payload: Multipart,
     web::Query(pagination): web::Query<Pagination>,
+    session: Session,
 ) -> Result<HttpResponse, Error> {
+    require_login(&session)?;
+
+    let mut uploads: Vec<Bytes> = Vec::new();
+    let mut focal_x: Option<f32> = None;
+    let mut focal_y: Option<f32> = None;
+
     while let Ok(Some(mut field)) = payload.try_next().await {
+        let name = field
+            .content_disposition()
+            .get_name()
+            .unwrap_or("")
+            .to_string();
         let mut bytes = BytesMut::new();
 
         while let Some(chunk) = field.next().await {
-            let data = chunk.unwrap();
+            let data = chunk?;
             bytes.extend_from_slice(&data);
         }
 
-        let image_data = bytes.freeze();
-        let img = image::load_from_memory(&image_data).unwrap();
-        let resized = img.resize_exact(1280, 720, image::imageops::FilterType::Lanczos3);
-        let image_path = format!("../public/assets/slider/{}.webp", Uuid::new_v4().to_string());
-        resized.save_with_format(&image_path, image::ImageFormat::WebP).unwrap();
+        match name.as_str() {
+            "focal_x" => {
+                focal_x = std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|v| v.trim().parse().ok())
+            }
+            "focal_y" => {
+                focal_y = std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|v| v.trim().parse().ok())
+            }
+            _ if !bytes.is_empty() => uploads.push(bytes.freeze()),
+            _ => (),
+        }
     }
 
-    let image_files = get_image_files().await?;
-    let paginated_images = paginate(image_files, pagination.clone());
+    // Only the original is stored here; resizing it into the slide widths
+    // is slow and runs as a background job.
+    let focal = images::FocalPoint::from_percent(focal_x, focal_y);
+    let stored = web::block(move || {
+        let mut originals = Vec::new();
+        for bytes in &uploads {
+            match images::store_slide_original(bytes) {
+                Ok(original) => originals.push(original),
+                Err(e) => {
+                    // Nothing is queued unless every file in the upload is usable.
+                    for original in &originals {
+                        let _ = images::delete_slide_original(original);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(originals)
+    })
+    .await?;
+
+    match stored {
+        Ok(originals) => {
+            for original in originals {
+                let job = images::SlideJob {
+                    original: original.clone(),
+                    focal,
+                };
+                jobs::enqueue(jobs::PROCESS_SLIDE, &job)
+                    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+                // Logged under the slide's name, which deleting it records too.
+                audit::record(
+                    &req,
+                    "upload",
+                    "image",
+                    images::slide_name(&original),
+                    None,
+                    Some(audit::summary(&original)),
+                );
+            }
+            let image_files = get_image_files().await?;
+            let paginated_images = paginate(image_files, pagination.clone());
 
-    Ok(HttpResponse::Ok().json(paginated_images))
+            // 202: the new slides appear in the list once they are processed.
+            Ok(HttpResponse::Accepted().json(paginated_images))
+        }
+        Err(image::ImageError::Unsupported(_)) => Ok(HttpResponse::UnsupportedMediaType().json(
+            serde_json::json!({
+                "error": i18n::t(Lang::preferred(&req), "admin.image.unsupported")
+            }),
+        )),
+        Err(image::ImageError::Decoding(_)) => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": i18n::t(Lang::preferred(&req), "admin.image.unreadable")
+            })))
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": e.to_string()
+        }))),
+    }
 }
@@ -1,23 +1,37 @@
 use crate::src::db;
-use std::path::PathBuf;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use crate::src::seo::{Meta, Post};
 use actix_web::{web::Json, web::Query ,HttpRequest, HttpResponse, Responder, Result};
 use serde::Serialize;
 use serde_derive::Deserialize;
-
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/duyurular.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+use utoipa::ToSchema;
+
+pub async fn handler(req: HttpRequest, pagination: Query<Pagination>) -> Result<HttpResponse> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let lang = Lang::of(&req);
+    let mut content = layout::template(lang, "duyurular.html").await?;
+    content = content.replace("{{announcements}}", &render_list(lang, page, false)?);
+    layout::page(&req, i18n::t(lang, "title.announcements"), &content).await
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 pub struct Announcement {
     pub id: i32,
     pub image: String,
     pub title: String,
+    /// HTML, as produced by the dashboard editor.
     pub content: String,
+    /// Publication day, `dd-mm-yyyy`.
     pub date: String,
     pub author: String,
+    /// What subscribers filter on; empty for announcements meant for everybody.
+    pub category: String,
+    /// Overrides the title in search results and link previews; empty uses
+    /// the title.
+    pub seo_title: String,
+    /// Meta description; empty uses the start of the content.
+    pub seo_description: String,
 }
 
 #[derive(Deserialize)]
@@ -26,18 +40,18 @@ pub struct Pagination {
     page_size: Option<i32>,
 }
 
-pub async fn announcements_handler(
-    req: HttpRequest,
-) -> Result<HttpResponse> {
-    let page: i32 = req.match_info().get("page").unwrap_or("1").parse().unwrap_or(1);
-    let from_main_page: bool = req.query_string().contains("main_page=true");
+/// A page of announcement cards with links to the neighbouring pages, or the
+/// latest three and a link to the rest on the main page.
+pub fn render_list(lang: Lang, page: i32, from_main_page: bool) -> Result<String> {
     let announcements_per_page = if from_main_page { 3 } else { 6 };
     let (announcements, total_announcements) = db::get_announcements(page, announcements_per_page)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     let mut response = String::new();
     response.push_str("<div class='grid gap-6 lg:grid-cols-3 xl:gap-x-12'>");
-    for announcement in announcements {
+    for mut announcement in announcements {
+        i18n::localize("announcement", announcement.id, lang, &mut announcement.title, &mut announcement.content)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
         response.push_str(&format!(
             "<div class='mb-6 lg:mb-0'>
             <div
@@ -55,12 +69,14 @@ pub async fn announcements_handler(
                     <p class='mb-4 text-neutral-500 dark:text-neutral-300'>
                         <small><u>{}</u><br /><a>{}</a></small>
                     </p>
-                    <button hx-get='/announcement/{}' hx-target='#main-container' hx-push-url='#duyuru'
-                        class='inline-flex items-center justify-center px-4 py-2 text-base font-medium text-white bg-blue-600 border border-transparent rounded-md shadow-sm hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>Oku</button>
+                    <a {}
+                        class='inline-flex items-center justify-center px-4 py-2 text-base font-medium text-white bg-blue-600 border border-transparent rounded-md shadow-sm hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>{}</a>
                 </div>
             </div>
         </div>",
-            announcement.image, announcement.title, announcement.date, announcement.author, announcement.id
+            announcement.image, announcement.title, announcement.date, announcement.author,
+            layout::link(lang, &format!("/announcement/{}", announcement.id)),
+            i18n::t(lang, "list.read")
         ));
     }
 
@@ -68,9 +84,10 @@ pub async fn announcements_handler(
 
     if from_main_page {
         response.push_str(&format!(
-            "<a id='link-duyurular' href='#duyurular' hx-get='/duyurular'
-            hx-target='#main-container' hx-trigger='click'
-            class='inline-flex items-center justify-center px-4 mt-6 py-2 text-base font-medium text-white bg-green-600 border border-transparent rounded-md shadow-sm hover:bg-green-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>Tüm Duyurular</a>"
+            "<a {}
+            class='inline-flex items-center justify-center px-4 mt-6 py-2 text-base font-medium text-white bg-green-600 border border-transparent rounded-md shadow-sm hover:bg-green-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>{}</a>",
+            layout::link(lang, "/duyurular"),
+            i18n::t(lang, "list.all_announcements")
         ));
     } 
     
@@ -80,26 +97,32 @@ pub async fn announcements_handler(
         if page > 1 {
             let prev_page = page - 1;
             response.push_str(&format!(
-                "<button class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' hx-get='/announcements/{}' hx-boost='true' hx-target='#announcement-container'>
+                "<a class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' href='{1}?page={0}' hx-get='{2}/{0}' hx-target='#announcement-container' hx-push-url='{1}?page={0}'>
                 <svg class='w-5 h-5 me-2 rtl:rotate-180' aria-hidden='true' xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 14 10'>
                 <path stroke='currentColor' stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M13 5H1m0 0 4 4M1 5l4-4'/>
                 </svg>
-                Önceki Sayfa
-                </button>",
-                prev_page
+                {3}
+                </a>",
+                prev_page,
+                lang.path("/duyurular"),
+                lang.path("/announcements"),
+                i18n::t(lang, "list.previous_page")
             ));
         }
 
         if page * 6 < total_announcements {
             let next_page = page + 1;
             response.push_str(&format!(
-                "<button class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' hx-get='/announcements/{}' hx-boost='true' hx-target='#announcement-container'>
-                Sonraki Sayfa
+                "<a class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' href='{1}?page={0}' hx-get='{2}/{0}' hx-target='#announcement-container' hx-push-url='{1}?page={0}'>
+                {3}
                 <svg class='w-5 h-5 ms-2 rtl:rotate-180' aria-hidden='true' xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 14 10'>
                 <path stroke='currentColor' stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M1 5h12m0 0L9 1m4 4L9 9'/>
                 </svg>
-                </button>",
-                next_page
+                </a>",
+                next_page,
+                lang.path("/duyurular"),
+                lang.path("/announcements"),
+                i18n::t(lang, "list.next_page")
             ));
         }
 
@@ -107,18 +130,37 @@ pub async fn announcements_handler(
     }
     
     response.push_str("</div>");
-    Ok(HttpResponse::Ok().content_type("text/html").body(response))
+    Ok(response)
+}
+
+pub async fn announcements_handler(req: HttpRequest) -> Result<HttpResponse> {
+    let page: i32 = req.match_info().get("page").unwrap_or("1").parse().unwrap_or(1);
+    let from_main_page: bool = req.query_string().contains("main_page=true");
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_list(Lang::of(&req), page, from_main_page)?))
 }
 
 pub async fn announcement_detail_handler(req: HttpRequest) -> Result<HttpResponse> {
-    let id: i32 = req.match_info().get("id").unwrap().parse().unwrap();
-    let announcement = db::get_announcement(id)
+    let id: i32 = req
+        .match_info()
+        .get("id")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Announcement not found"))?;
+    let mut announcement = db::get_announcement(id).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            actix_web::error::ErrorNotFound("Announcement not found")
+        }
+        e => actix_web::error::ErrorInternalServerError(e.to_string()),
+    })?;
+    let lang = Lang::of(&req);
+    i18n::localize("announcement", id, lang, &mut announcement.title, &mut announcement.content)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     let html = format!(
         
         "
-        <a href='#duyurular' hx-get='/duyurular' hx-target='#main-container' hx-trigger='click'
+        <a {}
         class='py-4 px-5 text-gray-900 rounded max-w-xs md:bg-transparent flex items-center' aria-current='page'>
         <svg class='w-6 h-6 text-gray-800 dark:text-white' aria-hidden='true' xmlns='http://www.w3.org/2000/svg'
             fill='none' viewBox='0 0 8 14'>
@@ -172,8 +214,23 @@ pub async fn announcement_detail_handler(req: HttpRequest) -> Result<HttpRespons
             </div>
         </div>",
         
-        announcement.title, announcement.image, announcement.content, announcement.date, announcement.author
+        layout::link(lang, "/duyurular"), announcement.title, announcement.image, announcement.content, announcement.date, announcement.author
     );
 
-    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+    let meta = Meta::post(
+        lang,
+        &lang.path(&format!("/announcement/{}", announcement.id)),
+        "NewsArticle",
+        Post {
+            title: &announcement.title,
+            content: &announcement.content,
+            image: &announcement.image,
+            date: &announcement.date,
+            author: &announcement.author,
+            // The SEO fields describe the Turkish text.
+            seo_title: if lang == Lang::Tr { &announcement.seo_title } else { "" },
+            seo_description: if lang == Lang::Tr { &announcement.seo_description } else { "" },
+        },
+    );
+    layout::page_with_meta(&req, &meta, &html).await
 }
\ No newline at end of file
@@ -0,0 +1,1061 @@
+use crate::src::announcements::Announcement;
+use crate::src::api_token;
+use crate::src::articles::Article;
+use crate::src::audit;
+use crate::src::db;
+use crate::src::docs::{Document, DocumentVersion};
+use crate::src::notify;
+use crate::src::personel::Staff;
+use crate::src::seo::Overrides;
+use crate::src::upload;
+use actix_multipart::Multipart;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_derive::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_SIZE: i32 = 20;
+pub const MAX_PAGE_SIZE: i32 = 100;
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+const IMAGE_UPLOAD_DIR: &str = "../public/assets/image/upload";
+const DEFAULT_ANNOUNCEMENT_IMAGE: &str = "/assets/image/duyuru_default.png";
+const DEFAULT_ARTICLE_IMAGE: &str = "/assets/image/makale_default.png";
+
+/// Every API error is answered with the same body:
+/// `{"error": {"status": 404, "code": "not_found", "message": "..."}}`.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            code: "invalid_parameter",
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            code: "unauthorized",
+            message: message.into(),
+        }
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::FORBIDDEN,
+            code: "forbidden",
+            message: message.into(),
+        }
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            code: "payload_too_large",
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            code: "not_found",
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(e: impl fmt::Display) -> Self {
+        eprintln!("API error: {}", e);
+        ApiError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "internal_error",
+            message: String::from("Internal server error"),
+        }
+    }
+}
+
+/// Body of every error response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: ErrorDetail,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// Same as the HTTP status.
+    pub status: u16,
+    /// Stable, machine-readable reason, e.g. `not_found` or `invalid_parameter`.
+    pub code: String,
+    pub message: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status);
+        if self.status == StatusCode::UNAUTHORIZED {
+            response.insert_header(("WWW-Authenticate", "Bearer"));
+        }
+        response.json(ErrorBody {
+            error: ErrorDetail {
+                status: self.status.as_u16(),
+                code: self.code.to_string(),
+                message: self.message.clone(),
+            },
+        })
+    }
+}
+
+/// Maps a lookup by id to 404 when the row does not exist.
+fn found<T>(result: rusqlite::Result<T>, what: &str) -> Result<T, ApiError> {
+    result.map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => ApiError::not_found(format!("{} not found", what)),
+        e => ApiError::internal(e),
+    })
+}
+
+/// Turns malformed query strings (e.g. `page=abc` or an unknown filter) into
+/// API errors instead of actix's plain-text ones.
+pub fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    ApiError::bad_request(err.to_string()).into()
+}
+
+pub fn json_error(err: actix_web::error::JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::bad_request(err.to_string()).into()
+}
+
+pub fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::bad_request(err.to_string()).into()
+}
+
+pub async fn not_found_handler() -> Result<HttpResponse, ApiError> {
+    Err(ApiError::not_found("No such endpoint"))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PageMeta {
+    pub page: i32,
+    pub page_size: i32,
+    pub total: i32,
+    pub total_pages: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub meta: PageMeta,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Item<T> {
+    pub data: T,
+}
+
+impl<T> Page<T> {
+    fn new(data: Vec<T>, paging: Paging, total: i32) -> Self {
+        Page {
+            data,
+            meta: PageMeta {
+                page: paging.page,
+                page_size: paging.page_size,
+                total,
+                total_pages: (total + paging.page_size - 1) / paging.page_size,
+            },
+        }
+    }
+
+    /// One page of rows that were filtered and sorted in memory.
+    fn slice(mut rows: Vec<T>, paging: Paging) -> Self {
+        let total = rows.len() as i32;
+        let start = (paging.offset() as usize).min(rows.len());
+        let end = (start + paging.page_size as usize).min(rows.len());
+        let data = rows.drain(start..end).collect();
+        Page::new(data, paging, total)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Paging {
+    pub page: i32,
+    pub page_size: i32,
+}
+
+impl Paging {
+    fn parse(page: Option<i32>, page_size: Option<i32>) -> Result<Self, ApiError> {
+        let page = page.unwrap_or(1);
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if page < 1 {
+            return Err(ApiError::bad_request("page must be at least 1"));
+        }
+        if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+            return Err(ApiError::bad_request(format!(
+                "page_size must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        // Keeps `offset` within i32 however large a page is asked for.
+        if (page - 1).checked_mul(page_size).is_none() {
+            return Err(ApiError::bad_request("page is too large"));
+        }
+        Ok(Paging { page, page_size })
+    }
+
+    pub fn offset(&self) -> i32 {
+        (self.page - 1) * self.page_size
+    }
+}
+
+/// Picks the sort field out of `allowed`; `order` is `asc` or `desc`.
+fn sorting(
+    sort: Option<&str>,
+    order: Option<&str>,
+    allowed: &[&'static str],
+    default: (&'static str, bool),
+) -> Result<(&'static str, bool), ApiError> {
+    let field = match sort {
+        None => default.0,
+        Some(sort) => allowed
+            .iter()
+            .copied()
+            .find(|field| *field == sort)
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("sort must be one of: {}", allowed.join(", ")))
+            })?,
+    };
+    let descending = match order {
+        None if sort.is_none() => default.1,
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(_) => return Err(ApiError::bad_request("order must be asc or desc")),
+    };
+    Ok((field, descending))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_date(value: Option<String>, name: &str) -> Result<Option<String>, ApiError> {
+    match non_empty(value) {
+        None => Ok(None),
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map(|date| Some(date.format("%Y-%m-%d").to_string()))
+            .map_err(|_| ApiError::bad_request(format!("{} must be a date as YYYY-MM-DD", name))),
+    }
+}
+
+fn contains(text: &str, needle: &str) -> bool {
+    text.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn ordered(ordering: Ordering, descending: bool) -> Ordering {
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct ContentQuery {
+    page: Option<i32>,
+    page_size: Option<i32>,
+    /// `date`, `title` or `id`.
+    sort: Option<String>,
+    order: Option<String>,
+    /// Matched against title and content.
+    q: Option<String>,
+    author: Option<String>,
+    /// First publication day to include, `YYYY-MM-DD`.
+    from: Option<String>,
+    /// Last publication day to include, `YYYY-MM-DD`.
+    to: Option<String>,
+}
+
+/// Validated filters for the announcement and article lists.
+pub struct ContentFilter {
+    pub q: Option<String>,
+    pub author: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub sort: &'static str,
+    pub descending: bool,
+    pub paging: Paging,
+}
+
+impl TryFrom<ContentQuery> for ContentFilter {
+    type Error = ApiError;
+
+    fn try_from(query: ContentQuery) -> Result<Self, ApiError> {
+        let (sort, descending) = sorting(
+            query.sort.as_deref(),
+            query.order.as_deref(),
+            &["date", "title", "id"],
+            ("date", true),
+        )?;
+        Ok(ContentFilter {
+            q: non_empty(query.q),
+            author: non_empty(query.author),
+            from: parse_date(query.from, "from")?,
+            to: parse_date(query.to, "to")?,
+            sort,
+            descending,
+            paging: Paging::parse(query.page, query.page_size)?,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/announcements",
+    tag = "announcements",
+    params(ContentQuery),
+    responses(
+        (status = 200, description = "The requested data", body = Page<Announcement>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+    )
+)]
+pub async fn announcements_handler(
+    query: web::Query<ContentQuery>,
+) -> Result<web::Json<Page<Announcement>>, ApiError> {
+    let filter = ContentFilter::try_from(query.into_inner())?;
+    let (announcements, total) = db::find_announcements(&filter).map_err(ApiError::internal)?;
+    Ok(web::Json(Page::new(announcements, filter.paging, total)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/announcements/{id}",
+    tag = "announcements",
+    params(("id" = i32, Path)),
+    responses(
+        (status = 200, description = "The requested data", body = Item<Announcement>),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn announcement_handler(
+    id: web::Path<i32>,
+) -> Result<web::Json<Item<Announcement>>, ApiError> {
+    let announcement = found(db::get_announcement(*id), "Announcement")?;
+    Ok(web::Json(Item { data: announcement }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles",
+    tag = "articles",
+    params(ContentQuery),
+    responses(
+        (status = 200, description = "The requested data", body = Page<Article>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+    )
+)]
+pub async fn articles_handler(
+    query: web::Query<ContentQuery>,
+) -> Result<web::Json<Page<Article>>, ApiError> {
+    let filter = ContentFilter::try_from(query.into_inner())?;
+    let (articles, total) = db::find_articles(&filter).map_err(ApiError::internal)?;
+    Ok(web::Json(Page::new(articles, filter.paging, total)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles/{id}",
+    tag = "articles",
+    params(("id" = i32, Path)),
+    responses(
+        (status = 200, description = "The requested data", body = Item<Article>),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn article_handler(id: web::Path<i32>) -> Result<web::Json<Item<Article>>, ApiError> {
+    let article = found(db::get_article(*id), "Article")?;
+    Ok(web::Json(Item { data: article }))
+}
+
+/// A document with the address its file can be downloaded from.
+#[derive(Serialize, ToSchema)]
+pub struct ApiDocument {
+    #[serde(flatten)]
+    pub document: Document,
+    pub url: String,
+}
+
+impl From<Document> for ApiDocument {
+    fn from(document: Document) -> Self {
+        ApiDocument {
+            url: format!("/dokumanlar/{}", document.filename),
+            document,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiDocumentVersion {
+    #[serde(flatten)]
+    pub version: DocumentVersion,
+    pub url: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiDocumentDetail {
+    #[serde(flatten)]
+    pub document: ApiDocument,
+    /// Earlier files, newest first.
+    pub versions: Vec<ApiDocumentVersion>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct DocumentQuery {
+    page: Option<i32>,
+    page_size: Option<i32>,
+    /// `title`, `category`, `academic_year` or `upload_date`.
+    sort: Option<String>,
+    order: Option<String>,
+    /// Matched against the title and the text inside the file.
+    q: Option<String>,
+    category: Option<String>,
+    academic_year: Option<String>,
+}
+
+/// Turns the stored `dd-mm-yyyy` into the sortable `yyyy-mm-dd` and back.
+fn flip_date(date: &str) -> String {
+    date.split('-').rev().collect::<Vec<_>>().join("-")
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/documents",
+    tag = "documents",
+    params(DocumentQuery),
+    responses(
+        (status = 200, description = "The requested data", body = Page<ApiDocument>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+    )
+)]
+pub async fn documents_handler(
+    query: web::Query<DocumentQuery>,
+) -> Result<web::Json<Page<ApiDocument>>, ApiError> {
+    let query = query.into_inner();
+    let paging = Paging::parse(query.page, query.page_size)?;
+    let (sort, descending) = sorting(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        &["title", "category", "academic_year", "upload_date"],
+        ("category", false),
+    )?;
+    let academic_year = non_empty(query.academic_year);
+
+    let mut documents: Vec<Document> = db::get_documents(
+        non_empty(query.category).as_deref(),
+        non_empty(query.q).as_deref(),
+    )
+    .map_err(ApiError::internal)?
+    .into_iter()
+    .filter(|document| {
+        academic_year
+            .as_ref()
+            .is_none_or(|year| document.academic_year == *year)
+    })
+    .collect();
+
+    documents.sort_by(|a, b| {
+        let ordering = match sort {
+            "title" => a.title.cmp(&b.title),
+            "academic_year" => a.academic_year.cmp(&b.academic_year),
+            "upload_date" => flip_date(&a.upload_date).cmp(&flip_date(&b.upload_date)),
+            _ => a.category.cmp(&b.category).then(a.title.cmp(&b.title)),
+        };
+        ordered(ordering, descending)
+    });
+
+    let documents = documents.into_iter().map(ApiDocument::from).collect();
+    Ok(web::Json(Page::slice(documents, paging)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/documents/{id}",
+    tag = "documents",
+    params(("id" = i32, Path)),
+    responses(
+        (status = 200, description = "The requested data", body = Item<ApiDocumentDetail>),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn document_handler(
+    id: web::Path<i32>,
+) -> Result<web::Json<Item<ApiDocumentDetail>>, ApiError> {
+    let document = found(db::get_document(*id), "Document")?;
+    let versions = db::get_document_versions(document.id)
+        .map_err(ApiError::internal)?
+        .into_iter()
+        .map(|version| ApiDocumentVersion {
+            url: format!("/dokumanlar/{}", version.filename),
+            version,
+        })
+        .collect();
+
+    Ok(web::Json(Item {
+        data: ApiDocumentDetail {
+            document: ApiDocument::from(document),
+            versions,
+        },
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct StaffQuery {
+    page: Option<i32>,
+    page_size: Option<i32>,
+    /// `name`, `program` or `display_order`.
+    sort: Option<String>,
+    order: Option<String>,
+    /// Matched against name, title and office.
+    q: Option<String>,
+    program: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff",
+    tag = "staff",
+    params(StaffQuery),
+    responses(
+        (status = 200, description = "The requested data", body = Page<Staff>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+    )
+)]
+pub async fn staff_handler(
+    query: web::Query<StaffQuery>,
+) -> Result<web::Json<Page<Staff>>, ApiError> {
+    let query = query.into_inner();
+    let paging = Paging::parse(query.page, query.page_size)?;
+    let (sort, descending) = sorting(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        &["name", "program", "display_order"],
+        ("program", false),
+    )?;
+    let q = non_empty(query.q);
+    let program = non_empty(query.program);
+
+    // Already ordered by program, then display order.
+    let mut staff: Vec<Staff> = db::get_staff()
+        .map_err(ApiError::internal)?
+        .into_iter()
+        .filter(|member| program.as_ref().is_none_or(|p| member.program == *p))
+        .filter(|member| {
+            q.as_ref().is_none_or(|q| {
+                contains(&member.name, q)
+                    || contains(&member.title, q)
+                    || contains(&member.office, q)
+            })
+        })
+        .collect();
+
+    staff.sort_by(|a, b| {
+        let ordering = match sort {
+            "name" => a.name.cmp(&b.name),
+            "display_order" => a.display_order.cmp(&b.display_order),
+            _ => a
+                .program
+                .cmp(&b.program)
+                .then(a.display_order.cmp(&b.display_order)),
+        };
+        ordered(ordering, descending)
+    });
+
+    Ok(web::Json(Page::slice(staff, paging)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/{id}",
+    tag = "staff",
+    params(("id" = i32, Path)),
+    responses(
+        (status = 200, description = "The requested data", body = Item<Staff>),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn staff_member_handler(id: web::Path<i32>) -> Result<web::Json<Item<Staff>>, ApiError> {
+    let member = found(db::get_staff_member(*id), "Staff member")?;
+    Ok(web::Json(Item { data: member }))
+}
+
+/// Body of the announcement and article write endpoints. On update, fields
+/// left out keep their current value.
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PostInput {
+    title: Option<String>,
+    /// HTML, as produced by the dashboard editor.
+    content: Option<String>,
+    /// Publication day, `YYYY-MM-DD`; today when left out on creation.
+    date: Option<String>,
+    /// An image under `/assets/image/`, e.g. one returned by `POST /api/v1/images`.
+    image: Option<String>,
+    /// Announcements only: what notification subscribers filter on. Empty
+    /// reaches every subscriber; ignored for articles.
+    category: Option<String>,
+    /// Title for search results and link previews; empty uses `title`.
+    seo_title: Option<String>,
+    /// Meta description; empty uses the start of `content`.
+    seo_description: Option<String>,
+}
+
+struct PostFields {
+    image: String,
+    title: String,
+    content: String,
+    /// As stored, `dd-mm-yyyy`.
+    date: String,
+    category: String,
+    seo: Overrides,
+}
+
+impl PostInput {
+    fn into_fields(
+        self,
+        current: Option<PostFields>,
+        default_image: &str,
+    ) -> Result<PostFields, ApiError> {
+        let required = |value: Option<String>, current: Option<String>, name: &str| match (
+            non_empty(value),
+            current,
+        ) {
+            (Some(value), _) => Ok(value),
+            (None, Some(current)) => Ok(current),
+            (None, None) => Err(ApiError::bad_request(format!("{} is required", name))),
+        };
+
+        let date = match parse_date(self.date, "date")? {
+            Some(date) => flip_date(&date),
+            None => match &current {
+                Some(current) => current.date.clone(),
+                None => chrono::Local::now().format("%d-%m-%Y").to_string(),
+            },
+        };
+        let image = match non_empty(self.image) {
+            Some(image) => {
+                let on_disk = format!("../public{}", image);
+                if !image.starts_with("/assets/image/")
+                    || image.contains("..")
+                    || !std::path::Path::new(&on_disk).is_file()
+                {
+                    return Err(ApiError::bad_request(
+                        "image must be an existing file under /assets/image/",
+                    ));
+                }
+                image
+            }
+            None => current
+                .as_ref()
+                .map(|current| current.image.clone())
+                .unwrap_or_else(|| default_image.to_string()),
+        };
+
+        let (title, content, category, seo) = match current {
+            Some(current) => (
+                Some(current.title),
+                Some(current.content),
+                current.category,
+                current.seo,
+            ),
+            None => (None, None, String::new(), Overrides::default()),
+        };
+        Ok(PostFields {
+            image,
+            title: required(self.title, title, "title")?,
+            content: required(self.content, content, "content")?,
+            date,
+            category: self
+                .category
+                .map(|category| category.trim().to_string())
+                .unwrap_or(category),
+            seo: Overrides {
+                title: self
+                    .seo_title
+                    .map(|title| title.trim().to_string())
+                    .unwrap_or(seo.title),
+                description: self
+                    .seo_description
+                    .map(|description| description.trim().to_string())
+                    .unwrap_or(seo.description),
+            },
+        })
+    }
+}
+
+fn created<T: Serialize>(location: String, data: T) -> HttpResponse {
+    HttpResponse::Created()
+        .insert_header(("Location", location))
+        .json(Item { data })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/announcements",
+    tag = "announcements",
+    request_body = PostInput,
+    security(("bearer" = ["announcements:write"])),
+    responses(
+        (status = 201, description = "Created", body = Item<Announcement>, headers(("Location" = String))),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+    )
+)]
+pub async fn create_announcement_handler(
+    req: HttpRequest,
+    input: web::Json<PostInput>,
+) -> Result<HttpResponse, ApiError> {
+    let token = api_token::authorize(&req, "announcements:write")?;
+    let fields = input
+        .into_inner()
+        .into_fields(None, DEFAULT_ANNOUNCEMENT_IMAGE)?;
+
+    let id = db::add_announcement(
+        &fields.image,
+        &fields.title,
+        &fields.content,
+        &fields.date,
+        &token.username,
+        &fields.category,
+        &fields.seo,
+    )
+    .map_err(ApiError::internal)?;
+    let announcement = db::get_announcement(id).map_err(ApiError::internal)?;
+    audit::created(&req, "announcement", id, &announcement);
+    notify::announcement_published(id);
+
+    Ok(created(
+        format!("/api/v1/announcements/{}", id),
+        announcement,
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/announcements/{id}",
+    tag = "announcements",
+    params(("id" = i32, Path)),
+    request_body = PostInput,
+    security(("bearer" = ["announcements:write"])),
+    responses(
+        (status = 200, description = "The requested data", body = Item<Announcement>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn update_announcement_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    input: web::Json<PostInput>,
+) -> Result<web::Json<Item<Announcement>>, ApiError> {
+    api_token::authorize(&req, "announcements:write")?;
+    let before = found(db::get_announcement(*id), "Announcement")?;
+    let fields = input.into_inner().into_fields(
+        Some(PostFields {
+            image: before.image.clone(),
+            title: before.title.clone(),
+            content: before.content.clone(),
+            date: before.date.clone(),
+            category: before.category.clone(),
+            seo: Overrides {
+                title: before.seo_title.clone(),
+                description: before.seo_description.clone(),
+            },
+        }),
+        DEFAULT_ANNOUNCEMENT_IMAGE,
+    )?;
+
+    db::edit_announcement(
+        before.id,
+        &fields.image,
+        &fields.title,
+        &fields.content,
+        &fields.date,
+        &before.author,
+        &fields.category,
+        &fields.seo,
+    )
+    .map_err(ApiError::internal)?;
+    let announcement = db::get_announcement(before.id).map_err(ApiError::internal)?;
+    audit::updated(&req, "announcement", before.id, &before, &announcement);
+
+    Ok(web::Json(Item { data: announcement }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/announcements/{id}",
+    tag = "announcements",
+    params(("id" = i32, Path)),
+    security(("bearer" = ["announcements:write"])),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn delete_announcement_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApiError> {
+    api_token::authorize(&req, "announcements:write")?;
+    let before = found(db::get_announcement(*id), "Announcement")?;
+
+    db::delete_announcement(before.id).map_err(ApiError::internal)?;
+    audit::deleted(&req, "announcement", before.id, &before);
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/articles",
+    tag = "articles",
+    request_body = PostInput,
+    security(("bearer" = ["articles:write"])),
+    responses(
+        (status = 201, description = "Created", body = Item<Article>, headers(("Location" = String))),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+    )
+)]
+pub async fn create_article_handler(
+    req: HttpRequest,
+    input: web::Json<PostInput>,
+) -> Result<HttpResponse, ApiError> {
+    let token = api_token::authorize(&req, "articles:write")?;
+    let fields = input
+        .into_inner()
+        .into_fields(None, DEFAULT_ARTICLE_IMAGE)?;
+
+    let id = db::add_article(
+        &fields.image,
+        &fields.title,
+        &fields.content,
+        &fields.date,
+        &token.username,
+        &fields.seo,
+    )
+    .map_err(ApiError::internal)?;
+    let article = db::get_article(id).map_err(ApiError::internal)?;
+    audit::created(&req, "article", id, &article);
+
+    Ok(created(format!("/api/v1/articles/{}", id), article))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/articles/{id}",
+    tag = "articles",
+    params(("id" = i32, Path)),
+    request_body = PostInput,
+    security(("bearer" = ["articles:write"])),
+    responses(
+        (status = 200, description = "The requested data", body = Item<Article>),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn update_article_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+    input: web::Json<PostInput>,
+) -> Result<web::Json<Item<Article>>, ApiError> {
+    api_token::authorize(&req, "articles:write")?;
+    let before = found(db::get_article(*id), "Article")?;
+    let fields = input.into_inner().into_fields(
+        Some(PostFields {
+            image: before.image.clone(),
+            title: before.title.clone(),
+            content: before.content.clone(),
+            date: before.date.clone(),
+            category: String::new(),
+            seo: Overrides {
+                title: before.seo_title.clone(),
+                description: before.seo_description.clone(),
+            },
+        }),
+        DEFAULT_ARTICLE_IMAGE,
+    )?;
+
+    db::edit_article(
+        before.id,
+        &fields.image,
+        &fields.title,
+        &fields.content,
+        &fields.date,
+        &before.author,
+        &fields.seo,
+    )
+    .map_err(ApiError::internal)?;
+    let article = db::get_article(before.id).map_err(ApiError::internal)?;
+    audit::updated(&req, "article", before.id, &before, &article);
+
+    Ok(web::Json(Item { data: article }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/articles/{id}",
+    tag = "articles",
+    params(("id" = i32, Path)),
+    security(("bearer" = ["articles:write"])),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+        (status = 404, description = "Not found", body = ErrorBody),
+    )
+)]
+pub async fn delete_article_handler(
+    req: HttpRequest,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApiError> {
+    api_token::authorize(&req, "articles:write")?;
+    let before = found(db::get_article(*id), "Article")?;
+
+    db::delete_article(before.id).map_err(ApiError::internal)?;
+    audit::deleted(&req, "article", before.id, &before);
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UploadedImage {
+    pub url: String,
+}
+
+/// Multipart body of the image upload; only described for the API docs.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct ImageUpload {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Stores a JPEG, PNG, WebP or GIF sent as the `file` part of a multipart body
+/// and returns the address to use as an announcement or article image.
+#[utoipa::path(
+    post,
+    path = "/api/v1/images",
+    tag = "images",
+    request_body(content = ImageUpload, content_type = "multipart/form-data"),
+    security(("bearer" = ["images:write"])),
+    responses(
+        (status = 201, description = "Created", body = Item<UploadedImage>, headers(("Location" = String))),
+        (status = 400, description = "Invalid parameter or body", body = ErrorBody),
+        (status = 401, description = "Missing, invalid, expired or revoked token", body = ErrorBody),
+        (status = 403, description = "Token lacks the required scope", body = ErrorBody),
+        (status = 413, description = "Image too large", body = ErrorBody),
+    )
+)]
+pub async fn upload_image_handler(
+    req: HttpRequest,
+    payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    api_token::authorize(&req, "images:write")?;
+
+    let upload = upload::read_form_upload(payload, Some(MAX_IMAGE_BYTES))
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    if upload.file_too_large {
+        return Err(ApiError::payload_too_large(format!(
+            "Images may be at most {} MB",
+            MAX_IMAGE_BYTES / 1024 / 1024
+        )));
+    }
+    let (_, bytes) = upload
+        .file
+        .ok_or_else(|| ApiError::bad_request("Send the image as a multipart part named file"))?;
+
+    let extension = match image::guess_format(&bytes) {
+        Ok(image::ImageFormat::Jpeg) => "jpg",
+        Ok(image::ImageFormat::Png) => "png",
+        Ok(image::ImageFormat::WebP) => "webp",
+        Ok(image::ImageFormat::Gif) => "gif",
+        _ => {
+            return Err(ApiError::bad_request(
+                "Only JPEG, PNG, WebP and GIF images are accepted",
+            ))
+        }
+    };
+    let filename = format!("{}.{}", Uuid::new_v4(), extension);
+    tokio::fs::write(format!("{}/{}", IMAGE_UPLOAD_DIR, filename), &bytes)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let image = UploadedImage {
+        url: format!("/assets/image/upload/{}", filename),
+    };
+    audit::record(
+        &req,
+        "upload",
+        "image",
+        &filename,
+        None,
+        Some(audit::summary(&image)),
+    );
+
+    Ok(created(image.url.clone(), image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paging_offset() {
+        let paging = Paging::parse(Some(3), Some(20)).unwrap();
+        assert_eq!(paging.offset(), 40);
+    }
+
+    #[test]
+    fn paging_rejects_pages_past_the_offset_range() {
+        assert!(Paging::parse(Some(i32::MAX), Some(MAX_PAGE_SIZE)).is_err());
+        assert!(Paging::parse(Some(0), None).is_err());
+    }
+}
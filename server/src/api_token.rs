@@ -0,0 +1,117 @@
+use crate::src::account;
+use crate::src::api::ApiError;
+use crate::src::db;
+use actix_web::{HttpMessage, HttpRequest};
+use rand::RngCore;
+use serde::Serialize;
+
+/// Prefix of every token, so leaked ones are easy to recognise in logs and
+/// secret scanners.
+pub const TOKEN_PREFIX: &str = "smyo_";
+
+pub const SCOPES: [(&str, &str); 3] = [
+    ("announcements:write", "Duyuru ekleme, düzenleme ve silme"),
+    ("articles:write", "Makale ekleme, düzenleme ve silme"),
+    ("images:write", "Görsel yükleme"),
+];
+
+/// A token as listed in the dashboard; the secret itself is never stored.
+#[derive(Clone, Serialize)]
+pub struct ApiToken {
+    pub id: i32,
+    pub user_id: i32,
+    pub username: String,
+    pub name: String,
+    /// First characters of the token, shown to tell tokens apart.
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl ApiToken {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    pub fn is_active(&self, now: &str) -> bool {
+        self.revoked_at.is_none() && self.expires_at.as_deref().is_none_or(|at| at > now)
+    }
+
+    /// How the token shows up as the actor of audit entries.
+    pub fn actor(&self) -> String {
+        format!("{} (API: {})", self.username, self.name)
+    }
+}
+
+/// Set on requests authenticated with a token so the audit log credits the
+/// token instead of a session.
+#[derive(Clone)]
+pub struct ApiActor(pub String);
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Creates a token for `user_id` and returns its secret, which is shown once.
+pub fn issue(
+    user_id: i32,
+    name: &str,
+    scopes: &[String],
+    expires_in_days: Option<u32>,
+) -> rusqlite::Result<(i32, String)> {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let secret: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let token = format!("{}{}", TOKEN_PREFIX, secret);
+
+    let created_at = chrono::Local::now();
+    let expires_at = expires_in_days.map(|days| {
+        (created_at + chrono::Duration::days(days.into()))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    });
+    let id = db::add_api_token(
+        user_id,
+        name,
+        &account::hash_token(&token),
+        &token[..TOKEN_PREFIX.len() + 6],
+        &scopes.join(","),
+        &created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        expires_at.as_deref(),
+    )?;
+
+    Ok((id, token))
+}
+
+/// Checks the `Authorization: Bearer` token of an API request for `scope`.
+pub fn authorize(req: &HttpRequest, scope: &str) -> Result<ApiToken, ApiError> {
+    let token = req
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or_else(|| ApiError::unauthorized("Missing bearer token"))?;
+
+    let now = now();
+    let api_token = db::get_api_token_by_hash(&account::hash_token(token))
+        .map_err(ApiError::internal)?
+        .filter(|api_token| api_token.is_active(&now))
+        .ok_or_else(|| ApiError::unauthorized("Invalid, expired or revoked token"))?;
+    if !api_token.has_scope(scope) {
+        return Err(ApiError::forbidden(format!(
+            "Token lacks the {} scope",
+            scope
+        )));
+    }
+
+    if let Err(e) = db::touch_api_token(api_token.id, &now) {
+        eprintln!("Failed to update token {} usage: {}", api_token.id, e);
+    }
+    req.extensions_mut().insert(ApiActor(api_token.actor()));
+
+    Ok(api_token)
+}
@@ -1,23 +1,35 @@
 use crate::src::db;
-use std::path::PathBuf;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use crate::src::seo::{Meta, Post};
 use actix_web::{web::Json, web::Query ,HttpRequest, HttpResponse, Responder, Result};
 use serde::Serialize;
 use serde_derive::Deserialize;
-
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/makaleler.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+use utoipa::ToSchema;
+
+pub async fn handler(req: HttpRequest, pagination: Query<Pagination>) -> Result<HttpResponse> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let lang = Lang::of(&req);
+    let mut content = layout::template(lang, "makaleler.html").await?;
+    content = content.replace("{{articles}}", &render_list(lang, page, false)?);
+    layout::page(&req, i18n::t(lang, "title.articles"), &content).await
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 pub struct Article {
     pub id: i32,
     pub image: String,
     pub title: String,
+    /// HTML, as produced by the dashboard editor.
     pub content: String,
+    /// Publication day, `dd-mm-yyyy`.
     pub date: String,
     pub author: String,
+    /// Overrides the title in search results and link previews; empty uses
+    /// the title.
+    pub seo_title: String,
+    /// Meta description; empty uses the start of the content.
+    pub seo_description: String,
 }
 
 #[derive(Deserialize)]
@@ -26,18 +38,18 @@ pub struct Pagination {
     page_size: Option<i32>,
 }
 
-pub async fn articles_handler(
-    req: HttpRequest,
-) -> Result<HttpResponse> {
-    let page: i32 = req.match_info().get("page").unwrap_or("1").parse().unwrap_or(1);
-    let from_main_page: bool = req.query_string().contains("main_page=true");
+/// A page of article cards with links to the neighbouring pages, or the
+/// latest three and a link to the rest on the main page.
+pub fn render_list(lang: Lang, page: i32, from_main_page: bool) -> Result<String> {
     let articles_per_page = if from_main_page { 3 } else { 6 };
     let (articles, total_articles) = db::get_articles(page, articles_per_page)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     let mut response = String::new();
     response.push_str("<div class='grid gap-6 lg:grid-cols-3 xl:gap-x-12'>");
-    for article in articles {
+    for mut article in articles {
+        i18n::localize("article", article.id, lang, &mut article.title, &mut article.content)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
         response.push_str(&format!(
             "<div class='mb-6 lg:mb-0'>
                 <div class='relative block rounded-lg bg-white shadow-[0_2px_15px_-3px_rgba(0,0,0,0.07),0_10px_20px_-2px_rgba(0,0,0,0.04)] dark:bg-neutral-700'>
@@ -54,13 +66,15 @@ pub async fn articles_handler(
                             <br/>
                             <a>{}</a></small>
                         </p>
-                        <button hx-get='/article/{}' hx-target='#main-container' hx-push-url='#makale' class='inline-flex items-center justify-center px-4 py-2 text-base font-medium text-white bg-blue-600 border border-transparent rounded-md shadow-sm hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>
-                            Oku
-                        </button>
+                        <a {} class='inline-flex items-center justify-center px-4 py-2 text-base font-medium text-white bg-blue-600 border border-transparent rounded-md shadow-sm hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>
+                            {}
+                        </a>
                         </div>
                 </div>
             </div>",
-            article.image, article.title, article.date, article.author, article.id
+            article.image, article.title, article.date, article.author,
+            layout::link(lang, &format!("/article/{}", article.id)),
+            i18n::t(lang, "list.read")
         ));
     }
 
@@ -68,9 +82,10 @@ pub async fn articles_handler(
 
     if from_main_page {
         response.push_str(&format!(
-            "<a id='link-makaleler' href='#makaleler' hx-get='/makaleler'
-            hx-target='#main-container' hx-trigger='click'
-            class='inline-flex items-center justify-center px-4 mt-6 py-2 text-base font-medium text-white bg-green-600 border border-transparent rounded-md shadow-sm hover:bg-green-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>Tüm Makaleler</a>"
+            "<a {}
+            class='inline-flex items-center justify-center px-4 mt-6 py-2 text-base font-medium text-white bg-green-600 border border-transparent rounded-md shadow-sm hover:bg-green-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500'>{}</a>",
+            layout::link(lang, "/makaleler"),
+            i18n::t(lang, "list.all_articles")
         ));
     } 
     
@@ -80,26 +95,32 @@ pub async fn articles_handler(
         if page > 1 {
             let prev_page = page - 1;
             response.push_str(&format!(
-                "<button class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' hx-get='/articles/{}' hx-boost='true' hx-target='#articles-container'>
+                "<a class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' href='{1}?page={0}' hx-get='{2}/{0}' hx-target='#articles-container' hx-push-url='{1}?page={0}'>
                 <svg class='w-5 h-5 me-2 rtl:rotate-180' aria-hidden='true' xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 14 10'>
                 <path stroke='currentColor' stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M13 5H1m0 0 4 4M1 5l4-4'/>
                 </svg>
-                Önceki Sayfa
-                </button>",
-                prev_page
+                {3}
+                </a>",
+                prev_page,
+                lang.path("/makaleler"),
+                lang.path("/articles"),
+                i18n::t(lang, "list.previous_page")
             ));
         }
 
         if page * 6 < total_articles {
             let next_page = page + 1;
             response.push_str(&format!(
-                "<button class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' hx-get='/articles/{}' hx-boost='true' hx-target='#articles-container'>
-                Sonraki Sayfa
+                "<a class='flex items-center justify-center px-3 h-8 ms-3 text-lg font-bold text-gray-600 bg-white border border-gray-300 rounded-lg hover:bg-gray-300 hover:text-gray-700 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-white' href='{1}?page={0}' hx-get='{2}/{0}' hx-target='#articles-container' hx-push-url='{1}?page={0}'>
+                {3}
                 <svg class='w-5 h-5 ms-2 rtl:rotate-180' aria-hidden='true' xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 14 10'>
                 <path stroke='currentColor' stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M1 5h12m0 0L9 1m4 4L9 9'/>
                 </svg>
-                </button>",
-                next_page
+                </a>",
+                next_page,
+                lang.path("/makaleler"),
+                lang.path("/articles"),
+                i18n::t(lang, "list.next_page")
             ));
         }
 
@@ -107,18 +128,37 @@ pub async fn articles_handler(
     }
     
     response.push_str("</div>");
-    Ok(HttpResponse::Ok().content_type("text/html").body(response))
+    Ok(response)
+}
+
+pub async fn articles_handler(req: HttpRequest) -> Result<HttpResponse> {
+    let page: i32 = req.match_info().get("page").unwrap_or("1").parse().unwrap_or(1);
+    let from_main_page: bool = req.query_string().contains("main_page=true");
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_list(Lang::of(&req), page, from_main_page)?))
 }
 
 pub async fn article_detail_handler(req: HttpRequest) -> Result<HttpResponse> {
-    let id: i32 = req.match_info().get("id").unwrap().parse().unwrap();
-    let article = db::get_article(id)
+    let id: i32 = req
+        .match_info()
+        .get("id")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| actix_web::error::ErrorNotFound("Article not found"))?;
+    let mut article = db::get_article(id).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            actix_web::error::ErrorNotFound("Article not found")
+        }
+        e => actix_web::error::ErrorInternalServerError(e.to_string()),
+    })?;
+    let lang = Lang::of(&req);
+    i18n::localize("article", id, lang, &mut article.title, &mut article.content)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     let html = format!(
         
         "
-        <a href='#makaleler' hx-get='/makaleler' hx-target='#main-container' hx-trigger='click'
+        <a {}
         class='py-4 px-5 text-gray-900 rounded max-w-xs md:bg-transparent flex items-center' aria-current='page'>
         <svg class='w-6 h-6 text-gray-800 dark:text-white' aria-hidden='true' xmlns='http://www.w3.org/2000/svg'
             fill='none' viewBox='0 0 8 14'>
@@ -172,8 +212,23 @@ pub async fn article_detail_handler(req: HttpRequest) -> Result<HttpResponse> {
             </div>
         </div>",
         
-        article.title, article.image, article.content, article.date, article.author
+        layout::link(lang, "/makaleler"), article.title, article.image, article.content, article.date, article.author
     );
 
-    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+    let meta = Meta::post(
+        lang,
+        &lang.path(&format!("/article/{}", article.id)),
+        "Article",
+        Post {
+            title: &article.title,
+            content: &article.content,
+            image: &article.image,
+            date: &article.date,
+            author: &article.author,
+            // The SEO fields describe the Turkish text.
+            seo_title: if lang == Lang::Tr { &article.seo_title } else { "" },
+            seo_description: if lang == Lang::Tr { &article.seo_description } else { "" },
+        },
+    );
+    layout::page_with_meta(&req, &meta, &html).await
 }
\ No newline at end of file
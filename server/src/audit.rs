@@ -0,0 +1,240 @@
+use crate::src::account;
+use crate::src::api_token::ApiActor;
+use crate::src::client_ip;
+use crate::src::db;
+use crate::src::i18n::{self, Lang};
+use actix_session::SessionExt;
+use actix_web::{HttpMessage, HttpRequest};
+use serde::Serialize;
+use serde_derive::Deserialize;
+
+/// Audit actions with the catalogue key of their label.
+pub const ACTIONS: [(&str, &str); 12] = [
+    ("create", "audit.action.create"),
+    ("update", "audit.action.update"),
+    ("delete", "audit.action.delete"),
+    ("upload", "audit.action.upload"),
+    ("reply", "audit.action.reply"),
+    ("mark_unread", "audit.action.mark_unread"),
+    ("bulk", "audit.action.bulk"),
+    ("anonymize", "audit.action.anonymize"),
+    ("erase", "audit.action.erase"),
+    ("password_reset", "audit.action.password_reset"),
+    ("revoke", "audit.action.revoke"),
+    ("retry", "audit.action.retry"),
+];
+
+/// Audited record types with the catalogue key of their label.
+pub const ENTITIES: [(&str, &str); 13] = [
+    ("announcement", "audit.entity.announcement"),
+    ("article", "audit.entity.article"),
+    ("document", "audit.entity.document"),
+    ("user", "audit.entity.user"),
+    ("staff", "audit.entity.staff"),
+    ("image", "audit.entity.image"),
+    ("message", "audit.entity.message"),
+    ("contact_category", "audit.entity.contact_category"),
+    ("contact_field", "audit.entity.contact_field"),
+    ("personal_data", "audit.entity.personal_data"),
+    ("api_token", "audit.entity.api_token"),
+    ("event", "audit.entity.event"),
+    ("job", "audit.entity.job"),
+];
+
+fn label(lang: Lang, labels: &[(&str, &'static str)], value: &str) -> String {
+    match labels.iter().find(|(key, _)| *key == value) {
+        Some((_, label)) => i18n::t(lang, label).to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// One row of the audit log. Rows are only ever inserted; the table refuses
+/// updates other than IP anonymisation and all deletes.
+#[derive(Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub created_at: String,
+    pub actor: String,
+    pub action: String,
+    pub entity: String,
+    pub entity_id: String,
+    /// JSON summary of the record before the change, empty for creations.
+    pub before_state: String,
+    /// JSON summary of the record after the change, empty for deletions.
+    pub after_state: String,
+    pub ip_address: String,
+}
+
+impl AuditEntry {
+    pub fn action_label(&self, lang: Lang) -> String {
+        label(lang, &ACTIONS, &self.action)
+    }
+
+    pub fn entity_label(&self, lang: Lang) -> String {
+        label(lang, &ENTITIES, &self.entity)
+    }
+}
+
+/// Audit view filters as sent by the filter bar; empty strings mean "any".
+#[derive(Deserialize, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub entity: Option<String>,
+    /// First day to include, `YYYY-MM-DD`.
+    pub from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD`.
+    pub to: Option<String>,
+    pub page: Option<i32>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl AuditFilter {
+    pub fn actor(&self) -> Option<&str> {
+        non_empty(&self.actor)
+    }
+
+    pub fn action(&self) -> Option<&str> {
+        non_empty(&self.action)
+    }
+
+    pub fn entity(&self) -> Option<&str> {
+        non_empty(&self.entity)
+    }
+
+    pub fn from(&self) -> Option<&str> {
+        non_empty(&self.from)
+    }
+
+    pub fn to(&self) -> Option<&str> {
+        non_empty(&self.to)
+    }
+
+    pub fn page(&self) -> i32 {
+        self.page.unwrap_or(1).max(1)
+    }
+}
+
+/// JSON summary of a record for the before/after columns.
+pub fn summary<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Records an administrative change made by the signed-in user or API token.
+/// A failure to write the entry is logged rather than undoing a change that
+/// already happened.
+pub fn record(
+    req: &HttpRequest,
+    action: &str,
+    entity: &str,
+    entity_id: impl ToString,
+    before_state: Option<String>,
+    after_state: Option<String>,
+) {
+    let api_actor = req.extensions().get::<ApiActor>().cloned();
+    let actor = match api_actor {
+        Some(ApiActor(actor)) => actor,
+        None => account::signed_in(&req.get_session())
+            .ok()
+            .flatten()
+            .map(|account| account.username)
+            .unwrap_or_else(|| String::from("-")),
+    };
+
+    let entry = AuditEntry {
+        id: 0,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        actor,
+        action: action.to_string(),
+        entity: entity.to_string(),
+        entity_id: entity_id.to_string(),
+        before_state: before_state.unwrap_or_default(),
+        after_state: after_state.unwrap_or_default(),
+        ip_address: client_ip::client_ip(req),
+    };
+
+    if let Err(e) = db::add_audit_entry(&entry) {
+        eprintln!(
+            "Failed to write audit entry {} {} {}: {}",
+            entry.action, entry.entity, entry.entity_id, e
+        );
+    }
+}
+
+pub fn created<T: Serialize + ?Sized>(
+    req: &HttpRequest,
+    entity: &str,
+    entity_id: impl ToString,
+    after: &T,
+) {
+    record(req, "create", entity, entity_id, None, Some(summary(after)));
+}
+
+pub fn updated<T: Serialize + ?Sized>(
+    req: &HttpRequest,
+    entity: &str,
+    entity_id: impl ToString,
+    before: &T,
+    after: &T,
+) {
+    record(
+        req,
+        "update",
+        entity,
+        entity_id,
+        Some(summary(before)),
+        Some(summary(after)),
+    );
+}
+
+pub fn deleted<T: Serialize + ?Sized>(
+    req: &HttpRequest,
+    entity: &str,
+    entity_id: impl ToString,
+    before: &T,
+) {
+    record(
+        req,
+        "delete",
+        entity,
+        entity_id,
+        Some(summary(before)),
+        None,
+    );
+}
+
+fn csv_field(value: &str) -> String {
+    // Keep spreadsheet programs from evaluating cells as formulas.
+    let value = if value.len() > 1 && value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// The given entries as CSV, header row first.
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut csv =
+        String::from("id,created_at,actor,action,entity,entity_id,before,after,ip_address\r\n");
+    for entry in entries {
+        let fields = [
+            entry.id.to_string(),
+            entry.created_at.clone(),
+            entry.actor.clone(),
+            entry.action.clone(),
+            entry.entity.clone(),
+            entry.entity_id.clone(),
+            entry.before_state.clone(),
+            entry.after_state.clone(),
+            entry.ip_address.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
@@ -0,0 +1,181 @@
+use crate::src::config;
+use crate::src::db;
+use actix_web::HttpRequest;
+use std::net::IpAddr;
+
+/// An address or CIDR block from `[network] trusted_proxies`.
+struct TrustedProxy {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl TrustedProxy {
+    fn parse(value: &str) -> Option<Self> {
+        let (address, prefix) = match value.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse().ok()?)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = address.parse().ok()?;
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+        (prefix <= bits).then_some(TrustedProxy { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, canonical(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(network).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(network.into(), ip.into(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u32, prefix: u32) -> bool {
+    prefix == 0 || (network ^ ip) >> (bits - prefix) == 0
+}
+
+/// IPv4 clients reaching a dual-stack socket show up as `::ffff:a.b.c.d`.
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+fn is_trusted(ip: IpAddr) -> bool {
+    config::get()
+        .network
+        .trusted_proxies
+        .iter()
+        .filter_map(|proxy| TrustedProxy::parse(proxy))
+        .any(|proxy| proxy.contains(ip))
+}
+
+/// Reads one node of a `Forwarded: for=...` or `X-Forwarded-For` list.
+/// Obfuscated identifiers and `unknown` yield `None`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        // "[2001:db8::1]:4711"
+        return rest.split(']').next()?.parse().ok();
+    }
+    if let Ok(ip) = node.parse() {
+        return Some(ip);
+    }
+    // "192.0.2.43:47011"
+    node.rsplit_once(':')?.0.parse().ok()
+}
+
+/// Addresses the request passed through, client first, as reported by the
+/// proxies. `Forwarded` (RFC 7239) wins over `X-Forwarded-For` when both are sent.
+fn forwarded_chain(req: &HttpRequest) -> Vec<Option<IpAddr>> {
+    let headers = req.headers();
+
+    let forwarded: Vec<Option<IpAddr>> = headers
+        .get_all("forwarded")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+
+    headers
+        .get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(parse_node)
+        .collect()
+}
+
+/// Address of the client that sent the request. Forwarding headers are only
+/// believed when the connection comes from a trusted proxy, and then only up
+/// to the first hop that is not itself a trusted proxy, so clients cannot
+/// pick their own address by sending the headers themselves.
+pub fn client_ip(req: &HttpRequest) -> String {
+    let peer = match req.peer_addr() {
+        Some(addr) => canonical(addr.ip()),
+        None => return String::from("Unknown"),
+    };
+    if !is_trusted(peer) {
+        return peer.to_string();
+    }
+
+    let mut client = peer;
+    for hop in forwarded_chain(req).into_iter().rev() {
+        match hop.map(canonical) {
+            Some(ip) => {
+                client = ip;
+                if !is_trusted(ip) {
+                    break;
+                }
+            }
+            // A hop we cannot read; nothing further left is trustworthy.
+            None => break,
+        }
+    }
+
+    client.to_string()
+}
+
+/// Drops the host part of an address: the last octet of IPv4 and everything
+/// after the /48 prefix of IPv6. Anything that is not an address is cleared.
+pub fn anonymize_ip(ip: &str) -> String {
+    match ip.parse::<IpAddr>().map(canonical) {
+        Ok(IpAddr::V4(v4)) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{}.{}.{}.0", a, b, c)
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let segments = v6.segments();
+            format!("{:x}:{:x}:{:x}::", segments[0], segments[1], segments[2])
+        }
+        Err(_) => String::new(),
+    }
+}
+
+/// Anonymises the addresses stored with messages and audit entries older than
+/// `[privacy] ip_retention_days` and returns how many were changed.
+pub fn anonymize_old_ips() -> rusqlite::Result<usize> {
+    let days = config::get().privacy.ip_retention_days;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = (chrono::Local::now() - chrono::Duration::days(days.into()))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    let mut changed = 0;
+    for (id, ip_address) in db::get_message_ips_before(&cutoff)? {
+        let anonymized = anonymize_ip(&ip_address);
+        if anonymized != ip_address {
+            db::set_message_ip(id, &anonymized)?;
+            changed += 1;
+        }
+    }
+    for (id, ip_address) in db::get_audit_ips_before(&cutoff)? {
+        let anonymized = anonymize_ip(&ip_address);
+        if anonymized != ip_address {
+            db::set_audit_ip(id, &anonymized)?;
+            changed += 1;
+        }
+    }
+
+    Ok(changed)
+}
@@ -0,0 +1,200 @@
+use serde_derive::Deserialize;
+use std::sync::OnceLock;
+
+pub const CONFIG_PATH: &str = "./config.toml";
+
+/// Server settings read from `config.toml`. Every section is optional; a
+/// missing file or key falls back to the defaults below.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub mail: MailConfig,
+    pub contact: ContactConfig,
+    pub network: NetworkConfig,
+    pub privacy: PrivacyConfig,
+    pub password: PasswordConfig,
+    pub notifications: NotificationConfig,
+    pub jobs: JobsConfig,
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpEncryption {
+    /// Plain connection, e.g. a local SMTP sink during development.
+    #[default]
+    None,
+    StartTls,
+    Tls,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct MailConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub encryption: SmtpEncryption,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    /// Who hears about new contact messages that have no assigned staff yet.
+    pub notify: Vec<String>,
+    /// Public address of the site, used for links in outgoing mail.
+    pub site_url: String,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        MailConfig {
+            enabled: false,
+            host: String::from("localhost"),
+            port: 1025,
+            encryption: SmtpEncryption::None,
+            username: None,
+            password: None,
+            from: String::from("SMYO <noreply@localhost>"),
+            notify: Vec::new(),
+            site_url: String::from("http://192.168.1.6:1907"),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ContactConfig {
+    /// Submissions allowed per client address within `rate_limit_window_secs`.
+    pub rate_limit: usize,
+    pub rate_limit_window_secs: u64,
+    /// Forms sent back faster than this are assumed to come from a bot.
+    pub min_submit_secs: u64,
+    pub max_name_length: usize,
+    pub max_message_length: usize,
+    pub spam_threshold: i32,
+    /// Largest file visitors may attach to a message.
+    pub max_attachment_bytes: usize,
+}
+
+impl Default for ContactConfig {
+    fn default() -> Self {
+        ContactConfig {
+            rate_limit: 5,
+            rate_limit_window_secs: 3600,
+            min_submit_secs: 3,
+            max_name_length: 100,
+            max_message_length: 5000,
+            spam_threshold: 5,
+            max_attachment_bytes: 5 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Reverse proxies (addresses or CIDR blocks) whose `Forwarded` and
+    /// `X-Forwarded-For` headers are believed. Empty means the peer address
+    /// is always the client.
+    pub trusted_proxies: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// Stored client IPs are anonymised after this many days; 0 keeps them.
+    pub ip_retention_days: u32,
+    /// Contact messages are deleted this many days after they arrive; 0 keeps them.
+    pub message_retention_days: u32,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        PrivacyConfig {
+            ip_retention_days: 30,
+            message_retention_days: 730,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordConfig {
+    pub min_length: usize,
+    /// Require both upper and lower case letters.
+    pub require_mixed_case: bool,
+    pub require_digit: bool,
+    /// Require a character that is neither a letter nor a digit.
+    pub require_symbol: bool,
+    /// How long a password reset link stays valid.
+    pub reset_token_minutes: u32,
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        PasswordConfig {
+            min_length: 10,
+            require_mixed_case: true,
+            require_digit: true,
+            require_symbol: false,
+            reset_token_minutes: 60,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Contact for push services in the VAPID claims, a `mailto:` or `https:` URL.
+    pub vapid_subject: String,
+    /// Where the VAPID signing key is kept. It is created on first use and
+    /// must stay the same, or browsers have to subscribe again.
+    pub vapid_key_path: String,
+    /// Hour of the day (0-23) daily and weekly digests are sent.
+    pub digest_hour: u32,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            vapid_subject: String::from("mailto:noreply@localhost"),
+            vapid_key_path: String::from("./db/vapid.pk8"),
+            digest_hour: 8,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct JobsConfig {
+    /// How many background jobs run at the same time.
+    pub workers: usize,
+    /// A job that fails this many times is set aside as failed.
+    pub max_attempts: i32,
+    /// Wait before the first retry; it doubles with every further attempt.
+    pub retry_delay_secs: u64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        JobsConfig {
+            workers: 2,
+            max_attempts: 5,
+            retry_delay_secs: 60,
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn load() -> Config {
+    match std::fs::read_to_string(CONFIG_PATH) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Invalid {}: {}", CONFIG_PATH, e);
+            Config::default()
+        }),
+        Err(_) => Config::default(),
+    }
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(load)
+}
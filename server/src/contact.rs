@@ -1,55 +1,638 @@
-use crate::src::db::contact_message;
-use actix_web::{web, HttpRequest, HttpResponse, Result};
+use crate::src::client_ip;
+use crate::src::config;
+use crate::src::db::{self, contact_message};
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use crate::src::mailer;
+use crate::src::spam;
+use crate::src::upload::{self, FormUpload};
+use actix_multipart::Multipart;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, Result};
+use image::ImageFormat;
+use serde::Serialize;
 use serde_derive::Deserialize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
 
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/contact.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+/// Contact form attachments are kept outside `public` and only served to admins.
+pub const ATTACHMENTS_DIR: &str = "./uploads/contact";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    New,
+    InProgress,
+    Answered,
+    Spam,
+}
+
+impl MessageStatus {
+    pub const ALL: [MessageStatus; 4] = [
+        MessageStatus::New,
+        MessageStatus::InProgress,
+        MessageStatus::Answered,
+        MessageStatus::Spam,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageStatus::New => "new",
+            MessageStatus::InProgress => "in_progress",
+            MessageStatus::Answered => "answered",
+            MessageStatus::Spam => "spam",
+        }
+    }
+
+    pub fn label(&self, lang: Lang) -> &'static str {
+        match self {
+            MessageStatus::New => i18n::t(lang, "admin.inbox.status.new"),
+            MessageStatus::InProgress => i18n::t(lang, "admin.inbox.status.in_progress"),
+            MessageStatus::Answered => i18n::t(lang, "admin.inbox.status.answered"),
+            MessageStatus::Spam => i18n::t(lang, "admin.inbox.status.spam"),
+        }
+    }
+}
+
+impl FromStr for MessageStatus {
+    type Err = ();
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        MessageStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+            .ok_or(())
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct Message {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub message: String,
+    pub ip_address: String,
+    pub received_at: Option<String>,
+    pub is_read: bool,
+    pub assigned_staff_id: Option<i32>,
+    pub status: MessageStatus,
+    pub notes: String,
+    pub spam_score: i32,
+    pub category_id: Option<i32>,
+    pub extra_fields: Vec<FieldValue>,
+    /// Stored file name under `ATTACHMENTS_DIR`.
+    pub attachment_filename: Option<String>,
+    /// File name as the visitor uploaded it.
+    pub attachment_name: Option<String>,
+}
+
+/// Who a message is about; each category routes new messages to one staff member.
+#[derive(Clone, Serialize)]
+pub struct ContactCategory {
+    pub id: i32,
+    pub name: String,
+    pub staff_id: Option<i32>,
+    pub display_order: i32,
+}
+
+/// Extra field types with the catalogue key of their name.
+pub const FIELD_TYPES: [(&str, &str); 5] = [
+    ("text", "admin.contact_form.type.text"),
+    ("textarea", "admin.contact_form.type.textarea"),
+    ("number", "admin.contact_form.type.number"),
+    ("date", "admin.contact_form.type.date"),
+    ("select", "admin.contact_form.type.select"),
+];
+
+/// An extra question admins add to the contact form next to name, e-mail and message.
+#[derive(Clone, Serialize)]
+pub struct ContactField {
+    pub id: i32,
+    pub label: String,
+    pub field_type: String,
+    pub required: bool,
+    /// Comma separated choices for `select` fields.
+    pub options: String,
+    pub display_order: i32,
+}
+
+impl ContactField {
+    pub fn input_name(&self) -> String {
+        format!("field_{}", self.id)
+    }
+
+    pub fn option_list(&self) -> Vec<&str> {
+        self.options
+            .split(',')
+            .map(str::trim)
+            .filter(|option| !option.is_empty())
+            .collect()
+    }
+}
+
+/// Answer to an extra field, stored with the message under the label it had
+/// when the message was sent.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FieldValue {
+    pub label: String,
+    pub value: String,
+}
+
+/// An answer sent to the sender of a message from the inbox.
+#[derive(Clone, Serialize)]
+pub struct MessageReply {
+    pub id: i32,
+    pub message_id: i32,
+    pub author: String,
+    pub subject: String,
+    pub body: String,
+    pub sent_at: String,
+}
+
+/// Inbox filters as sent by the filter bar; empty strings mean "any".
+#[derive(Deserialize, Default)]
+pub struct MessageFilter {
+    pub status: Option<String>,
+    pub unread: Option<String>,
+    pub assigned: Option<String>,
+    pub q: Option<String>,
 }
 
-#[derive(Deserialize)]
-pub struct FormData {
+impl MessageFilter {
+    pub fn status(&self) -> Option<MessageStatus> {
+        self.status.as_deref().and_then(|s| s.parse().ok())
+    }
+
+    pub fn unread_only(&self) -> bool {
+        self.unread.as_deref().is_some_and(|v| !v.is_empty())
+    }
+
+    pub fn assigned_staff_id(&self) -> Option<i32> {
+        self.assigned.as_deref().and_then(|s| s.parse().ok())
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+}
+
+/// Values and per-field errors used to render the contact form.
+#[derive(Default)]
+struct ContactForm {
     name: String,
     email: String,
     message: String,
+    category: String,
+    extra: HashMap<i32, String>,
+    errors: HashMap<String, String>,
 }
 
-pub async fn post_handler(req: HttpRequest, form: web::Form<FormData>) -> Result<HttpResponse> {
-    let form_data = form.into_inner();
+impl ContactForm {
+    fn from_upload(upload: &FormUpload, fields: &[ContactField]) -> Self {
+        ContactForm {
+            name: upload.field("name"),
+            email: upload.field("email"),
+            message: upload.field("message"),
+            category: upload.field("category"),
+            extra: fields
+                .iter()
+                .map(|field| (field.id, upload.field(&field.input_name())))
+                .collect(),
+            errors: HashMap::new(),
+        }
+    }
+
+    fn error(&mut self, field: &str, text: &str) {
+        self.errors.insert(field.to_string(), text.to_string());
+    }
+
+    fn error_html(&self, field: &str) -> String {
+        self.errors
+            .get(field)
+            .map(|text| format!("<p class='mt-1 text-sm text-red-600'>{}</p>", text))
+            .unwrap_or_default()
+    }
+}
 
-    let ip_address = match req.peer_addr() {
-        Some(addr) => addr.ip().to_string(),
-        None => String::from("Unknown"),
+const INPUT_CLASS: &str = "shadow-sm bg-gray-50 border border-gray-300 text-black text-sm rounded-lg focus:ring-primary-500 focus:border-primary-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white";
+const LABEL_CLASS: &str = "block mb-2 text-2xl font-serif text-black dark:text-gray-300";
+
+fn render_category_field(lang: Lang, form: &ContactForm, categories: &[ContactCategory]) -> String {
+    if categories.is_empty() {
+        return String::new();
+    }
+
+    let options: String = categories
+        .iter()
+        .map(|category| {
+            format!(
+                "<option value='{}'{}>{}</option>",
+                category.id,
+                if form.category == category.id.to_string() {
+                    " selected"
+                } else {
+                    ""
+                },
+                escape_html(&category.name)
+            )
+        })
+        .collect();
+
+    format!(
+        "<div>
+            <label for='category' class='{}'>{}</label>
+            <select id='category' name='category' class='{}' required>
+                <option value=''>{}</option>{}
+            </select>
+            {}
+        </div>",
+        LABEL_CLASS,
+        i18n::t(lang, "contact.topic"),
+        INPUT_CLASS,
+        i18n::t(lang, "contact.topic_prompt"),
+        options,
+        form.error_html("category")
+    )
+}
+
+fn render_extra_field(lang: Lang, form: &ContactForm, field: &ContactField) -> String {
+    let name = field.input_name();
+    let value = escape_html(form.extra.get(&field.id).map(String::as_str).unwrap_or(""));
+    let required = if field.required { " required" } else { "" };
+
+    let input = match field.field_type.as_str() {
+        "textarea" => format!(
+            "<textarea id='{0}' name='{0}' rows='4' class='{1}'{2}>{3}</textarea>",
+            name, INPUT_CLASS, required, value
+        ),
+        "select" => {
+            let options: String = field
+                .option_list()
+                .iter()
+                .map(|option| {
+                    let option = escape_html(option);
+                    format!(
+                        "<option{}>{}</option>",
+                        if option == value { " selected" } else { "" },
+                        option
+                    )
+                })
+                .collect();
+            format!(
+                "<select id='{0}' name='{0}' class='{1}'{2}><option value=''>{3}</option>{4}</select>",
+                name,
+                INPUT_CLASS,
+                required,
+                i18n::t(lang, "contact.choose"),
+                options
+            )
+        }
+        input_type => format!(
+            "<input type='{0}' id='{1}' name='{1}' value='{2}' class='{3}'{4}>",
+            if matches!(input_type, "number" | "date") {
+                input_type
+            } else {
+                "text"
+            },
+            name,
+            value,
+            INPUT_CLASS,
+            required
+        ),
     };
 
-    match contact_message(&form_data.name, &form_data.email, &form_data.message, &ip_address) {
-        Ok(_) => Ok(HttpResponse::Ok()
-            .content_type("text/html")
-            .body(format!(
-                "<div class='flex flex-col items-center justify-center h-screen'>
-                    <h1 class='text-4xl font-bold text-gray-900'>Mesajınız Alınmıştır</h1>
-                    <a href='#' hx-get='/main' hx-target='#main-container' hx-push-url='#' hx-trigger='click'
-                    class='py-4 px-5 text-gray-900 rounded max-w-xs md:bg-transparent flex items-center' aria-current='page'>
-                        <svg class='w-6 h-6 mr-2' fill='none' stroke='currentColor' viewBox='0 0 24 24' xmlns='http://www.w3.org/2000/svg'>
-                            <path stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M11 19l-7-7 7-7m8 14l-7-7 7-7'></path>
-                        </svg>
-                    </a>
-                </div>"
-            ))),
-        Err(e) => Ok(HttpResponse::InternalServerError()
-            .content_type("text/html")
-            .body(format!(
-                "<div class='flex flex-col items-center justify-center h-screen'>
-                    <h1 class='text-4xl font-bold text-gray-900'>Mesajınız Gönderilemedi Lütfen Daha Sonra Tekrar Deneyiniz</h1>
-                    <a href='#' hx-get='/main' hx-target='#main-container' hx-push-url='#' hx-trigger='click'
+    format!(
+        "<div>
+            <label for='{}' class='{}'>{}</label>
+            {}
+            {}
+        </div>",
+        name,
+        LABEL_CLASS,
+        escape_html(&field.label),
+        input,
+        form.error_html(&name)
+    )
+}
+
+async fn render_form(lang: Lang, form: &ContactForm) -> Result<String> {
+    let categories = db::get_contact_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let fields = db::get_contact_fields()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let mut content = layout::template(lang, "contact.html").await?;
+
+    let limits = &config::get().contact;
+    content = content.replace("{{form_token}}", &spam::issue_form_token());
+    content = content.replace("{{max_name_length}}", &limits.max_name_length.to_string());
+    content = content.replace(
+        "{{max_message_length}}",
+        &limits.max_message_length.to_string(),
+    );
+    content = content.replace(
+        "{{max_attachment_mb}}",
+        &(limits.max_attachment_bytes / (1024 * 1024)).to_string(),
+    );
+    content = content.replace(
+        "{{category_field}}",
+        &render_category_field(lang, form, &categories),
+    );
+    content = content.replace(
+        "{{extra_fields}}",
+        &fields
+            .iter()
+            .map(|field| render_extra_field(lang, form, field))
+            .collect::<String>(),
+    );
+    content = content.replace("{{name}}", &escape_html(&form.name));
+    content = content.replace("{{email}}", &escape_html(&form.email));
+    content = content.replace("{{message}}", &escape_html(&form.message));
+    for field in ["name", "email", "message", "attachment", "form"] {
+        content = content.replace(&format!("{{{{{}_error}}}}", field), &form.error_html(field));
+    }
+
+    Ok(content)
+}
+
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    let content = render_form(lang, &ContactForm::default()).await?;
+    layout::page(&req, i18n::t(lang, "title.contact"), &content).await
+}
+
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    email.len() <= 254
+        && !local.is_empty()
+        && !email
+            .chars()
+            .any(|c| c.is_whitespace() || c == '<' || c == '>')
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+fn validate(
+    lang: Lang,
+    form: &mut ContactForm,
+    categories: &[ContactCategory],
+    fields: &[ContactField],
+) {
+    let limits = &config::get().contact;
+
+    if !categories.is_empty()
+        && !categories
+            .iter()
+            .any(|category| category.id.to_string() == form.category)
+    {
+        form.error("category", i18n::t(lang, "contact.error.category"));
+    }
+
+    for field in fields {
+        let value = form.extra.get(&field.id).cloned().unwrap_or_default();
+        let error = if value.is_empty() {
+            field.required.then_some(i18n::t(lang, "contact.error.required"))
+        } else {
+            match field.field_type.as_str() {
+                "number" if value.parse::<f64>().is_err() => Some(i18n::t(lang, "contact.error.number")),
+                "date" if chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").is_err() => {
+                    Some(i18n::t(lang, "contact.error.date"))
+                }
+                "select" if !field.option_list().contains(&value.as_str()) => {
+                    Some(i18n::t(lang, "contact.error.select"))
+                }
+                _ if value.chars().count() > limits.max_message_length => Some(i18n::t(lang, "contact.error.too_long")),
+                _ => None,
+            }
+        };
+        if let Some(error) = error {
+            form.error(&field.input_name(), error);
+        }
+    }
+
+    let name_length = form.name.chars().count();
+    if name_length < 2 {
+        form.error("name", i18n::t(lang, "contact.error.name"));
+    } else if name_length > limits.max_name_length {
+        form.error(
+            "name",
+            &i18n::t(lang, "contact.error.name_length")
+                .replace("{max}", &limits.max_name_length.to_string()),
+        );
+    }
+
+    if !is_valid_email(&form.email) {
+        form.error("email", i18n::t(lang, "contact.error.email"));
+    }
+
+    let message_length = form.message.chars().count();
+    if message_length < 10 {
+        form.error("message", i18n::t(lang, "contact.error.message"));
+    } else if message_length > limits.max_message_length {
+        form.error(
+            "message",
+            &i18n::t(lang, "contact.error.message_length")
+                .replace("{max}", &limits.max_message_length.to_string())
+                .replace("{length}", &message_length.to_string()),
+        );
+    }
+}
+
+/// Extension for an accepted attachment, decided by its content rather than
+/// by the name the visitor gave it.
+fn attachment_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"%PDF-") {
+        return Some("pdf");
+    }
+    match image::guess_format(bytes).ok()? {
+        ImageFormat::Jpeg => Some("jpg"),
+        ImageFormat::Png => Some("png"),
+        ImageFormat::WebP => Some("webp"),
+        _ => None,
+    }
+}
+
+async fn save_attachment(extension: &str, bytes: &[u8]) -> std::io::Result<String> {
+    let filename = format!("{}.{}", Uuid::new_v4(), extension);
+    tokio::fs::create_dir_all(ATTACHMENTS_DIR).await?;
+    tokio::fs::write(Path::new(ATTACHMENTS_DIR).join(&filename), bytes).await?;
+    Ok(filename)
+}
+
+/// Deletes a message together with its replies and attachment.
+pub fn delete_message_with_attachment(id: i32) -> rusqlite::Result<()> {
+    let message = db::get_message(id)?;
+    db::delete_message(id)?;
+    if let Some(filename) = message.attachment_filename {
+        let _ = std::fs::remove_file(Path::new(ATTACHMENTS_DIR).join(filename));
+    }
+    Ok(())
+}
+
+/// Shown after a message is sent, and after a failure that the visitor can
+/// do nothing about.
+async fn result_page(req: &HttpRequest, heading_key: &str) -> Result<HttpResponse> {
+    let lang = Lang::of(req);
+    let html = format!(
+        "<div class='flex flex-col items-center justify-center h-screen'>
+                    <h1 class='text-4xl font-bold text-gray-900'>{}</h1>
+                    <a {}
                     class='py-4 px-5 text-gray-900 rounded max-w-xs md:bg-transparent flex items-center' aria-current='page'>
                         <svg class='w-6 h-6 mr-2' fill='none' stroke='currentColor' viewBox='0 0 24 24' xmlns='http://www.w3.org/2000/svg'>
                             <path stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M11 19l-7-7 7-7m8 14l-7-7 7-7'></path>
                         </svg>
                     </a>
-                </div>"
-            ))),
+                </div>",
+        i18n::t(lang, heading_key),
+        layout::link(lang, "/")
+    );
+    layout::page(req, i18n::t(lang, "title.contact"), &html).await
+}
+
+async fn success_page(req: &HttpRequest) -> Result<HttpResponse> {
+    result_page(req, "contact.sent").await
+}
+
+pub async fn post_handler(req: HttpRequest, payload: Multipart) -> Result<HttpResponse> {
+    let limits = &config::get().contact;
+    let lang = Lang::of(&req);
+    let upload = upload::read_form_upload(payload, Some(limits.max_attachment_bytes)).await?;
+
+    let ip_address = client_ip::client_ip(&req);
+
+    // Bots get the normal thank-you page so they have nothing to adapt to.
+    // The `website` field is hidden from people with CSS.
+    if !upload.field("website").is_empty() {
+        return success_page(&req).await;
+    }
+
+    let categories = db::get_contact_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let fields = db::get_contact_fields()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let mut form = ContactForm::from_upload(&upload, &fields);
+
+    match spam::form_age_secs(&upload.field("form_token")) {
+        None => form.error(
+            "form",
+            i18n::t(lang, "contact.error.expired"),
+        ),
+        Some(age) if age < limits.min_submit_secs => form.error(
+            "form",
+            i18n::t(lang, "contact.error.too_fast"),
+        ),
+        Some(_) => validate(lang, &mut form, &categories, &fields),
+    }
+
+    let attachment = match &upload.file {
+        Some((name, bytes)) => match attachment_extension(bytes) {
+            Some(extension) => Some((name.clone(), extension, bytes)),
+            None => {
+                form.error(
+                    "attachment",
+                    i18n::t(lang, "contact.error.attachment_type"),
+                );
+                None
+            }
+        },
+        None => None,
+    };
+    if upload.file_too_large {
+        form.error(
+            "attachment",
+            &i18n::t(lang, "contact.error.attachment_size")
+                .replace("{mb}", &(limits.max_attachment_bytes / (1024 * 1024)).to_string()),
+        );
+    }
+
+    if form.errors.is_empty()
+        && !spam::contact_limiter().check(
+            &ip_address,
+            limits.rate_limit,
+            Duration::from_secs(limits.rate_limit_window_secs),
+        )
+    {
+        form.error(
+            "form",
+            i18n::t(lang, "contact.error.rate_limit"),
+        );
+    }
+
+    if !form.errors.is_empty() {
+        let content = render_form(lang, &form).await?;
+        return layout::page(&req, i18n::t(lang, "title.contact"), &content).await;
+    }
+
+    let extra_fields: Vec<FieldValue> = fields
+        .iter()
+        .filter_map(|field| {
+            let value = form.extra.remove(&field.id).unwrap_or_default();
+            (!value.is_empty()).then(|| FieldValue {
+                label: field.label.clone(),
+                value,
+            })
+        })
+        .collect();
+    let category = categories
+        .iter()
+        .find(|category| category.id.to_string() == form.category);
+
+    let scored_text = extra_fields
+        .iter()
+        .fold(form.message.clone(), |text, field| {
+            text + "\n" + &field.value
+        });
+    let spam_score = spam::spam_score(&form.name, &form.email, &scored_text);
+    let status = if spam::is_spam(spam_score) {
+        MessageStatus::Spam
+    } else {
+        MessageStatus::New
+    };
+
+    let (attachment_filename, attachment_name) = match attachment {
+        Some((name, extension, bytes)) => {
+            let filename = save_attachment(extension, bytes).await?;
+            (Some(filename), Some(name))
+        }
+        None => (None, None),
+    };
+
+    let saved = contact_message(&Message {
+        id: 0,
+        name: form.name,
+        email: form.email,
+        message: form.message,
+        ip_address,
+        received_at: None,
+        is_read: false,
+        assigned_staff_id: category.and_then(|category| category.staff_id),
+        status,
+        notes: String::new(),
+        spam_score,
+        category_id: category.map(|category| category.id),
+        extra_fields,
+        attachment_filename,
+        attachment_name,
+    });
+    if let (Ok(id), MessageStatus::New) = (&saved, status) {
+        mailer::notify_message_in_background(*id);
+    }
+
+    match saved {
+        Ok(_) => success_page(&req).await,
+        Err(e) => {
+            eprintln!("Failed to save contact message: {}", e);
+            let mut response = result_page(&req, "contact.failed").await?;
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            Ok(response)
+        }
     }
 }
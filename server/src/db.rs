@@ -1,7 +1,22 @@
+use crate::src::account::{self, Account, UserError};
 use crate::src::announcements::Announcement;
+use crate::src::api::ContentFilter;
+use crate::src::api_token::ApiToken;
 use crate::src::articles::Article;
+use crate::src::audit::{AuditEntry, AuditFilter};
+use crate::src::contact::{
+    ContactCategory, ContactField, Message, MessageFilter, MessageReply, MessageStatus,
+};
+use crate::src::docs::{Document, DocumentVersion};
+use crate::src::events::Event;
+use crate::src::i18n::Translation;
+use crate::src::jobs::Job;
+use crate::src::notify::{PushSubscription, Subscription};
+use crate::src::personel::Staff;
+use crate::src::seo::Overrides;
 use bcrypt::{hash, verify, DEFAULT_COST}; //12
-use rusqlite::{Connection, Result, ToSql};
+use rusqlite::{Connection, OptionalExtension, Result, ToSql};
+use std::collections::HashMap;
 
 pub fn establish_connection() -> Result<Connection> {
     Connection::open("./db/database.db")
@@ -26,6 +41,9 @@ pub fn get_announcements(
                 content: row.get(3)?,
                 date: row.get(4)?,
                 author: row.get(5)?,
+                category: row.get(6)?,
+                seo_title: row.get(7)?,
+                seo_description: row.get(8)?,
             };
             Ok(announcement)
         })?;
@@ -53,6 +71,9 @@ pub fn get_announcement(id: i32) -> Result<Announcement> {
             content: row.get(3)?,
             date: row.get(4)?,
             author: row.get(5)?,
+            category: row.get(6)?,
+            seo_title: row.get(7)?,
+            seo_description: row.get(8)?,
         };
         Ok(announcement)
     })?;
@@ -61,7 +82,10 @@ pub fn get_announcement(id: i32) -> Result<Announcement> {
     for announcement in announcement_iter {
         announcements.push(announcement?);
     }
-    Ok(announcements[0].clone())
+    announcements
+        .into_iter()
+        .next()
+        .ok_or(rusqlite::Error::QueryReturnedNoRows)
 }
 
 pub fn add_announcement(
@@ -70,17 +94,20 @@ pub fn add_announcement(
     content: &str,
     date: &str,
     author: &str,
-) -> Result<()> {
+    category: &str,
+    seo: &Overrides,
+) -> Result<i32> {
     let conn = establish_connection()?;
 
     conn.execute(
-        "INSERT INTO announcements (image, title, content, date, author) VALUES (?1, ?2, ?3, ?4, ?5)",
-        &[image, title, content, date, author],
+        "INSERT INTO announcements (image, title, content, date, author, category, seo_title, seo_description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        &[image, title, content, date, author, category, &seo.title, &seo.description],
     )?;
 
-    Ok(())
+    Ok(conn.last_insert_rowid() as i32)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn edit_announcement(
     id: i32,
     image: &str,
@@ -88,12 +115,14 @@ pub fn edit_announcement(
     content: &str,
     date: &str,
     author: &str,
+    category: &str,
+    seo: &Overrides,
 ) -> Result<()> {
     let conn = establish_connection()?;
 
     conn.execute(
-        "UPDATE announcements SET image = ?1, title = ?2, content = ?3, date = ?4, author = ?5 WHERE id = ?6",
-        &[image, title, content, date, author, &id.to_string()],
+        "UPDATE announcements SET image = ?1, title = ?2, content = ?3, date = ?4, author = ?5, category = ?6, seo_title = ?7, seo_description = ?8 WHERE id = ?9",
+        &[image, title, content, date, author, category, &seo.title, &seo.description, &id.to_string()],
     )?;
 
     Ok(())
@@ -106,6 +135,10 @@ pub fn delete_announcement(id: i32) -> Result<()> {
         "DELETE FROM announcements WHERE id = ?1",
         &[&id.to_string()],
     )?;
+    conn.execute(
+        "DELETE FROM translations WHERE entity = 'announcement' AND entity_id = ?1",
+        [id],
+    )?;
 
     Ok(())
 }
@@ -125,6 +158,8 @@ pub fn get_articles(page: i32, page_size: i32) -> Result<(Vec<Article>, i32), ru
                 content: row.get(3)?,
                 date: row.get(4)?,
                 author: row.get(5)?,
+                seo_title: row.get(6)?,
+                seo_description: row.get(7)?,
             };
             Ok(article)
         })?;
@@ -152,6 +187,8 @@ pub fn get_article(id: i32) -> Result<Article> {
             content: row.get(3)?,
             date: row.get(4)?,
             author: row.get(5)?,
+            seo_title: row.get(6)?,
+            seo_description: row.get(7)?,
         };
         Ok(article)
     })?;
@@ -160,7 +197,10 @@ pub fn get_article(id: i32) -> Result<Article> {
     for article in article_iter {
         articles.push(article?);
     }
-    Ok(articles[0].clone())
+    articles
+        .into_iter()
+        .next()
+        .ok_or(rusqlite::Error::QueryReturnedNoRows)
 }
 
 pub fn add_article(
@@ -169,15 +209,16 @@ pub fn add_article(
     content: &str,
     date: &str,
     author: &str,
-) -> Result<()> {
+    seo: &Overrides,
+) -> Result<i32> {
     let conn = establish_connection()?;
 
     conn.execute(
-        "INSERT INTO articles (image, title, content, date, author) VALUES (?1, ?2, ?3, ?4, ?5)",
-        &[image, title, content, date, author],
+        "INSERT INTO articles (image, title, content, date, author, seo_title, seo_description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        &[image, title, content, date, author, &seo.title, &seo.description],
     )?;
 
-    Ok(())
+    Ok(conn.last_insert_rowid() as i32)
 }
 
 pub fn edit_article(
@@ -187,12 +228,13 @@ pub fn edit_article(
     content: &str,
     date: &str,
     author: &str,
+    seo: &Overrides,
 ) -> Result<()> {
     let conn = establish_connection()?;
 
     conn.execute(
-        "UPDATE articles SET image = ?1, title = ?2, content = ?3, date = ?4, author = ?5 WHERE id = ?6",
-        &[image, title, content, date, author, &id.to_string()],
+        "UPDATE articles SET image = ?1, title = ?2, content = ?3, date = ?4, author = ?5, seo_title = ?6, seo_description = ?7 WHERE id = ?8",
+        &[image, title, content, date, author, &seo.title, &seo.description, &id.to_string()],
     )?;
 
     Ok(())
@@ -202,151 +244,1943 @@ pub fn delete_article(id: i32) -> Result<()> {
     let conn = establish_connection()?;
 
     conn.execute("DELETE FROM articles WHERE id = ?1", &[&id.to_string()])?;
+    conn.execute(
+        "DELETE FROM translations WHERE entity = 'article' AND entity_id = ?1",
+        [id],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_announcement_categories() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT category FROM announcements WHERE category != '' ORDER BY category",
+    )?;
+    let category_iter = stmt.query_map([], |row| row.get(0))?;
+
+    let mut categories = Vec::new();
+    for category in category_iter {
+        categories.push(category?);
+    }
+    Ok(categories)
+}
+
+/// Announcements published after `id`, oldest first, for digests.
+pub fn get_announcements_after(id: i32) -> Result<Vec<Announcement>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT * FROM announcements WHERE id > ?1 ORDER BY id")?;
+    let announcement_iter = stmt.query_map([id], |row| {
+        Ok(Announcement {
+            id: row.get(0)?,
+            image: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            date: row.get(4)?,
+            author: row.get(5)?,
+            category: row.get(6)?,
+            seo_title: row.get(7)?,
+            seo_description: row.get(8)?,
+        })
+    })?;
+
+    let mut announcements = Vec::new();
+    for announcement in announcement_iter {
+        announcements.push(announcement?);
+    }
+    Ok(announcements)
+}
+
+/// Id and date of every row of `announcements` or `articles`, for the
+/// sitemap.
+pub fn get_post_dates(table: &str) -> Result<Vec<(i32, String)>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!("SELECT id, date FROM {} ORDER BY id DESC", table))?;
+    let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
 
+    let mut rows = Vec::new();
+    for row in row_iter {
+        rows.push(row?);
+    }
+    Ok(rows)
+}
+
+/// `entity` is `announcement` or `article`, as in the audit log.
+pub fn get_translation(entity: &str, id: i32, lang: &str) -> Result<Option<Translation>> {
+    let conn = establish_connection()?;
+    conn.query_row(
+        "SELECT title, content FROM translations WHERE entity = ?1 AND entity_id = ?2 AND lang = ?3",
+        rusqlite::params![entity, id, lang],
+        |row| {
+            Ok(Translation {
+                title: row.get(0)?,
+                content: row.get(1)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Stores a translation, or removes it when both fields are empty.
+pub fn save_translation(entity: &str, id: i32, lang: &str, translation: &Translation) -> Result<()> {
+    let conn = establish_connection()?;
+    if translation.title.trim().is_empty() && translation.content.trim().is_empty() {
+        conn.execute(
+            "DELETE FROM translations WHERE entity = ?1 AND entity_id = ?2 AND lang = ?3",
+            rusqlite::params![entity, id, lang],
+        )?;
+    } else {
+        conn.execute(
+            "INSERT INTO translations (entity, entity_id, lang, title, content) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (entity, entity_id, lang) DO UPDATE SET title = excluded.title, content = excluded.content",
+            rusqlite::params![entity, id, lang, translation.title, translation.content],
+        )?;
+    }
     Ok(())
 }
 
-pub fn contact_message(name: &str, email: &str, message: &str, ip_address: &str) -> Result<()> {
+pub fn get_last_announcement_id() -> Result<i32> {
+    let conn = establish_connection()?;
+    conn.query_row(
+        "SELECT COALESCE(MAX(id), 0) FROM announcements",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Announcements and articles share a table layout, so one query serves
+/// both API lists.
+fn find_posts<T>(
+    table: &str,
+    filter: &ContentFilter,
+    from_row: fn(&rusqlite::Row) -> Result<T>,
+) -> Result<(Vec<T>, i32)> {
+    let conn = establish_connection()?;
+
+    // Dates are stored as dd-mm-yyyy.
+    let date = "(substr(date, 7, 4) || '-' || substr(date, 4, 2) || '-' || substr(date, 1, 2))";
+    let conditions = format!(
+        "(?1 IS NULL OR title LIKE ?1 OR content LIKE ?1)
+         AND (?2 IS NULL OR author = ?2)
+         AND (?3 IS NULL OR {0} >= ?3)
+         AND (?4 IS NULL OR {0} <= ?4)",
+        date
+    );
+    let sort = match filter.sort {
+        "title" => "title",
+        "id" => "id",
+        _ => date,
+    };
+    let direction = if filter.descending { "DESC" } else { "ASC" };
+    let pattern = filter.q.as_ref().map(|q| format!("%{}%", q));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT * FROM {table} WHERE {conditions}
+         ORDER BY {sort} {direction}, id {direction}
+         LIMIT ?5 OFFSET ?6"
+    ))?;
+    let row_iter = stmt.query_map(
+        rusqlite::params![
+            pattern,
+            filter.author,
+            filter.from,
+            filter.to,
+            filter.paging.page_size,
+            filter.paging.offset()
+        ],
+        from_row,
+    )?;
+
+    let mut rows = Vec::new();
+    for row in row_iter {
+        rows.push(row?);
+    }
+
+    let total = conn.query_row(
+        &format!("SELECT COUNT(*) FROM {} WHERE {}", table, conditions),
+        rusqlite::params![pattern, filter.author, filter.from, filter.to],
+        |row| row.get(0),
+    )?;
+
+    Ok((rows, total))
+}
+
+pub fn find_announcements(filter: &ContentFilter) -> Result<(Vec<Announcement>, i32)> {
+    find_posts("announcements", filter, |row| {
+        Ok(Announcement {
+            id: row.get(0)?,
+            image: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            date: row.get(4)?,
+            author: row.get(5)?,
+            category: row.get(6)?,
+            seo_title: row.get(7)?,
+            seo_description: row.get(8)?,
+        })
+    })
+}
+
+pub fn find_articles(filter: &ContentFilter) -> Result<(Vec<Article>, i32)> {
+    find_posts("articles", filter, |row| {
+        Ok(Article {
+            id: row.get(0)?,
+            image: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            date: row.get(4)?,
+            author: row.get(5)?,
+            seo_title: row.get(6)?,
+            seo_description: row.get(7)?,
+        })
+    })
+}
+
+fn row_to_document(row: &rusqlite::Row) -> Result<Document> {
+    Ok(Document {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        category: row.get(2)?,
+        academic_year: row.get(3)?,
+        version: row.get(4)?,
+        filename: row.get(5)?,
+        original_name: row.get(6)?,
+        upload_date: row.get(7)?,
+        change_note: row.get(9)?,
+    })
+}
+
+pub fn get_documents(
+    category: Option<&str>,
+    query: Option<&str>,
+) -> Result<Vec<Document>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let pattern = query.map(|q| format!("%{}%", q));
+    let mut stmt = conn.prepare(
+        "SELECT * FROM documents
+         WHERE (?1 IS NULL OR category = ?1)
+           AND (?2 IS NULL OR title LIKE ?2 OR content_text LIKE ?2)
+         ORDER BY category, title",
+    )?;
+    let document_iter = stmt.query_map(rusqlite::params![category, pattern], row_to_document)?;
+
+    let mut documents = Vec::new();
+    for document in document_iter {
+        documents.push(document?);
+    }
+
+    Ok(documents)
+}
+
+pub fn get_documents_without_text() -> Result<Vec<Document>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT * FROM documents WHERE content_text IS NULL")?;
+    let document_iter = stmt.query_map([], row_to_document)?;
+
+    let mut documents = Vec::new();
+    for document in document_iter {
+        documents.push(document?);
+    }
+
+    Ok(documents)
+}
+
+pub fn set_document_text(id: i32, text: &str) -> Result<()> {
     let conn = establish_connection()?;
 
     conn.execute(
-        "INSERT INTO messages (name, email, message, ip_address) VALUES (?1, ?2, ?3, ?4)",
-        &[&name, &email, &message, &ip_address],
+        "UPDATE documents SET content_text = ?1 WHERE id = ?2",
+        rusqlite::params![text, id],
     )?;
 
     Ok(())
 }
 
-pub fn get_messages() -> Result<Vec<(i32, String, String, String, String)>, rusqlite::Error> {
+pub fn get_document_categories() -> Result<Vec<String>, rusqlite::Error> {
     let conn = establish_connection()?;
 
-    let mut stmt = conn.prepare("SELECT id, name, email, message, ip_address FROM messages")?;
-    let message_iter = stmt.query_map([], |row| {
-        let id: i32 = row.get(0)?;
-        let name: String = row.get(1)?;
-        let email: String = row.get(2)?;
-        let message: String = row.get(3)?;
-        let ip_address: String = row.get(4)?;
-        Ok((id, name, email, message, ip_address))
-    })?;
+    let mut stmt = conn.prepare("SELECT DISTINCT category FROM documents ORDER BY category")?;
+    let category_iter = stmt.query_map([], |row| row.get(0))?;
 
-    let mut messages = Vec::new();
-    for message in message_iter {
-        messages.push(message?);
+    let mut categories = Vec::new();
+    for category in category_iter {
+        categories.push(category?);
     }
 
-    Ok(messages)
+    Ok(categories)
 }
 
-pub fn delete_message(id: i32) -> Result<()> {
+pub fn get_document(id: i32) -> Result<Document> {
     let conn = establish_connection()?;
 
-    conn.execute("DELETE FROM messages WHERE id = ?1", &[&id.to_string()])?;
+    conn.query_row(
+        "SELECT * FROM documents WHERE id = ?1",
+        [&id],
+        row_to_document,
+    )
+}
+
+pub fn get_document_by_filename(filename: &str) -> Result<Document> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        "SELECT * FROM documents WHERE filename = ?1",
+        [filename],
+        row_to_document,
+    )
+}
+
+pub fn add_document(document: &Document) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO documents (title, category, academic_year, version, filename, original_name, upload_date, change_note) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        [
+            &document.title,
+            &document.category,
+            &document.academic_year,
+            &document.version,
+            &document.filename,
+            &document.original_name,
+            &document.upload_date,
+            &document.change_note,
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+/// Saves a document. When its file was replaced, `previous` records the old
+/// file as an earlier version in the same transaction.
+pub fn edit_document(document: &Document, previous: Option<&DocumentVersion>) -> Result<()> {
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+
+    if let Some(version) = previous {
+        tx.execute(
+            "INSERT INTO document_versions (document_id, version, filename, original_name, upload_date, change_note) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                version.document_id,
+                version.version,
+                version.filename,
+                version.original_name,
+                version.upload_date,
+                version.change_note,
+            ],
+        )?;
+    }
+    tx.execute(
+        "UPDATE documents SET title = ?1, category = ?2, academic_year = ?3, version = ?4, filename = ?5, original_name = ?6, upload_date = ?7, change_note = ?8 WHERE id = ?9",
+        [
+            &document.title,
+            &document.category,
+            &document.academic_year,
+            &document.version,
+            &document.filename,
+            &document.original_name,
+            &document.upload_date,
+            &document.change_note,
+            &document.id.to_string(),
+        ],
+    )?;
+    tx.commit()?;
 
     Ok(())
 }
 
-pub fn authenticate_user(
-    username: &str,
-    password: &str,
-) -> Result<(bool, Option<String>), rusqlite::Error> {
+pub fn delete_document(id: i32) -> Result<()> {
     let conn = establish_connection()?;
 
-    let mut stmt = conn.prepare("SELECT password, name FROM users WHERE username = ?1")?;
-    let mut user_iter = stmt.query_map(&[username], |row| {
-        let hashed_password: String = row.get(0)?;
-        let name: String = row.get(1)?;
-        let is_password_match = verify(password, &hashed_password).unwrap_or(false);
-        Ok((is_password_match, name))
-    })?;
+    conn.execute("DELETE FROM document_versions WHERE document_id = ?1", [id])?;
+    conn.execute("DELETE FROM documents WHERE id = ?1", [id])?;
 
-    let user = user_iter
-        .filter_map(|result| match result {
-            Ok((true, name)) => Some(name),
-            _ => None,
-        })
-        .next();
+    Ok(())
+}
 
-    Ok((user.is_some(), user))
+fn row_to_document_version(row: &rusqlite::Row) -> Result<DocumentVersion> {
+    Ok(DocumentVersion {
+        id: row.get(0)?,
+        document_id: row.get(1)?,
+        version: row.get(2)?,
+        filename: row.get(3)?,
+        original_name: row.get(4)?,
+        upload_date: row.get(5)?,
+        change_note: row.get(6)?,
+    })
 }
 
-pub fn get_users() -> Result<Vec<String>, rusqlite::Error> {
+/// Earlier versions of a document, newest first.
+pub fn get_document_versions(document_id: i32) -> Result<Vec<DocumentVersion>, rusqlite::Error> {
     let conn = establish_connection()?;
 
-    let mut stmt = conn.prepare("SELECT name,username FROM users")?;
-    let user_iter = stmt.query_map([], |row| {
-        let name: String = row.get(0)?;
-        let username: String = row.get(1)?;
-        Ok(format!("{} ({})", name, username))
-    })?;
+    let mut stmt =
+        conn.prepare("SELECT * FROM document_versions WHERE document_id = ?1 ORDER BY id DESC")?;
+    let version_iter = stmt.query_map([document_id], row_to_document_version)?;
 
-    let mut users = Vec::new();
-    for user in user_iter {
-        users.push(user?);
+    let mut versions = Vec::new();
+    for version in version_iter {
+        versions.push(version?);
     }
 
-    Ok(users)
+    Ok(versions)
 }
 
-pub fn get_user(username: &str) -> Result<(String, String), rusqlite::Error> {
+pub fn get_document_version_by_filename(filename: &str) -> Result<DocumentVersion> {
     let conn = establish_connection()?;
 
-    let mut stmt = conn.prepare("SELECT name,username FROM users WHERE username = ?1")?;
-    let mut user_iter = stmt.query_map(&[username], |row| {
-        let name: String = row.get(0)?;
-        let username: String = row.get(1)?;
-        Ok((name, username))
-    })?;
+    conn.query_row(
+        "SELECT * FROM document_versions WHERE filename = ?1",
+        [filename],
+        row_to_document_version,
+    )
+}
 
-    let mut user = user_iter
-        .next()
-        .unwrap_or(Ok(("".to_string(), "".to_string())))?;
+fn row_to_staff(row: &rusqlite::Row) -> Result<Staff> {
+    Ok(Staff {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        title: row.get(2)?,
+        program: row.get(3)?,
+        office: row.get(4)?,
+        phone: row.get(5)?,
+        email: row.get(6)?,
+        photo: row.get(7)?,
+        office_hours: row.get(8)?,
+        display_order: row.get(9)?,
+    })
+}
+
+/// All staff, ordered by program and then by display order within it.
+pub fn get_staff() -> Result<Vec<Staff>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT * FROM staff ORDER BY program, display_order, name")?;
+    let staff_iter = stmt.query_map([], row_to_staff)?;
 
-    Ok(user)
+    let mut staff = Vec::new();
+    for member in staff_iter {
+        staff.push(member?);
+    }
+
+    Ok(staff)
 }
 
-pub fn add_user(
-    name: &str,
-    username: &str,
-    password: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub fn get_staff_member(id: i32) -> Result<Staff> {
     let conn = establish_connection()?;
-    let hashed_password = hash(password, DEFAULT_COST)?;
+
+    conn.query_row("SELECT * FROM staff WHERE id = ?1", [id], row_to_staff)
+}
+
+pub fn get_staff_programs() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT DISTINCT program FROM staff ORDER BY program")?;
+    let program_iter = stmt.query_map([], |row| row.get(0))?;
+
+    let mut programs = Vec::new();
+    for program in program_iter {
+        programs.push(program?);
+    }
+
+    Ok(programs)
+}
+
+pub fn add_staff(staff: &Staff) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO staff (name, title, program, office, phone, email, photo, office_hours, display_order) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            staff.name,
+            staff.title,
+            staff.program,
+            staff.office,
+            staff.phone,
+            staff.email,
+            staff.photo,
+            staff.office_hours,
+            staff.display_order,
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+pub fn edit_staff(staff: &Staff) -> Result<()> {
+    let conn = establish_connection()?;
+
     conn.execute(
-        "INSERT INTO users (name, username, password) VALUES (?1, ?2, ?3)",
-        &[&name, &username, &hashed_password as &str],
+        "UPDATE staff SET name = ?1, title = ?2, program = ?3, office = ?4, phone = ?5, email = ?6, photo = ?7, office_hours = ?8, display_order = ?9 WHERE id = ?10",
+        rusqlite::params![
+            staff.name,
+            staff.title,
+            staff.program,
+            staff.office,
+            staff.phone,
+            staff.email,
+            staff.photo,
+            staff.office_hours,
+            staff.display_order,
+            staff.id,
+        ],
     )?;
+
     Ok(())
 }
 
-pub fn delete_user(username: &str) -> Result<()> {
+pub fn delete_staff(id: i32) -> Result<()> {
     let conn = establish_connection()?;
 
-    conn.execute("DELETE FROM users WHERE username = ?1", &[&username])?;
+    conn.execute("DELETE FROM staff WHERE id = ?1", [id])?;
 
     Ok(())
 }
 
-pub fn edit_user(
-    username: &str,
-    name: &str,
-    new_username: &str,
-    password: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    if password.is_empty() {
-        let conn = establish_connection()?;
-        conn.execute(
-            "UPDATE users SET name = ?1, username = ?2 WHERE username = ?3",
-            &[&name, &new_username, &username],
-        )?;
-        return Ok(());
-    } else {
-        let conn = establish_connection()?;
-        let hashed_password = hash(password, DEFAULT_COST)?;
-        conn.execute(
-            "UPDATE users SET name = ?1, username = ?2, password = ?3 WHERE username = ?4",
-            &[&name, &new_username, &hashed_password as &str, &username],
-        )?;
-        return Ok(());
+fn row_to_event(row: &rusqlite::Row) -> Result<Event> {
+    Ok(Event {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        category: row.get(3)?,
+        location: row.get(4)?,
+        starts_at: row.get(5)?,
+        ends_at: row.get(6)?,
+        all_day: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// Events overlapping `[from, until)`, optionally of one category, earliest
+/// first. Either bound may be left open.
+pub fn get_events(
+    from: Option<&str>,
+    until: Option<&str>,
+    category: Option<&str>,
+) -> Result<Vec<Event>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    // All-day events store their last day at 00:00, so compare by day there.
+    let mut stmt = conn.prepare(
+        "SELECT * FROM events
+         WHERE (?1 IS NULL OR CASE WHEN all_day THEN substr(COALESCE(ends_at, starts_at), 1, 10) >= substr(?1, 1, 10)
+                                   ELSE COALESCE(ends_at, starts_at) >= ?1 END)
+           AND (?2 IS NULL OR starts_at < ?2)
+           AND (?3 IS NULL OR category = ?3)
+         ORDER BY starts_at, id",
+    )?;
+    let event_iter = stmt.query_map(rusqlite::params![from, until, category], row_to_event)?;
+
+    let mut events = Vec::new();
+    for event in event_iter {
+        events.push(event?);
+    }
+
+    Ok(events)
+}
+
+pub fn get_event(id: i32) -> Result<Event> {
+    let conn = establish_connection()?;
+
+    conn.query_row("SELECT * FROM events WHERE id = ?1", [id], row_to_event)
+}
+
+pub fn get_event_categories() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT DISTINCT category FROM events ORDER BY category")?;
+    let category_iter = stmt.query_map([], |row| row.get(0))?;
+
+    let mut categories = Vec::new();
+    for category in category_iter {
+        categories.push(category?);
+    }
+
+    Ok(categories)
+}
+
+pub fn add_event(event: &Event) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO events (title, description, category, location, starts_at, ends_at, all_day, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            event.title,
+            event.description,
+            event.category,
+            event.location,
+            event.starts_at,
+            event.ends_at,
+            event.all_day,
+            event.updated_at,
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+pub fn edit_event(event: &Event) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE events SET title = ?1, description = ?2, category = ?3, location = ?4, starts_at = ?5, ends_at = ?6, all_day = ?7, updated_at = ?8 WHERE id = ?9",
+        rusqlite::params![
+            event.title,
+            event.description,
+            event.category,
+            event.location,
+            event.starts_at,
+            event.ends_at,
+            event.all_day,
+            event.updated_at,
+            event.id,
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_event(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM events WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+fn row_to_contact_category(row: &rusqlite::Row) -> Result<ContactCategory> {
+    Ok(ContactCategory {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        staff_id: row.get(2)?,
+        display_order: row.get(3)?,
+    })
+}
+
+pub fn get_contact_categories() -> Result<Vec<ContactCategory>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT * FROM contact_categories ORDER BY display_order, name")?;
+    let category_iter = stmt.query_map([], row_to_contact_category)?;
+
+    let mut categories = Vec::new();
+    for category in category_iter {
+        categories.push(category?);
     }
+
+    Ok(categories)
+}
+
+pub fn add_contact_category(category: &ContactCategory) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO contact_categories (name, staff_id, display_order) VALUES (?1, ?2, ?3)",
+        rusqlite::params![category.name, category.staff_id, category.display_order],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+pub fn edit_contact_category(category: &ContactCategory) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE contact_categories SET name = ?1, staff_id = ?2, display_order = ?3 WHERE id = ?4",
+        rusqlite::params![
+            category.name,
+            category.staff_id,
+            category.display_order,
+            category.id
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_contact_category(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM contact_categories WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+fn row_to_contact_field(row: &rusqlite::Row) -> Result<ContactField> {
+    Ok(ContactField {
+        id: row.get(0)?,
+        label: row.get(1)?,
+        field_type: row.get(2)?,
+        required: row.get(3)?,
+        options: row.get(4)?,
+        display_order: row.get(5)?,
+    })
+}
+
+pub fn get_contact_fields() -> Result<Vec<ContactField>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT * FROM contact_fields ORDER BY display_order, id")?;
+    let field_iter = stmt.query_map([], row_to_contact_field)?;
+
+    let mut fields = Vec::new();
+    for field in field_iter {
+        fields.push(field?);
+    }
+
+    Ok(fields)
+}
+
+pub fn add_contact_field(field: &ContactField) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO contact_fields (label, field_type, required, options, display_order) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            field.label,
+            field.field_type,
+            field.required,
+            field.options,
+            field.display_order
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+pub fn edit_contact_field(field: &ContactField) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE contact_fields SET label = ?1, field_type = ?2, required = ?3, options = ?4, display_order = ?5 WHERE id = ?6",
+        rusqlite::params![
+            field.label,
+            field.field_type,
+            field.required,
+            field.options,
+            field.display_order,
+            field.id
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_contact_field(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM contact_fields WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+/// Stores a message from the contact form with its initial status and spam score.
+pub fn contact_message(message: &Message) -> Result<i32> {
+    let conn = establish_connection()?;
+    let received_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    conn.execute(
+        "INSERT INTO messages (name, email, message, ip_address, received_at, assigned_staff_id, status, spam_score, category_id, extra_fields, attachment_filename, attachment_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        rusqlite::params![
+            message.name,
+            message.email,
+            message.message,
+            message.ip_address,
+            received_at,
+            message.assigned_staff_id,
+            message.status.as_str(),
+            message.spam_score,
+            message.category_id,
+            serde_json::to_string(&message.extra_fields).unwrap_or_else(|_| "[]".to_string()),
+            message.attachment_filename,
+            message.attachment_name
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+fn row_to_message(row: &rusqlite::Row) -> Result<Message> {
+    let status: String = row.get(8)?;
+    let extra_fields: String = row.get(12)?;
+    Ok(Message {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        email: row.get(2)?,
+        message: row.get(3)?,
+        ip_address: row.get(4)?,
+        received_at: row.get(5)?,
+        is_read: row.get(6)?,
+        assigned_staff_id: row.get(7)?,
+        status: status.parse().unwrap_or(MessageStatus::New),
+        notes: row.get(9)?,
+        spam_score: row.get(10)?,
+        category_id: row.get(11)?,
+        extra_fields: serde_json::from_str(&extra_fields).unwrap_or_default(),
+        attachment_filename: row.get(13)?,
+        attachment_name: row.get(14)?,
+    })
+}
+
+const MESSAGE_COLUMNS: &str =
+    "id, name, email, message, ip_address, received_at, is_read, assigned_staff_id, status, notes, spam_score, category_id, extra_fields, attachment_filename, attachment_name";
+
+/// Messages matching the inbox filters, newest first. Spam only shows up
+/// when it is asked for explicitly.
+pub fn get_messages(filter: &MessageFilter) -> Result<Vec<Message>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let pattern = filter.query().map(|q| format!("%{}%", q));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM messages
+         WHERE ((?1 IS NULL AND status != 'spam') OR status = ?1)
+           AND (?2 = 0 OR is_read = 0)
+           AND (?3 IS NULL OR assigned_staff_id = ?3)
+           AND (?4 IS NULL OR name LIKE ?4 OR email LIKE ?4 OR message LIKE ?4)
+         ORDER BY id DESC",
+        MESSAGE_COLUMNS
+    ))?;
+    let message_iter = stmt.query_map(
+        rusqlite::params![
+            filter.status().map(|status| status.as_str()),
+            filter.unread_only(),
+            filter.assigned_staff_id(),
+            pattern
+        ],
+        row_to_message,
+    )?;
+
+    let mut messages = Vec::new();
+    for message in message_iter {
+        messages.push(message?);
+    }
+
+    Ok(messages)
+}
+
+pub fn get_message(id: i32) -> Result<Message> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM messages WHERE id = ?1", MESSAGE_COLUMNS),
+        [id],
+        row_to_message,
+    )
+}
+
+pub fn count_spam_messages() -> Result<i64> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        "SELECT COUNT(*) FROM messages WHERE status = 'spam'",
+        [],
+        |row| row.get(0),
+    )
+}
+
+pub fn count_unread_messages() -> Result<i64> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        "SELECT COUNT(*) FROM messages WHERE is_read = 0 AND status != 'spam'",
+        [],
+        |row| row.get(0),
+    )
+}
+
+pub fn set_message_read(id: i32, is_read: bool) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE messages SET is_read = ?1 WHERE id = ?2",
+        rusqlite::params![is_read, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn set_message_status(id: i32, status: MessageStatus) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE messages SET status = ?1 WHERE id = ?2",
+        rusqlite::params![status.as_str(), id],
+    )?;
+
+    Ok(())
+}
+
+/// Saves the workflow fields an admin can change from the detail view.
+pub fn update_message_workflow(
+    id: i32,
+    status: MessageStatus,
+    assigned_staff_id: Option<i32>,
+    notes: &str,
+) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE messages SET status = ?1, assigned_staff_id = ?2, notes = ?3 WHERE id = ?4",
+        rusqlite::params![status.as_str(), assigned_staff_id, notes, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_message(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM message_replies WHERE message_id = ?1", [id])?;
+    conn.execute("DELETE FROM messages WHERE id = ?1", &[&id.to_string()])?;
+
+    Ok(())
+}
+
+/// Ids and stored IPs of messages received before `cutoff`. Messages from
+/// before arrival times were recorded count as old.
+pub fn get_message_ips_before(cutoff: &str) -> Result<Vec<(i32, String)>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, ip_address FROM messages WHERE (received_at IS NULL OR received_at < ?1) AND ip_address != ''",
+    )?;
+    let row_iter = stmt.query_map([cutoff], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut rows = Vec::new();
+    for row in row_iter {
+        rows.push(row?);
+    }
+
+    Ok(rows)
+}
+
+pub fn set_message_ip(id: i32, ip_address: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE messages SET ip_address = ?1 WHERE id = ?2",
+        rusqlite::params![ip_address, id],
+    )?;
+
+    Ok(())
+}
+
+/// Every message sent with `email`, oldest first. Addresses are compared
+/// without regard to case or surrounding spaces.
+pub fn get_messages_by_email(email: &str) -> Result<Vec<Message>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM messages WHERE lower(trim(email)) = lower(trim(?1)) ORDER BY id",
+        MESSAGE_COLUMNS
+    ))?;
+    let message_iter = stmt.query_map([email], row_to_message)?;
+
+    let mut messages = Vec::new();
+    for message in message_iter {
+        messages.push(message?);
+    }
+
+    Ok(messages)
+}
+
+/// Ids of messages received before `cutoff`. Messages without an arrival
+/// time are left out since their age is unknown.
+pub fn get_message_ids_before(cutoff: &str) -> Result<Vec<i32>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT id FROM messages WHERE received_at < ?1")?;
+    let id_iter = stmt.query_map([cutoff], |row| row.get(0))?;
+
+    let mut ids = Vec::new();
+    for id in id_iter {
+        ids.push(id?);
+    }
+
+    Ok(ids)
+}
+
+/// Clears everything that identifies the sender while keeping the message's
+/// category, dates and status for statistics.
+pub fn anonymize_message(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE messages SET name = 'Anonim', email = '', message = '', ip_address = '', notes = '', extra_fields = '[]', attachment_filename = NULL, attachment_name = NULL WHERE id = ?1",
+        [id],
+    )?;
+    conn.execute(
+        "UPDATE message_replies SET body = '' WHERE message_id = ?1",
+        [id],
+    )?;
+
+    Ok(())
+}
+
+pub fn add_message_reply(reply: &MessageReply) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO message_replies (message_id, author, subject, body, sent_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            reply.message_id,
+            reply.author,
+            reply.subject,
+            reply.body,
+            reply.sent_at
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Replies sent for a message, oldest first so they read as a thread.
+pub fn get_message_replies(message_id: i32) -> Result<Vec<MessageReply>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, message_id, author, subject, body, sent_at FROM message_replies WHERE message_id = ?1 ORDER BY id",
+    )?;
+    let reply_iter = stmt.query_map([message_id], |row| {
+        Ok(MessageReply {
+            id: row.get(0)?,
+            message_id: row.get(1)?,
+            author: row.get(2)?,
+            subject: row.get(3)?,
+            body: row.get(4)?,
+            sent_at: row.get(5)?,
+        })
+    })?;
+
+    let mut replies = Vec::new();
+    for reply in reply_iter {
+        replies.push(reply?);
+    }
+
+    Ok(replies)
+}
+
+/// The account with this username and password, if there is one.
+pub fn authenticate_user(username: &str, password: &str) -> Result<Option<Account>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, password FROM users WHERE username = ?1",
+        ACCOUNT_COLUMNS
+    ))?;
+    let mut user_iter = stmt.query_map([username], |row| {
+        let hashed_password: String = row.get(5)?;
+        let is_password_match = verify(password, &hashed_password).unwrap_or(false);
+        Ok((is_password_match, account_from_row(row)?))
+    })?;
+
+    Ok(user_iter.find_map(|result| match result {
+        Ok((true, account)) => Some(account),
+        _ => None,
+    }))
+}
+
+const ACCOUNT_COLUMNS: &str = "id, username, name, email, is_admin";
+
+fn account_from_row(row: &rusqlite::Row) -> Result<Account> {
+    Ok(Account {
+        id: row.get(0)?,
+        username: row.get(1)?,
+        name: row.get(2)?,
+        email: row.get(3)?,
+        is_admin: row.get(4)?,
+    })
+}
+
+pub fn get_users() -> Result<Vec<Account>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM users ORDER BY name COLLATE NOCASE",
+        ACCOUNT_COLUMNS
+    ))?;
+    let user_iter = stmt.query_map([], account_from_row)?;
+
+    let mut users = Vec::new();
+    for user in user_iter {
+        users.push(user?);
+    }
+
+    Ok(users)
+}
+
+pub fn get_account_by_id(id: i32) -> Result<Option<Account>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM users WHERE id = ?1",
+        ACCOUNT_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map([id], account_from_row)?;
+
+    rows.next().transpose()
+}
+
+/// Usernames are unique regardless of case; `except_id` skips the user
+/// being edited.
+fn username_taken(conn: &Connection, username: &str, except_id: Option<i32>) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM users WHERE lower(username) = lower(?1) AND id IS NOT ?2",
+        rusqlite::params![username, except_id],
+        |row| row.get::<_, i32>(0),
+    )
+    .map(|count| count > 0)
+}
+
+fn admin_count(conn: &Connection) -> Result<i32> {
+    conn.query_row("SELECT COUNT(*) FROM users WHERE is_admin = 1", [], |row| {
+        row.get(0)
+    })
+}
+
+/// The unique index backs up `username_taken` when two saves race.
+fn user_write_error(e: rusqlite::Error) -> Box<dyn std::error::Error> {
+    match e.sqlite_error_code() {
+        Some(rusqlite::ErrorCode::ConstraintViolation) => Box::new(UserError::DuplicateUsername),
+        _ => Box::new(e),
+    }
+}
+
+pub fn add_user(
+    name: &str,
+    username: &str,
+    email: &str,
+    password: &str,
+    is_admin: bool,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let conn = establish_connection()?;
+    if username_taken(&conn, username, None)? {
+        return Err(Box::new(UserError::DuplicateUsername));
+    }
+    account::check_password(password)?;
+
+    let hashed_password = hash(password, DEFAULT_COST)?;
+    conn.execute(
+        "INSERT INTO users (name, username, email, password, is_admin) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![name, username, email, hashed_password, is_admin],
+    )
+    .map_err(user_write_error)?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+/// Deletes a user unless they are the last admin.
+pub fn delete_user(id: i32) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+
+    let is_admin: bool = tx.query_row("SELECT is_admin FROM users WHERE id = ?1", [id], |row| {
+        row.get(0)
+    })?;
+    if is_admin && admin_count(&tx)? <= 1 {
+        return Err(Box::new(UserError::LastAdmin));
+    }
+
+    tx.execute("DELETE FROM password_reset_tokens WHERE user_id = ?1", [id])?;
+    tx.execute("DELETE FROM api_tokens WHERE user_id = ?1", [id])?;
+    tx.execute("DELETE FROM users WHERE id = ?1", [id])?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Updates a user; an empty `password` keeps the current one. The last admin
+/// cannot lose the admin role.
+pub fn edit_user(
+    id: i32,
+    name: &str,
+    username: &str,
+    email: &str,
+    password: &str,
+    is_admin: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !password.is_empty() {
+        account::check_password(password)?;
+    }
+
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+
+    if username_taken(&tx, username, Some(id))? {
+        return Err(Box::new(UserError::DuplicateUsername));
+    }
+    let was_admin: bool =
+        tx.query_row("SELECT is_admin FROM users WHERE id = ?1", [id], |row| {
+            row.get(0)
+        })?;
+    if was_admin && !is_admin && admin_count(&tx)? <= 1 {
+        return Err(Box::new(UserError::LastAdmin));
+    }
+
+    tx.execute(
+        "UPDATE users SET name = ?1, username = ?2, email = ?3, is_admin = ?4 WHERE id = ?5",
+        rusqlite::params![name, username, email, is_admin, id],
+    )
+    .map_err(user_write_error)?;
+    if !password.is_empty() {
+        let hashed_password = hash(password, DEFAULT_COST)?;
+        tx.execute(
+            "UPDATE users SET password = ?1 WHERE id = ?2",
+            rusqlite::params![hashed_password, id],
+        )?;
+        tx.execute(
+            "DELETE FROM password_reset_tokens WHERE user_id = ?1 AND used_at IS NULL",
+            [id],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Looks an account up by username or, ignoring case, by e-mail address.
+pub fn find_account(login: &str) -> Result<Option<Account>> {
+    if login.is_empty() {
+        return Ok(None);
+    }
+
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, username, name, email, is_admin FROM users
+         WHERE username = ?1 OR (email <> '' AND lower(email) = lower(?1))
+         ORDER BY username = ?1 DESC
+         LIMIT 1",
+    )?;
+    let mut rows = stmt.query_map([login], account_from_row)?;
+
+    rows.next().transpose()
+}
+
+pub fn update_account(id: i32, name: &str, email: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE users SET name = ?1, email = ?2 WHERE id = ?3",
+        rusqlite::params![name, email, id],
+    )?;
+
+    Ok(())
+}
+
+/// Sets a new password for the user and voids their outstanding reset links.
+pub fn set_password(id: i32, password: &str) -> Result<(), Box<dyn std::error::Error>> {
+    account::check_password(password)?;
+    let hashed_password = hash(password, DEFAULT_COST)?;
+
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE users SET password = ?1 WHERE id = ?2",
+        rusqlite::params![hashed_password, id],
+    )?;
+    tx.execute(
+        "DELETE FROM password_reset_tokens WHERE user_id = ?1 AND used_at IS NULL",
+        [id],
+    )?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Stores a new reset token for the user. Earlier unused tokens stop working
+/// and long-expired ones are cleared out.
+pub fn add_password_reset_token(
+    user_id: i32,
+    token_hash: &str,
+    created_at: &str,
+    expires_at: &str,
+) -> Result<()> {
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM password_reset_tokens WHERE (user_id = ?1 AND used_at IS NULL) OR expires_at < ?2",
+        rusqlite::params![user_id, created_at],
+    )?;
+    tx.execute(
+        "INSERT INTO password_reset_tokens (user_id, token_hash, created_at, expires_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![user_id, token_hash, created_at, expires_at],
+    )?;
+    tx.commit()
+}
+
+/// The account an unused, unexpired reset token belongs to.
+pub fn get_password_reset_account(token_hash: &str, now: &str) -> Result<Option<Account>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT users.id, users.username, users.name, users.email, users.is_admin
+         FROM password_reset_tokens
+         JOIN users ON users.id = password_reset_tokens.user_id
+         WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2",
+    )?;
+    let mut rows = stmt.query_map([token_hash, now], account_from_row)?;
+
+    rows.next().transpose()
+}
+
+/// Uses up a reset token and sets the new password in one step. Returns
+/// `None` when the token is unknown, used or expired.
+pub fn reset_password(
+    token_hash: &str,
+    now: &str,
+    password: &str,
+) -> Result<Option<Account>, Box<dyn std::error::Error>> {
+    account::check_password(password)?;
+    let hashed_password = hash(password, DEFAULT_COST)?;
+
+    let mut conn = establish_connection()?;
+    let tx = conn.transaction()?;
+    let used = tx.execute(
+        "UPDATE password_reset_tokens SET used_at = ?2
+         WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2",
+        [token_hash, now],
+    )?;
+    if used == 0 {
+        return Ok(None);
+    }
+
+    let account = tx.query_row(
+        "SELECT users.id, users.username, users.name, users.email, users.is_admin
+         FROM password_reset_tokens
+         JOIN users ON users.id = password_reset_tokens.user_id
+         WHERE token_hash = ?1",
+        [token_hash],
+        account_from_row,
+    )?;
+    tx.execute(
+        "UPDATE users SET password = ?1 WHERE id = ?2",
+        rusqlite::params![hashed_password, account.id],
+    )?;
+    tx.execute(
+        "DELETE FROM password_reset_tokens WHERE user_id = ?1 AND used_at IS NULL",
+        [account.id],
+    )?;
+    tx.commit()?;
+
+    Ok(Some(account))
+}
+
+const API_TOKEN_COLUMNS: &str = "api_tokens.id, user_id, users.username, api_tokens.name, token_prefix, scopes, created_at, expires_at, last_used_at, revoked_at";
+
+fn api_token_from_row(row: &rusqlite::Row) -> Result<ApiToken> {
+    let scopes: String = row.get(5)?;
+    Ok(ApiToken {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        username: row.get(2)?,
+        name: row.get(3)?,
+        token_prefix: row.get(4)?,
+        scopes: scopes
+            .split(',')
+            .filter(|scope| !scope.is_empty())
+            .map(String::from)
+            .collect(),
+        created_at: row.get(6)?,
+        expires_at: row.get(7)?,
+        last_used_at: row.get(8)?,
+        revoked_at: row.get(9)?,
+    })
+}
+
+pub fn add_api_token(
+    user_id: i32,
+    name: &str,
+    token_hash: &str,
+    token_prefix: &str,
+    scopes: &str,
+    created_at: &str,
+    expires_at: Option<&str>,
+) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, created_at, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![user_id, name, token_hash, token_prefix, scopes, created_at, expires_at],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+/// Tokens of one user, or of everyone when `user_id` is `None`, newest first.
+pub fn get_api_tokens(user_id: Option<i32>) -> Result<Vec<ApiToken>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM api_tokens JOIN users ON users.id = api_tokens.user_id
+         WHERE ?1 IS NULL OR user_id = ?1
+         ORDER BY api_tokens.id DESC",
+        API_TOKEN_COLUMNS
+    ))?;
+    let token_iter = stmt.query_map([user_id], api_token_from_row)?;
+
+    let mut tokens = Vec::new();
+    for token in token_iter {
+        tokens.push(token?);
+    }
+
+    Ok(tokens)
+}
+
+pub fn get_api_token(id: i32) -> Result<ApiToken> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM api_tokens JOIN users ON users.id = api_tokens.user_id WHERE api_tokens.id = ?1",
+            API_TOKEN_COLUMNS
+        ),
+        [id],
+        api_token_from_row,
+    )
+}
+
+pub fn get_api_token_by_hash(token_hash: &str) -> Result<Option<ApiToken>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM api_tokens JOIN users ON users.id = api_tokens.user_id WHERE token_hash = ?1",
+        API_TOKEN_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map([token_hash], api_token_from_row)?;
+
+    rows.next().transpose()
+}
+
+pub fn touch_api_token(id: i32, used_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE api_tokens SET last_used_at = ?1 WHERE id = ?2",
+        rusqlite::params![used_at, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn revoke_api_token(id: i32, revoked_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE api_tokens SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+        rusqlite::params![revoked_at, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn add_audit_entry(entry: &AuditEntry) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO audit_log (created_at, actor, action, entity, entity_id, before_state, after_state, ip_address) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            entry.created_at,
+            entry.actor,
+            entry.action,
+            entry.entity,
+            entry.entity_id,
+            entry.before_state,
+            entry.after_state,
+            entry.ip_address
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn row_to_audit_entry(row: &rusqlite::Row) -> Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        created_at: row.get(1)?,
+        actor: row.get(2)?,
+        action: row.get(3)?,
+        entity: row.get(4)?,
+        entity_id: row.get(5)?,
+        before_state: row.get(6)?,
+        after_state: row.get(7)?,
+        ip_address: row.get(8)?,
+    })
+}
+
+const AUDIT_FILTER: &str = "(?1 IS NULL OR actor = ?1)
+   AND (?2 IS NULL OR action = ?2)
+   AND (?3 IS NULL OR entity = ?3)
+   AND (?4 IS NULL OR created_at >= ?4)
+   AND (?5 IS NULL OR created_at < date(?5, '+1 day'))";
+
+/// Audit entries matching the filter, newest first, with the total number
+/// of matches. A `page_size` of `None` returns every match.
+pub fn get_audit_entries(
+    filter: &AuditFilter,
+    page_size: Option<i32>,
+) -> Result<(Vec<AuditEntry>, i32), rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let limit = page_size.unwrap_or(-1);
+    let offset = page_size.map_or(0, |size| (filter.page() - 1) * size);
+    let params = rusqlite::params![
+        filter.actor(),
+        filter.action(),
+        filter.entity(),
+        filter.from(),
+        filter.to(),
+        limit,
+        offset
+    ];
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT * FROM audit_log WHERE {} ORDER BY id DESC LIMIT ?6 OFFSET ?7",
+        AUDIT_FILTER
+    ))?;
+    let entry_iter = stmt.query_map(params, row_to_audit_entry)?;
+
+    let mut entries = Vec::new();
+    for entry in entry_iter {
+        entries.push(entry?);
+    }
+
+    let total: i32 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM audit_log WHERE {}", AUDIT_FILTER),
+        &params[..5],
+        |row| row.get(0),
+    )?;
+
+    Ok((entries, total))
+}
+
+pub fn get_audit_actors() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT DISTINCT actor FROM audit_log ORDER BY actor")?;
+    let actor_iter = stmt.query_map([], |row| row.get(0))?;
+
+    let mut actors = Vec::new();
+    for actor in actor_iter {
+        actors.push(actor?);
+    }
+
+    Ok(actors)
+}
+
+/// Ids and IPs of audit entries written before `cutoff`.
+pub fn get_audit_ips_before(cutoff: &str) -> Result<Vec<(i32, String)>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, ip_address FROM audit_log WHERE created_at < ?1 AND ip_address != ''",
+    )?;
+    let row_iter = stmt.query_map([cutoff], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut rows = Vec::new();
+    for row in row_iter {
+        rows.push(row?);
+    }
+
+    Ok(rows)
+}
+
+pub fn set_audit_ip(id: i32, ip_address: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE audit_log SET ip_address = ?1 WHERE id = ?2",
+        rusqlite::params![ip_address, id],
+    )?;
+
+    Ok(())
+}
+
+const SUBSCRIPTION_COLUMNS: &str = "id, email, frequency, categories, token, created_at, confirmed_at, last_announcement_id, last_sent_at";
+
+/// Subscription categories are stored one per line, as admins may use commas
+/// in category names.
+fn split_categories(categories: String) -> Vec<String> {
+    categories
+        .lines()
+        .filter(|category| !category.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn subscription_from_row(row: &rusqlite::Row) -> Result<Subscription> {
+    Ok(Subscription {
+        id: row.get(0)?,
+        email: row.get(1)?,
+        frequency: row.get(2)?,
+        categories: split_categories(row.get(3)?),
+        token: row.get(4)?,
+        created_at: row.get(5)?,
+        confirmed_at: row.get(6)?,
+        last_announcement_id: row.get(7)?,
+        last_sent_at: row.get(8)?,
+    })
+}
+
+pub fn add_subscription(
+    email: &str,
+    frequency: &str,
+    categories: &[String],
+    token: &str,
+    created_at: &str,
+) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO subscriptions (email, frequency, categories, token, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![email, frequency, categories.join("\n"), token, created_at],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+fn find_subscription(column: &str, value: &str) -> Result<Option<Subscription>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM subscriptions WHERE {} = ?1",
+        SUBSCRIPTION_COLUMNS, column
+    ))?;
+    let mut rows = stmt.query_map([value], subscription_from_row)?;
+
+    rows.next().transpose()
+}
+
+pub fn get_subscription_by_email(email: &str) -> Result<Option<Subscription>> {
+    find_subscription("email", email)
+}
+
+pub fn get_subscription_by_token(token: &str) -> Result<Option<Subscription>> {
+    find_subscription("token", token)
+}
+
+/// Confirmed subscriptions with the given frequency.
+pub fn get_confirmed_subscriptions(frequency: &str) -> Result<Vec<Subscription>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM subscriptions WHERE frequency = ?1 AND confirmed_at IS NOT NULL ORDER BY id",
+        SUBSCRIPTION_COLUMNS
+    ))?;
+    let subscription_iter = stmt.query_map([frequency], subscription_from_row)?;
+
+    let mut subscriptions = Vec::new();
+    for subscription in subscription_iter {
+        subscriptions.push(subscription?);
+    }
+    Ok(subscriptions)
+}
+
+/// Marks the opt-in as done. Digests start with the announcements published
+/// after `last_announcement_id`.
+pub fn confirm_subscription(id: i32, confirmed_at: &str, last_announcement_id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE subscriptions SET confirmed_at = ?1, last_sent_at = ?1, last_announcement_id = ?2 WHERE id = ?3",
+        rusqlite::params![confirmed_at, last_announcement_id, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn edit_subscription(id: i32, frequency: &str, categories: &[String]) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE subscriptions SET frequency = ?1, categories = ?2 WHERE id = ?3",
+        rusqlite::params![frequency, categories.join("\n"), id],
+    )?;
+
+    Ok(())
+}
+
+/// Records that a digest covering announcements up to `last_announcement_id` went out.
+pub fn set_subscription_sent(id: i32, last_announcement_id: i32, sent_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE subscriptions SET last_announcement_id = ?1, last_sent_at = ?2 WHERE id = ?3",
+        rusqlite::params![last_announcement_id, sent_at, id],
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_subscription(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM subscriptions WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+pub fn delete_subscription_by_email(email: &str) -> Result<usize> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM subscriptions WHERE email = ?1", [email.trim()])
+}
+
+/// Deletes subscriptions created before `cutoff` that were never confirmed.
+pub fn delete_unconfirmed_subscriptions(cutoff: &str) -> Result<usize> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "DELETE FROM subscriptions WHERE confirmed_at IS NULL AND created_at < ?1",
+        [cutoff],
+    )
+}
+
+fn push_subscription_from_row(row: &rusqlite::Row) -> Result<PushSubscription> {
+    Ok(PushSubscription {
+        id: row.get(0)?,
+        endpoint: row.get(1)?,
+        p256dh: row.get(2)?,
+        auth: row.get(3)?,
+        categories: split_categories(row.get(4)?),
+        created_at: row.get(5)?,
+    })
+}
+
+/// Adds a browser, or updates its keys and categories if it subscribed before.
+pub fn save_push_subscription(subscription: &PushSubscription) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO push_subscriptions (endpoint, p256dh, auth, categories, created_at) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (endpoint) DO UPDATE SET p256dh = ?2, auth = ?3, categories = ?4",
+        rusqlite::params![
+            subscription.endpoint,
+            subscription.p256dh,
+            subscription.auth,
+            subscription.categories.join("\n"),
+            subscription.created_at
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_push_subscriptions() -> Result<Vec<PushSubscription>, rusqlite::Error> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, endpoint, p256dh, auth, categories, created_at FROM push_subscriptions ORDER BY id",
+    )?;
+    let subscription_iter = stmt.query_map([], push_subscription_from_row)?;
+
+    let mut subscriptions = Vec::new();
+    for subscription in subscription_iter {
+        subscriptions.push(subscription?);
+    }
+    Ok(subscriptions)
+}
+
+pub fn get_push_subscription(id: i32) -> Result<Option<PushSubscription>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, endpoint, p256dh, auth, categories, created_at FROM push_subscriptions WHERE id = ?1",
+    )?;
+    let mut rows = stmt.query_map([id], push_subscription_from_row)?;
+
+    rows.next().transpose()
+}
+
+pub fn delete_push_subscription(id: i32) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute("DELETE FROM push_subscriptions WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+pub fn delete_push_subscription_by_endpoint(endpoint: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "DELETE FROM push_subscriptions WHERE endpoint = ?1",
+        [endpoint],
+    )?;
+
+    Ok(())
+}
+
+const JOB_COLUMNS: &str =
+    "id, kind, payload, status, attempts, run_at, created_at, started_at, finished_at, last_error";
+
+fn job_from_row(row: &rusqlite::Row) -> Result<Job> {
+    Ok(Job {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        payload: row.get(2)?,
+        status: row.get(3)?,
+        attempts: row.get(4)?,
+        run_at: row.get(5)?,
+        created_at: row.get(6)?,
+        started_at: row.get(7)?,
+        finished_at: row.get(8)?,
+        last_error: row.get(9)?,
+    })
+}
+
+pub fn add_job(kind: &str, payload: &str, run_at: &str, created_at: &str) -> Result<i32> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "INSERT INTO jobs (kind, payload, run_at, created_at) VALUES (?1, ?2, ?3, ?4)",
+        [kind, payload, run_at, created_at],
+    )?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+/// Takes the oldest queued job that is due and marks it running, in one
+/// statement so two workers never get the same job.
+pub fn claim_job(now: &str) -> Result<Option<Job>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "UPDATE jobs SET status = 'running', attempts = attempts + 1, started_at = ?1
+         WHERE id = (SELECT id FROM jobs WHERE status = 'queued' AND run_at <= ?1 ORDER BY run_at, id LIMIT 1)
+         RETURNING {}",
+        JOB_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map([now], job_from_row)?;
+
+    rows.next().transpose()
+}
+
+pub fn finish_job(id: i32, finished_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE jobs SET status = 'done', finished_at = ?1, last_error = NULL WHERE id = ?2",
+        rusqlite::params![finished_at, id],
+    )?;
+
+    Ok(())
+}
+
+/// Puts a failed job back in the queue to be tried again at `run_at`.
+pub fn retry_job(id: i32, error: &str, run_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE jobs SET status = 'queued', run_at = ?1, last_error = ?2 WHERE id = ?3",
+        rusqlite::params![run_at, error, id],
+    )?;
+
+    Ok(())
+}
+
+/// Sets aside a job that has used up its attempts.
+pub fn fail_job(id: i32, error: &str, finished_at: &str) -> Result<()> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE jobs SET status = 'failed', finished_at = ?1, last_error = ?2 WHERE id = ?3",
+        rusqlite::params![finished_at, error, id],
+    )?;
+
+    Ok(())
+}
+
+/// Puts jobs left running by a previous process back in the queue.
+pub fn requeue_running_jobs() -> Result<usize> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "UPDATE jobs SET status = 'queued' WHERE status = 'running'",
+        [],
+    )
+}
+
+/// Gives a failed job a fresh set of attempts, starting at `run_at`.
+/// Returns whether there was such a job.
+pub fn requeue_failed_job(id: i32, run_at: &str) -> Result<bool> {
+    let conn = establish_connection()?;
+
+    let changed = conn.execute(
+        "UPDATE jobs SET status = 'queued', attempts = 0, run_at = ?1, finished_at = NULL
+         WHERE id = ?2 AND status = 'failed'",
+        rusqlite::params![run_at, id],
+    )?;
+
+    Ok(changed > 0)
+}
+
+pub fn delete_failed_job(id: i32) -> Result<bool> {
+    let conn = establish_connection()?;
+
+    let changed = conn.execute("DELETE FROM jobs WHERE id = ?1 AND status = 'failed'", [id])?;
+
+    Ok(changed > 0)
+}
+
+pub fn get_job(id: i32) -> Result<Job> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        [id],
+        job_from_row,
+    )
+}
+
+/// Jobs with the given status, the ones due or finished first.
+pub fn get_jobs(status: &str, limit: i32) -> Result<Vec<Job>> {
+    let conn = establish_connection()?;
+
+    let order = match status {
+        "queued" | "running" => "run_at, id",
+        _ => "finished_at DESC, id DESC",
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM jobs WHERE status = ?1 ORDER BY {} LIMIT ?2",
+        JOB_COLUMNS, order
+    ))?;
+    let jobs = stmt.query_map(rusqlite::params![status, limit], job_from_row)?;
+
+    jobs.collect()
+}
+
+/// How many jobs there are in each status.
+pub fn count_jobs_by_status() -> Result<HashMap<String, i64>> {
+    let conn = establish_connection()?;
+
+    let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM jobs GROUP BY status")?;
+    let counts = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    counts.collect()
+}
+
+/// When a job of this kind was last queued, for the periodic tasks.
+pub fn get_last_job_created_at(kind: &str) -> Result<Option<String>> {
+    let conn = establish_connection()?;
+
+    conn.query_row(
+        "SELECT MAX(created_at) FROM jobs WHERE kind = ?1",
+        [kind],
+        |row| row.get(0),
+    )
+}
+
+/// Deletes completed jobs finished before `cutoff`.
+pub fn delete_finished_jobs(cutoff: &str) -> Result<usize> {
+    let conn = establish_connection()?;
+
+    conn.execute(
+        "DELETE FROM jobs WHERE status = 'done' AND finished_at < ?1",
+        [cutoff],
+    )
 }
@@ -1,25 +1,449 @@
+use crate::src::db;
+use crate::src::feed;
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use crate::src::preview;
+use crate::src::safe_path;
+use actix_files::NamedFile;
+use actix_web::http::header::{
+    Charset, ContentDisposition, DispositionParam, DispositionType, ExtendedValue,
+};
 use actix_web::{web, HttpRequest, HttpResponse, Result};
-use std::path::PathBuf;
-use std::process::Command;
+use serde::Serialize;
+use serde_derive::Deserialize;
+use std::path::Path;
 use tokio::fs;
+use utoipa::ToSchema;
+use uuid::Uuid;
 
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/dokuman.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+pub const DOCS_DIR: &str = "../public/assets/docs";
+pub const ALLOWED_EXTENSIONS: [&str; 9] = [
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "txt",
+];
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Document {
+    pub id: i32,
+    pub title: String,
+    pub category: String,
+    pub academic_year: String,
+    pub version: String,
+    pub filename: String,
+    pub original_name: String,
+    pub upload_date: String,
+    pub change_note: String,
+}
+
+/// An earlier file of a document, kept when a new version replaces it.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct DocumentVersion {
+    pub id: i32,
+    pub document_id: i32,
+    pub version: String,
+    pub filename: String,
+    pub original_name: String,
+    pub upload_date: String,
+    pub change_note: String,
+}
+
+impl DocumentVersion {
+    /// Snapshot of the file a document currently points at.
+    pub fn from_document(document: &Document) -> Self {
+        DocumentVersion {
+            id: 0,
+            document_id: document.id,
+            version: document.version.clone(),
+            filename: document.filename.clone(),
+            original_name: document.original_name.clone(),
+            upload_date: document.upload_date.clone(),
+            change_note: document.change_note.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DocumentFilter {
+    category: Option<String>,
+    q: Option<String>,
+    main_page: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct DownloadOptions {
+    inline: Option<bool>,
+}
+
+pub fn document_extension(original_name: &str) -> Option<String> {
+    Path::new(original_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .filter(|ext| ALLOWED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Writes an uploaded document under a fresh name and returns that name.
+pub async fn save_document_file(extension: &str, bytes: &[u8]) -> std::io::Result<String> {
+    let filename = format!("{}.{}", Uuid::new_v4(), extension);
+    fs::create_dir_all(DOCS_DIR).await?;
+    fs::write(Path::new(DOCS_DIR).join(&filename), bytes).await?;
+    Ok(filename)
+}
+
+/// Stores the searchable text of a document, if its format can be read.
+pub fn index_document_text(id: i32, filename: &str) -> rusqlite::Result<()> {
+    let text = preview::extract_text(&Path::new(DOCS_DIR).join(filename)).unwrap_or_default();
+    db::set_document_text(id, &text)
+}
+
+/// Fills in search text for documents that were added before text extraction existed.
+pub fn backfill_document_text() -> rusqlite::Result<()> {
+    for document in db::get_documents_without_text()? {
+        index_document_text(document.id, &document.filename)?;
+    }
+    Ok(())
+}
+
+fn render_document(lang: Lang, document: &Document) -> String {
+    format!(
+        "<div class='flex flex-col items-center justify-center mt-10 mx-4'>
+            <div class='flex items-center justify-center w-20 h-20 bg-gray-100 rounded-full'>
+                <a href='/dokumanlar/{}'>
+                    <svg xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 24 24' stroke-width='1.5'
+                        stroke='currentColor' class='w-16 h-16'>
+                        <path stroke-linecap='round' stroke-linejoin='round'
+                            d='M19.5 14.25v-2.625a3.375 3.375 0 0 0-3.375-3.375h-1.5A1.125 1.125 0 0 1 13.5 7.125v-1.5a3.375 3.375 0 0 0-3.375-3.375H8.25m0 12.75h7.5m-7.5 3H12M10.5 2.25H5.625c-.621 0-1.125.504-1.125 1.125v17.25c0 .621.504 1.125 1.125 1.125h12.75c.621 0 1.125-.504 1.125-1.125V11.25a9 9 0 0 0-9-9Z' />
+                    </svg>
+                </a>
+            </div>
+            <a {}
+                class='mt-4 text-lg underline font-semibold text-black dark:text-gray-200'>{}
+            </a>
+            <small class='text-gray-600'>{} · {} · v{}</small>
+            <small class='text-gray-500'>{}</small>
+        </div>",
+        escape_html(&document.filename),
+        layout::link(lang, &format!("/dokumanlar/{}/onizleme", escape_html(&document.filename))),
+        escape_html(&document.title),
+        escape_html(&document.category),
+        escape_html(&document.academic_year),
+        escape_html(&document.version),
+        escape_html(&document.upload_date)
+    )
+}
+
+/// Cards for every document, as listed on the main page.
+pub fn render_all(lang: Lang) -> Result<String> {
+    let documents = db::get_documents(None, None)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    Ok(documents
+        .iter()
+        .map(|document| render_document(lang, document))
+        .collect())
+}
+
+fn render_category_button(
+    lang: Lang,
+    label: &str,
+    category: Option<&str>,
+    active: bool,
+) -> String {
+    let colour = if active {
+        "bg-blue-600 text-white"
+    } else {
+        "bg-white text-gray-700 hover:bg-gray-100"
+    };
+    let path = match category {
+        Some(category) => format!("/dokumanlar?category={}", feed::percent_encode(category)),
+        None => String::from("/dokumanlar"),
+    };
+
+    format!(
+        "<a {}
+            class='mx-1 mb-2 px-4 py-2 text-sm font-semibold border border-gray-300 rounded-lg {}'>{}</a>",
+        layout::link(lang, &path),
+        colour,
+        escape_html(label)
+    )
+}
+
+/// RSS and Atom links for the documents shown, narrowed to the selected category.
+fn render_feed_links(category: Option<&str>) -> String {
+    let query = category
+        .map(|category| format!("?category={}", feed::percent_encode(category)))
+        .unwrap_or_default();
+    let label = category
+        .map(|category| format!("{} ", escape_html(category)))
+        .unwrap_or_default();
+    [("rss", "RSS"), ("atom", "Atom")]
+        .iter()
+        .map(|(format, name)| {
+            format!(
+                "<a href='/dokumanlar/{}.xml{}' target='_blank' class='text-orange-600 hover:underline'>{}{}</a>",
+                format, query, label, name
+            )
+        })
+        .collect()
 }
 
-pub async fn doc_handler(filename: web::Path<String>) -> Result<HttpResponse> {
-    let filename_str = filename.into_inner();
-    let mut path: PathBuf = "../public/assets/docs".parse().unwrap();
-    path.push(&filename_str);
+pub async fn handler(req: HttpRequest, filter: web::Query<DocumentFilter>) -> Result<HttpResponse> {
+    let filter = filter.into_inner();
+    let category = filter.category.as_deref().filter(|c| !c.is_empty());
+    let query = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
 
-    let content = fs::read(path).await?;
+    let documents = db::get_documents(category, query)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let lang = Lang::of(&req);
+    let document_cards: String = documents
+        .iter()
+        .map(|document| render_document(lang, document))
+        .collect();
 
-    Ok(HttpResponse::Ok()
-        .header(
-            "Content-Disposition",
-            format!("attachment; filename={}", filename_str),
+    if filter.main_page.unwrap_or(false) {
+        return Ok(HttpResponse::Ok()
+            .content_type("text/html")
+            .body(document_cards));
+    }
+
+    let categories = db::get_document_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let mut category_buttons =
+        render_category_button(lang, i18n::t(lang, "list.all"), None, category.is_none());
+    for item in &categories {
+        category_buttons.push_str(&render_category_button(
+            lang,
+            item,
+            Some(item),
+            category == Some(item.as_str()),
+        ));
+    }
+
+    let mut content = layout::template(lang, "dokuman.html").await?;
+    content = content.replace("{{categories}}", &category_buttons);
+    content = content.replace("{{feeds}}", &render_feed_links(category));
+    content = content.replace("{{query}}", &query.unwrap_or("").replace('\'', "&#39;"));
+    let none = format!(
+        "<p class='mt-10 text-gray-600'>{}</p>",
+        i18n::t(lang, "documents.none")
+    );
+    content = content.replace(
+        "{{documents}}",
+        if document_cards.is_empty() {
+            &none
+        } else {
+            &document_cards
+        },
+    );
+
+    layout::page(&req, i18n::t(lang, "title.documents"), &content).await
+}
+
+/// ASCII fallback for the plain `filename` parameter; the UTF-8 name goes in `filename*`.
+fn ascii_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'ç' => 'c',
+            'Ç' => 'C',
+            'ğ' => 'g',
+            'Ğ' => 'G',
+            'ı' => 'i',
+            'İ' => 'I',
+            'ö' => 'o',
+            'Ö' => 'O',
+            'ş' => 's',
+            'Ş' => 'S',
+            'ü' => 'u',
+            'Ü' => 'U',
+            c if c.is_ascii() && c != '"' && c != '\\' && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+pub fn download_name(document: &Document) -> String {
+    match Path::new(&document.filename)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        Some(extension) => format!("{}.{}", document.title, extension),
+        None => document.title.clone(),
+    }
+}
+
+fn version_download_name(version: &DocumentVersion) -> String {
+    let title = db::get_document(version.document_id)
+        .map(|document| document.title)
+        .unwrap_or_else(|_| version.original_name.clone());
+    match Path::new(&version.filename)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        Some(extension) => format!("{} (v{}).{}", title, version.version, extension),
+        None => format!("{} (v{})", title, version.version),
+    }
+}
+
+/// List of earlier versions with download links, or an empty string if there are none.
+pub fn render_version_history(lang: Lang, versions: &[DocumentVersion]) -> String {
+    if versions.is_empty() {
+        return String::new();
+    }
+
+    let items: String = versions
+        .iter()
+        .map(|version| {
+            let note = if version.change_note.is_empty() {
+                String::new()
+            } else {
+                format!(" — {}", escape_html(&version.change_note))
+            };
+            format!(
+                "<li class='mb-1'><a href='/dokumanlar/{}' class='text-blue-600 underline'>v{}</a>
+                    <span class='text-gray-600'>({}){}</span></li>",
+                escape_html(&version.filename),
+                escape_html(&version.version),
+                escape_html(&version.upload_date),
+                note
+            )
+        })
+        .collect();
+
+    format!(
+        "<div class='mt-6 text-left'>
+            <h2 class='text-xl font-semibold mb-2'>{}</h2>
+            <ul class='list-disc pl-6'>{}</ul>
+        </div>",
+        i18n::t(lang, "documents.previous_versions"),
+        items
+    )
+}
+
+pub fn content_disposition(disposition: DispositionType, name: &str) -> ContentDisposition {
+    ContentDisposition {
+        disposition,
+        parameters: vec![
+            DispositionParam::Filename(ascii_filename(name)),
+            DispositionParam::FilenameExt(ExtendedValue {
+                charset: Charset::Ext(String::from("UTF-8")),
+                language_tag: None,
+                value: name.as_bytes().to_vec(),
+            }),
+        ],
+    }
+}
+
+pub async fn doc_handler(
+    filename: web::Path<String>,
+    options: web::Query<DownloadOptions>,
+) -> Result<NamedFile> {
+    let filename = filename.into_inner();
+    let path = safe_path::resolve(DOCS_DIR, &filename).map_err(safe_path::to_http_error)?;
+
+    let name = match db::get_document_by_filename(&filename) {
+        Ok(document) => download_name(&document),
+        Err(_) => match db::get_document_version_by_filename(&filename) {
+            Ok(version) => version_download_name(&version),
+            Err(_) => filename,
+        },
+    };
+
+    let file = NamedFile::open_async(path).await?;
+    let disposition = if options.inline.unwrap_or(false) && file.content_type().subtype() == "pdf" {
+        DispositionType::Inline
+    } else {
+        DispositionType::Attachment
+    };
+
+    Ok(file
+        .use_etag(true)
+        .use_last_modified(true)
+        .set_content_disposition(content_disposition(disposition, &name)))
+}
+
+pub async fn preview_handler(
+    req: HttpRequest,
+    filename: web::Path<String>,
+) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    let filename = filename.into_inner();
+    let path = safe_path::resolve(DOCS_DIR, &filename).map_err(safe_path::to_http_error)?;
+    let document = db::get_document_by_filename(&filename)
+        .map_err(|_| actix_web::error::ErrorNotFound("Document not found"))?;
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let body = match extension.as_str() {
+        "pdf" => format!(
+            "<iframe src='/dokumanlar/{}?inline=true' class='w-full h-[80vh] rounded-lg border' title='{}'></iframe>",
+            escape_html(&document.filename),
+            escape_html(&document.title)
+        ),
+        "docx" => {
+            let html = web::block(move || preview::docx_to_html(&path).map_err(|e| e.to_string()))
+                .await?
+                .unwrap_or_default();
+            if html.is_empty() {
+                format!("<p class='text-gray-600'>{}</p>", i18n::t(lang, "documents.no_preview"))
+            } else {
+                format!(
+                    "<div class='p-6 bg-white rounded-lg shadow text-left overflow-x-auto'>{}</div>",
+                    html
+                )
+            }
+        }
+        _ => format!(
+            "<p class='text-gray-600'>{}</p>",
+            i18n::t(lang, "documents.preview_unsupported")
+        ),
+    };
+
+    let change_note = if document.change_note.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p class='text-center text-gray-700 mb-6'><strong>{}</strong> {}</p>",
+            i18n::t(lang, "documents.changes"),
+            escape_html(&document.change_note)
         )
-        .body(content))
+    };
+    let versions = db::get_document_versions(document.id)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let html = format!(
+        "<a {}
+        class='py-4 px-5 text-gray-900 rounded max-w-xs md:bg-transparent flex items-center' aria-current='page'>
+        <svg class='w-6 h-6 text-gray-800 dark:text-white' aria-hidden='true' xmlns='http://www.w3.org/2000/svg'
+            fill='none' viewBox='0 0 8 14'>
+            <path stroke='currentColor' stroke-linecap='round' stroke-linejoin='round' stroke-width='2'
+                d='M7 1 1.3 6.326a.91.91 0 0 0 0 1.348L7 13' />
+        </svg>
+        </a>
+        <div class='max-w-screen-lg mx-auto p-5 sm:p-10'>
+            <h1 class='text-3xl font-semibold text-center mb-2'>{}</h1>
+            <p class='text-center text-gray-600 mb-6'>{} · {} · v{} · {}</p>
+            <div class='flex justify-center mb-6'>
+                <a href='/dokumanlar/{}'
+                    class='inline-flex items-center justify-center px-4 py-2 text-base font-medium text-white bg-blue-600 border border-transparent rounded-md shadow-sm hover:bg-blue-700'>{}</a>
+            </div>
+            {}
+            {}
+            {}
+        </div>",
+        layout::link(lang, "/dokumanlar"),
+        escape_html(&document.title),
+        escape_html(&document.category),
+        escape_html(&document.academic_year),
+        escape_html(&document.version),
+        escape_html(&document.upload_date),
+        escape_html(&document.filename),
+        i18n::t(lang, "documents.download"),
+        change_note,
+        body,
+        render_version_history(lang, &versions)
+    );
+
+    layout::page(&req, &document.title, &html).await
 }
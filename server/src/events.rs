@@ -0,0 +1,652 @@
+use crate::src::db;
+use crate::src::feed;
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_derive::Deserialize;
+
+const STORED_FORMAT: &str = "%Y-%m-%d %H:%M";
+/// Event times are entered as wall-clock time in this zone.
+const TIMEZONE: &str = "Europe/Istanbul";
+/// Catalogue keys of the month names, January first.
+const MONTHS: [&str; 12] = [
+    "month.january",
+    "month.february",
+    "month.march",
+    "month.april",
+    "month.may",
+    "month.june",
+    "month.july",
+    "month.august",
+    "month.september",
+    "month.october",
+    "month.november",
+    "month.december",
+];
+/// Catalogue keys of the weekday abbreviations, Monday first.
+const WEEKDAYS: [&str; 7] = [
+    "weekday.monday",
+    "weekday.tuesday",
+    "weekday.wednesday",
+    "weekday.thursday",
+    "weekday.friday",
+    "weekday.saturday",
+    "weekday.sunday",
+];
+/// How many events a calendar cell lists before "+N daha".
+const EVENTS_PER_DAY: usize = 3;
+
+/// A dated happening such as an exam week, a registration window or a seminar.
+#[derive(Clone, Serialize)]
+pub struct Event {
+    pub id: i32,
+    pub title: String,
+    /// Plain text; line breaks are kept.
+    pub description: String,
+    pub category: String,
+    pub location: String,
+    /// Local time, `YYYY-MM-DD HH:MM`; 00:00 for all-day events.
+    pub starts_at: String,
+    /// Same format; the last day for all-day events.
+    pub ends_at: Option<String>,
+    pub all_day: bool,
+    pub updated_at: String,
+}
+
+fn parse(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, STORED_FORMAT).ok()
+}
+
+impl Event {
+    pub fn start(&self) -> NaiveDateTime {
+        parse(&self.starts_at).unwrap_or_default()
+    }
+
+    pub fn end(&self) -> Option<NaiveDateTime> {
+        self.ends_at.as_deref().and_then(parse)
+    }
+
+    /// Last day the event takes place on.
+    pub fn last_day(&self) -> NaiveDate {
+        self.end().unwrap_or_else(|| self.start()).date()
+    }
+
+    pub fn is_on(&self, day: NaiveDate) -> bool {
+        self.start().date() <= day && day <= self.last_day()
+    }
+
+    /// e.g. "3 Ekim 2026, 10:00 - 12:00" or "1 Eylül 2026 - 5 Eylül 2026".
+    pub fn when(&self, lang: Lang) -> String {
+        let start = self.start();
+        let last_day = self.last_day();
+        let mut text = if last_day == start.date() {
+            long_date(lang, start.date())
+        } else {
+            format!(
+                "{} - {}",
+                long_date(lang, start.date()),
+                long_date(lang, last_day)
+            )
+        };
+        if !self.all_day {
+            text.push_str(&format!(", {}", start.format("%H:%M")));
+            if let Some(end) = self.end() {
+                text.push_str(&format!(" - {}", end.format("%H:%M")));
+            }
+        }
+        text
+    }
+}
+
+fn month_name(lang: Lang, month: NaiveDate) -> &'static str {
+    i18n::t(lang, MONTHS[month.month0() as usize])
+}
+
+fn long_date(lang: Lang, day: NaiveDate) -> String {
+    format!("{} {} {}", day.day(), month_name(lang, day), day.year())
+}
+
+/// The add and edit forms of the dashboard.
+#[derive(Deserialize)]
+pub struct EventForm {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub category: String,
+    #[serde(default)]
+    pub location: String,
+    /// `YYYY-MM-DD`.
+    pub start_date: String,
+    /// `HH:MM`; ignored for all-day events.
+    #[serde(default)]
+    pub start_time: String,
+    #[serde(default)]
+    pub end_date: String,
+    #[serde(default)]
+    pub end_time: String,
+    /// Checkbox; present only when ticked.
+    pub all_day: Option<String>,
+}
+
+impl EventForm {
+    /// Checks the form and turns it into an event, or says what is wrong.
+    pub fn to_event(&self, id: i32) -> Result<Event, String> {
+        let all_day = self.all_day.is_some();
+        let title = self.title.trim();
+        let category = self.category.trim();
+        if title.is_empty() || category.is_empty() {
+            return Err(String::from("Başlık ve kategori boş bırakılamaz."));
+        }
+
+        let date = |value: &str| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok();
+        let time = |value: &str| chrono::NaiveTime::parse_from_str(value.trim(), "%H:%M").ok();
+        let midnight = chrono::NaiveTime::MIN;
+
+        let start_date = date(&self.start_date).ok_or("Geçerli bir başlangıç tarihi girin.")?;
+        let start = if all_day {
+            start_date.and_time(midnight)
+        } else {
+            start_date.and_time(
+                time(&self.start_time)
+                    .ok_or("Tüm gün sürmeyen etkinlikler için başlangıç saati girin.")?,
+            )
+        };
+
+        let end = match (
+            self.end_date.trim().is_empty(),
+            self.end_time.trim().is_empty(),
+        ) {
+            (true, true) => None,
+            _ if all_day => Some(
+                date(&self.end_date)
+                    .ok_or("Geçerli bir bitiş tarihi girin.")?
+                    .and_time(midnight),
+            ),
+            _ => {
+                // An end time alone means the event ends on its first day.
+                let end_date = match self.end_date.trim() {
+                    "" => start_date,
+                    value => date(value).ok_or("Geçerli bir bitiş tarihi girin.")?,
+                };
+                let end_time = time(&self.end_time).ok_or("Geçerli bir bitiş saati girin.")?;
+                Some(end_date.and_time(end_time))
+            }
+        };
+        if end.is_some_and(|end| end < start) {
+            return Err(String::from("Bitiş, başlangıçtan önce olamaz."));
+        }
+
+        Ok(Event {
+            id,
+            title: title.to_string(),
+            description: self.description.trim().to_string(),
+            category: category.to_string(),
+            location: self.location.trim().to_string(),
+            starts_at: start.format(STORED_FORMAT).to_string(),
+            ends_at: end.map(|end| end.format(STORED_FORMAT).to_string()),
+            all_day,
+            updated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CalendarQuery {
+    /// `YYYY-MM`; the current month when left out.
+    month: Option<String>,
+    category: Option<String>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Address of the calendar for a month and, optionally, a category.
+fn calendar_path(month: NaiveDate, category: Option<&str>) -> String {
+    let mut path = format!("/etkinlikler?month={}", month.format("%Y-%m"));
+    if let Some(category) = category {
+        path.push_str(&format!("&amp;category={}", feed::percent_encode(category)));
+    }
+    path
+}
+
+fn month_link(lang: Lang, month: NaiveDate, category: Option<&str>, label: &str) -> String {
+    format!(
+        "<a {}
+            class='px-4 py-2 text-sm font-semibold bg-white border border-gray-300 rounded-lg hover:bg-gray-100'>{}</a>",
+        layout::link(lang, &calendar_path(month, category)),
+        label
+    )
+}
+
+fn render_category_button(
+    lang: Lang,
+    month: NaiveDate,
+    label: &str,
+    category: Option<&str>,
+    active: bool,
+) -> String {
+    let colour = if active {
+        "bg-blue-600 text-white"
+    } else {
+        "bg-white text-gray-700 hover:bg-gray-100"
+    };
+    format!(
+        "<a {}
+            class='mx-1 mb-2 px-4 py-2 text-sm font-semibold border border-gray-300 rounded-lg {}'>{}</a>",
+        layout::link(lang, &calendar_path(month, category)),
+        colour,
+        escape_html(label)
+    )
+}
+
+/// Month grid with weeks starting on Monday.
+fn render_month(lang: Lang, month: NaiveDate, events: &[Event]) -> String {
+    let today = Local::now().date_naive();
+    let mut html = String::from("<div class='grid grid-cols-7 gap-px bg-gray-300 border border-gray-300 rounded-lg overflow-hidden text-left'>");
+    for weekday in WEEKDAYS {
+        html.push_str(&format!(
+            "<div class='bg-gray-100 py-2 text-center text-xs font-bold text-gray-700'>{}</div>",
+            i18n::t(lang, weekday)
+        ));
+    }
+
+    for _ in 0..month.weekday().num_days_from_monday() {
+        html.push_str("<div class='bg-gray-50 min-h-24'></div>");
+    }
+    for day in month
+        .iter_days()
+        .take_while(|day| day.month() == month.month())
+    {
+        let day_events: Vec<&Event> = events.iter().filter(|event| event.is_on(day)).collect();
+        let number = if day == today {
+            format!(
+                "<span class='inline-flex items-center justify-center w-6 h-6 rounded-full bg-blue-600 text-white'>{}</span>",
+                day.day()
+            )
+        } else {
+            day.day().to_string()
+        };
+
+        let mut entries: String = day_events
+            .iter()
+            .take(EVENTS_PER_DAY)
+            .map(|event| {
+                let time = if event.all_day || event.start().date() != day {
+                    String::new()
+                } else {
+                    format!("{} ", event.start().format("%H:%M"))
+                };
+                format!(
+                    "<button type='button' onclick=\"document.getElementById('etkinlik-{}').scrollIntoView({{behavior: 'smooth'}})\" title='{}'
+                        class='block w-full text-left truncate text-xs px-1 mb-1 rounded bg-blue-100 text-blue-900 hover:bg-blue-200'>{}{}</button>",
+                    event.id,
+                    escape_html(&event.title),
+                    time,
+                    escape_html(&event.title)
+                )
+            })
+            .collect();
+        if day_events.len() > EVENTS_PER_DAY {
+            entries.push_str(&format!(
+                "<span class='block text-xs text-gray-500 px-1'>{}</span>",
+                i18n::t(lang, "events.more")
+                    .replace("{count}", &(day_events.len() - EVENTS_PER_DAY).to_string())
+            ));
+        }
+
+        html.push_str(&format!(
+            "<div class='bg-white min-h-24 p-1'><div class='text-sm font-semibold text-gray-700 mb-1'>{}</div>{}</div>",
+            number, entries
+        ));
+    }
+
+    html.push_str("</div>");
+    html
+}
+
+fn render_event(lang: Lang, event: &Event) -> String {
+    let location = if event.location.is_empty() {
+        String::new()
+    } else {
+        format!(" · {}", escape_html(&event.location))
+    };
+    let description = if event.description.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p class='mt-2 text-gray-700'>{}</p>",
+            escape_html(&event.description).replace('\n', "<br>")
+        )
+    };
+    format!(
+        "<li id='etkinlik-{}' class='p-4 bg-white rounded-lg shadow text-left'>
+            <span class='text-xs font-semibold uppercase text-blue-700'>{}</span>
+            <h3 class='text-lg font-bold'>{}</h3>
+            <p class='text-sm text-gray-600'>{}{}</p>
+            {}
+        </li>",
+        event.id,
+        escape_html(&event.category),
+        escape_html(&event.title),
+        event.when(lang),
+        location,
+        description
+    )
+}
+
+fn render_feed_links(lang: Lang, category: Option<&str>) -> String {
+    let query = category
+        .map(|category| format!("?category={}", feed::percent_encode(category)))
+        .unwrap_or_default();
+    let label = match category {
+        Some(category) => {
+            i18n::t(lang, "events.subscribe_category").replace("{category}", &escape_html(category))
+        }
+        None => i18n::t(lang, "events.subscribe").to_string(),
+    };
+    let address = format!("{}/etkinlikler.ics{}", feed::site_url(), query);
+    format!(
+        "<a href='/etkinlikler.ics{}' class='text-orange-600 hover:underline'>{} (.ics)</a>
+        <span class='block text-xs text-gray-500 mt-1'>{} {}</span>",
+        query,
+        label,
+        i18n::t(lang, "events.subscribe_hint"),
+        escape_html(&address)
+    )
+}
+
+/// Public calendar: one month as a grid, followed by that month's events.
+pub async fn handler(req: HttpRequest, query: web::Query<CalendarQuery>) -> Result<HttpResponse> {
+    let category = non_empty(&query.category);
+    let month = non_empty(&query.month)
+        .and_then(|month| NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok())
+        .unwrap_or_else(|| Local::now().date_naive().with_day(1).unwrap());
+    let next_month = month.checked_add_months(chrono::Months::new(1)).unwrap();
+    let previous_month = month.checked_sub_months(chrono::Months::new(1)).unwrap();
+
+    let events = db::get_events(
+        Some(&month.format("%Y-%m-%d 00:00").to_string()),
+        Some(&next_month.format("%Y-%m-%d 00:00").to_string()),
+        category,
+    )
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let categories = db::get_event_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let lang = Lang::of(&req);
+    let mut category_buttons = render_category_button(
+        lang,
+        month,
+        i18n::t(lang, "list.all"),
+        None,
+        category.is_none(),
+    );
+    for item in &categories {
+        category_buttons.push_str(&render_category_button(
+            lang,
+            month,
+            item,
+            Some(item),
+            category == Some(item.as_str()),
+        ));
+    }
+
+    let list: String = events
+        .iter()
+        .map(|event| render_event(lang, event))
+        .collect();
+    let mut content = layout::template(lang, "etkinlikler.html").await?;
+    content = content.replace(
+        "{{month}}",
+        &format!("{} {}", month_name(lang, month), month.year()),
+    );
+    content = content.replace(
+        "{{previous}}",
+        &month_link(
+            lang,
+            previous_month,
+            category,
+            i18n::t(lang, "events.previous_month"),
+        ),
+    );
+    content = content.replace(
+        "{{next}}",
+        &month_link(
+            lang,
+            next_month,
+            category,
+            i18n::t(lang, "events.next_month"),
+        ),
+    );
+    content = content.replace("{{categories}}", &category_buttons);
+    content = content.replace("{{calendar}}", &render_month(lang, month, &events));
+    let none = format!(
+        "<p class='text-gray-600'>{}</p>",
+        i18n::t(lang, "events.none")
+    );
+    content = content.replace("{{events}}", if list.is_empty() { &none } else { &list });
+    content = content.replace("{{feeds}}", &render_feed_links(lang, category));
+
+    layout::page(&req, i18n::t(lang, "title.events"), &content).await
+}
+
+/// Escapes a TEXT value (RFC 5545, 3.3.11).
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets without splitting a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Turns a time written by this server (e.g. `updated_at`) into UTC.
+fn utc(time: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| time.and_utc())
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn local(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn updated_at(event: &Event) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(&event.updated_at, "%Y-%m-%d %H:%M:%S").unwrap_or_default()
+}
+
+/// The events as an iCalendar document.
+pub fn to_ics(events: &[Event], name: &str) -> String {
+    let site = feed::site_url();
+    let host = site
+        .split("://")
+        .last()
+        .and_then(|rest| rest.split([':', '/']).next())
+        .unwrap_or("localhost")
+        .to_string();
+
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//CÜ Sivas TBMYO//Etkinlik Takvimi//TR"),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("METHOD:PUBLISH"),
+        format!("X-WR-CALNAME:{}", ics_text(name)),
+        format!("X-WR-TIMEZONE:{}", TIMEZONE),
+        // Turkey has kept UTC+3 all year since 2016, so a single STANDARD
+        // rule covers every event the calendar can hold.
+        String::from("BEGIN:VTIMEZONE"),
+        format!("TZID:{}", TIMEZONE),
+        String::from("BEGIN:STANDARD"),
+        String::from("DTSTART:19700101T000000"),
+        String::from("TZOFFSETFROM:+0300"),
+        String::from("TZOFFSETTO:+0300"),
+        String::from("TZNAME:+03"),
+        String::from("END:STANDARD"),
+        String::from("END:VTIMEZONE"),
+    ];
+    for event in events {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:event-{}@{}", event.id, host));
+        lines.push(format!("DTSTAMP:{}", utc(updated_at(event))));
+        lines.push(format!("LAST-MODIFIED:{}", utc(updated_at(event))));
+        if event.all_day {
+            // DTEND is exclusive for dates.
+            let end = event.last_day().succ_opt().unwrap_or(event.last_day());
+            lines.push(format!(
+                "DTSTART;VALUE=DATE:{}",
+                event.start().format("%Y%m%d")
+            ));
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        } else {
+            lines.push(format!(
+                "DTSTART;TZID={}:{}",
+                TIMEZONE,
+                local(event.start())
+            ));
+            if let Some(end) = event.end() {
+                lines.push(format!("DTEND;TZID={}:{}", TIMEZONE, local(end)));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", ics_text(&event.title)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ics_text(&event.description)));
+        }
+        if !event.location.is_empty() {
+            lines.push(format!("LOCATION:{}", ics_text(&event.location)));
+        }
+        lines.push(format!("CATEGORIES:{}", ics_text(&event.category)));
+        lines.push(format!("URL:{}/etkinlikler", site));
+        lines.push(String::from("END:VEVENT"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[derive(Deserialize)]
+pub struct IcsQuery {
+    category: Option<String>,
+}
+
+/// Subscribable `.ics` feed of all events, or of one category.
+pub async fn ics_handler(req: HttpRequest, query: web::Query<IcsQuery>) -> Result<HttpResponse> {
+    let category = non_empty(&query.category);
+    let events = db::get_events(None, None, category)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    let name = match category {
+        Some(category) => format!("{} - CÜ Sivas TBMYO Bilgisayar Teknolojileri", category),
+        None => String::from("CÜ Sivas TBMYO Bilgisayar Teknolojileri Etkinlikleri"),
+    };
+    let last_modified = events
+        .iter()
+        .map(updated_at)
+        .max()
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .unwrap_or_else(Local::now);
+
+    Ok(feed::cached_response(
+        &req,
+        to_ics(&events, &name),
+        "text/calendar; charset=utf-8",
+        last_modified,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(starts_at: &str, ends_at: Option<&str>, all_day: bool) -> Event {
+        Event {
+            id: 7,
+            title: String::from("Vize haftası"),
+            description: String::new(),
+            category: String::from("Sınav"),
+            location: String::new(),
+            starts_at: starts_at.to_string(),
+            ends_at: ends_at.map(str::to_string),
+            all_day,
+            updated_at: String::from("2026-10-01 09:00:00"),
+        }
+    }
+
+    #[test]
+    fn ics_text_escapes_special_characters() {
+        assert_eq!(ics_text("a\\b; c, d\r\ne\nf"), "a\\\\b\\; c\\, d\\ne\\nf");
+        assert_eq!(ics_text("Sınav haftası"), "Sınav haftası");
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        assert_eq!(fold("SUMMARY:Vize"), "SUMMARY:Vize\r\n");
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets() {
+        let line = format!("DESCRIPTION:{}", "a".repeat(100));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn folding_keeps_characters_whole() {
+        let line = format!("SUMMARY:{}", "ş".repeat(60));
+        let folded = fold(&line);
+        for part in folded.trim_end_matches("\r\n").split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn timed_events_use_istanbul_wall_clock_time() {
+        let ics = to_ics(
+            &[event("2026-10-19 10:00", Some("2026-10-19 12:30"), false)],
+            "Etkinlikler",
+        );
+        assert!(ics.contains("BEGIN:VTIMEZONE\r\nTZID:Europe/Istanbul\r\n"));
+        assert!(ics.contains("TZOFFSETTO:+0300\r\n"));
+        assert!(ics.contains("DTSTART;TZID=Europe/Istanbul:20261019T100000\r\n"));
+        assert!(ics.contains("DTEND;TZID=Europe/Istanbul:20261019T123000\r\n"));
+    }
+
+    #[test]
+    fn all_day_events_end_the_day_after() {
+        let ics = to_ics(
+            &[event("2026-11-02 00:00", Some("2026-11-06 00:00"), true)],
+            "Etkinlikler",
+        );
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261102\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20261107\r\n"));
+    }
+}
@@ -0,0 +1,443 @@
+use crate::src::config;
+use crate::src::db;
+use crate::src::docs;
+use crate::src::html::{escape_html, excerpt};
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use serde_derive::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// How many of the newest items a feed carries.
+const FEED_SIZE: i32 = 20;
+const SUMMARY_LENGTH: usize = 280;
+/// Feed readers poll often; let them and proxies reuse a copy for a while.
+const CACHE_CONTROL: &str = "public, max-age=900";
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Rss,
+    Atom,
+}
+
+impl Format {
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Rss => "application/rss+xml; charset=utf-8",
+            Format::Atom => "application/atom+xml; charset=utf-8",
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Format::Rss => "rss.xml",
+            Format::Atom => "atom.xml",
+        }
+    }
+}
+
+/// A file attached to an item: a post's image or a document's file.
+pub struct Enclosure {
+    pub url: String,
+    pub mime: String,
+    pub length: u64,
+}
+
+pub struct FeedItem {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub summary: String,
+    pub author: String,
+    pub published: DateTime<Local>,
+    pub enclosure: Option<Enclosure>,
+}
+
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    /// The page the feed mirrors.
+    pub link: String,
+    /// The feed's own address.
+    pub self_link: String,
+    pub items: Vec<FeedItem>,
+}
+
+pub fn site_url() -> String {
+    config::get()
+        .mail
+        .site_url
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Turns the stored `dd-mm-yyyy` into midnight of that day.
+fn published(date: &str) -> DateTime<Local> {
+    NaiveDate::parse_from_str(date, "%d-%m-%Y")
+        .ok()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .unwrap_or_else(Local::now)
+}
+
+/// Plain-text start of an HTML body, cut at a word boundary.
+pub fn summary(content: &str) -> String {
+    excerpt(content, SUMMARY_LENGTH)
+}
+
+/// Describes a file under `../public`, e.g. `/assets/image/duyuru.png`.
+fn enclosure(url: &str) -> Option<Enclosure> {
+    let path = format!("../public{}", url);
+    let length = std::fs::metadata(&path).ok()?.len();
+    let extension = Path::new(url).extension()?.to_str()?;
+    Some(Enclosure {
+        url: format!("{}{}", site_url(), url),
+        mime: actix_files::file_extension_to_mime(extension).to_string(),
+        length,
+    })
+}
+
+impl Feed {
+    pub fn to_rss(&self) -> String {
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">
+<channel>
+<title>{}</title>
+<link>{}</link>
+<description>{}</description>
+<language>tr</language>
+<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>
+",
+            escape_html(&self.title),
+            escape_html(&self.link),
+            escape_html(&self.description),
+            escape_html(&self.self_link)
+        );
+        if !self.items.is_empty() {
+            xml.push_str(&format!(
+                "<lastBuildDate>{}</lastBuildDate>\n",
+                self.last_modified().to_rfc2822()
+            ));
+        }
+
+        for item in &self.items {
+            xml.push_str(&format!(
+                "<item>
+<title>{}</title>
+<link>{}</link>
+<guid isPermaLink=\"false\">{}</guid>
+<pubDate>{}</pubDate>
+<dc:creator>{}</dc:creator>
+<description>{}</description>
+",
+                escape_html(&item.title),
+                escape_html(&item.link),
+                escape_html(&item.id),
+                item.published.to_rfc2822(),
+                escape_html(&item.author),
+                escape_html(&item.summary)
+            ));
+            if let Some(enclosure) = &item.enclosure {
+                xml.push_str(&format!(
+                    "<enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>\n",
+                    escape_html(&enclosure.url),
+                    enclosure.length,
+                    escape_html(&enclosure.mime)
+                ));
+            }
+            xml.push_str("</item>\n");
+        }
+
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    pub fn to_atom(&self) -> String {
+        let updated = self.last_modified();
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"tr\">
+<id>{}</id>
+<title>{}</title>
+<subtitle>{}</subtitle>
+<link href=\"{}\"/>
+<link href=\"{}\" rel=\"self\" type=\"application/atom+xml\"/>
+<updated>{}</updated>
+",
+            escape_html(&self.self_link),
+            escape_html(&self.title),
+            escape_html(&self.description),
+            escape_html(&self.link),
+            escape_html(&self.self_link),
+            updated.to_rfc3339()
+        );
+
+        for item in &self.items {
+            xml.push_str(&format!(
+                "<entry>
+<id>{}</id>
+<title>{}</title>
+<link href=\"{}\"/>
+<published>{}</published>
+<updated>{}</updated>
+<author><name>{}</name></author>
+<summary>{}</summary>
+",
+                escape_html(&item.id),
+                escape_html(&item.title),
+                escape_html(&item.link),
+                item.published.to_rfc3339(),
+                item.published.to_rfc3339(),
+                escape_html(&item.author),
+                escape_html(&item.summary)
+            ));
+            if let Some(enclosure) = &item.enclosure {
+                xml.push_str(&format!(
+                    "<link rel=\"enclosure\" href=\"{}\" length=\"{}\" type=\"{}\"/>\n",
+                    escape_html(&enclosure.url),
+                    enclosure.length,
+                    escape_html(&enclosure.mime)
+                ));
+            }
+            xml.push_str("</entry>\n");
+        }
+
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    fn last_modified(&self) -> DateTime<Local> {
+        self.items
+            .iter()
+            .map(|item| item.published)
+            .max()
+            .unwrap_or_else(Local::now)
+    }
+}
+
+fn respond(req: &HttpRequest, feed: Feed, format: Format) -> HttpResponse {
+    let body = match format {
+        Format::Rss => feed.to_rss(),
+        Format::Atom => feed.to_atom(),
+    };
+    cached_response(req, body, format.content_type(), feed.last_modified())
+}
+
+/// Serves a subscribable document with an `ETag` and `Last-Modified`,
+/// answering conditional requests whose copy is still current with
+/// `304 Not Modified`.
+pub fn cached_response(
+    req: &HttpRequest,
+    body: String,
+    content_type: &str,
+    last_modified: DateTime<Local>,
+) -> HttpResponse {
+    let etag = format!("\"{:x}\"", Sha256::digest(body.as_bytes()));
+    let last_modified = last_modified
+        .with_timezone(&chrono::Utc)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string();
+
+    let header_value = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    // If-None-Match wins over If-Modified-Since when both are sent.
+    let not_modified = match header_value(header::IF_NONE_MATCH) {
+        Some(tags) => tags
+            .split(',')
+            .any(|tag| tag.trim() == etag || tag.trim() == "*"),
+        None => header_value(header::IF_MODIFIED_SINCE) == Some(last_modified.as_str()),
+    };
+
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::LAST_MODIFIED, last_modified));
+    if not_modified {
+        response.finish()
+    } else {
+        response.content_type(content_type).body(body)
+    }
+}
+
+fn announcements_feed(format: Format) -> rusqlite::Result<Feed> {
+    let site = site_url();
+    let (announcements, _) = db::get_announcements(1, FEED_SIZE)?;
+    Ok(Feed {
+        title: String::from("Duyurular - CÜ Sivas TBMYO Bilgisayar Teknolojileri Bölümü"),
+        description: String::from("Bilgisayar Teknolojileri Bölümü duyuruları"),
+        link: format!("{}/duyurular", site),
+        self_link: format!("{}/duyurular/{}", site, format.file_name()),
+        items: announcements
+            .into_iter()
+            .map(|announcement| FeedItem {
+                id: format!("{}/announcement/{}", site, announcement.id),
+                link: format!("{}/announcement/{}", site, announcement.id),
+                summary: summary(&announcement.content),
+                published: published(&announcement.date),
+                enclosure: enclosure(&announcement.image),
+                title: announcement.title,
+                author: announcement.author,
+            })
+            .collect(),
+    })
+}
+
+fn articles_feed(format: Format) -> rusqlite::Result<Feed> {
+    let site = site_url();
+    let (articles, _) = db::get_articles(1, FEED_SIZE)?;
+    Ok(Feed {
+        title: String::from("Makaleler - CÜ Sivas TBMYO Bilgisayar Teknolojileri Bölümü"),
+        description: String::from("Bilgisayar Teknolojileri Bölümü makaleleri"),
+        link: format!("{}/makaleler", site),
+        self_link: format!("{}/makaleler/{}", site, format.file_name()),
+        items: articles
+            .into_iter()
+            .map(|article| FeedItem {
+                id: format!("{}/article/{}", site, article.id),
+                link: format!("{}/article/{}", site, article.id),
+                summary: summary(&article.content),
+                published: published(&article.date),
+                enclosure: enclosure(&article.image),
+                title: article.title,
+                author: article.author,
+            })
+            .collect(),
+    })
+}
+
+/// Newest documents, optionally of one category, each enclosing its file.
+fn documents_feed(format: Format, category: Option<&str>) -> rusqlite::Result<Feed> {
+    let site = site_url();
+    let mut documents = db::get_documents(category, None)?;
+    documents.sort_by_key(|document| std::cmp::Reverse(published(&document.upload_date)));
+    documents.truncate(FEED_SIZE as usize);
+
+    let (title, self_link) = match category {
+        Some(category) => (
+            format!("Dokümanlar: {}", category),
+            format!(
+                "{}/dokumanlar/{}?category={}",
+                site,
+                format.file_name(),
+                percent_encode(category)
+            ),
+        ),
+        None => (
+            String::from("Dokümanlar"),
+            format!("{}/dokumanlar/{}", site, format.file_name()),
+        ),
+    };
+    Ok(Feed {
+        title: format!("{} - CÜ Sivas TBMYO Bilgisayar Teknolojileri Bölümü", title),
+        description: String::from("Yeni yüklenen ve güncellenen dokümanlar"),
+        link: format!("{}/dokumanlar", site),
+        self_link,
+        items: documents
+            .into_iter()
+            .map(|document| {
+                let url = format!("/dokumanlar/{}", document.filename);
+                FeedItem {
+                    // A new version is a new item, so readers notice updates.
+                    id: format!("{}{}", site, url),
+                    link: format!("{}{}", site, url),
+                    summary: if document.change_note.is_empty() {
+                        format!("{} ({})", document.category, document.academic_year)
+                    } else {
+                        document.change_note.clone()
+                    },
+                    author: String::from("Bilgisayar Teknolojileri Bölümü"),
+                    published: published(&document.upload_date),
+                    enclosure: std::fs::metadata(
+                        Path::new(docs::DOCS_DIR).join(&document.filename),
+                    )
+                    .ok()
+                    .map(|meta| Enclosure {
+                        url: format!("{}{}", site, url),
+                        mime: docs::document_extension(&document.original_name)
+                            .map(|ext| actix_files::file_extension_to_mime(&ext).to_string())
+                            .unwrap_or_else(|| String::from("application/octet-stream")),
+                        length: meta.len(),
+                    }),
+                    title: document.title,
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Percent-encodes a query parameter value.
+pub fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn internal_error(e: rusqlite::Error) -> actix_web::Error {
+    actix_web::error::ErrorInternalServerError(e.to_string())
+}
+
+pub async fn announcements_rss(req: HttpRequest) -> Result<HttpResponse> {
+    let feed = announcements_feed(Format::Rss).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Rss))
+}
+
+pub async fn announcements_atom(req: HttpRequest) -> Result<HttpResponse> {
+    let feed = announcements_feed(Format::Atom).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Atom))
+}
+
+pub async fn articles_rss(req: HttpRequest) -> Result<HttpResponse> {
+    let feed = articles_feed(Format::Rss).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Rss))
+}
+
+pub async fn articles_atom(req: HttpRequest) -> Result<HttpResponse> {
+    let feed = articles_feed(Format::Atom).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Atom))
+}
+
+#[derive(Deserialize)]
+pub struct CategoryFilter {
+    category: Option<String>,
+}
+
+impl CategoryFilter {
+    fn category(&self) -> Option<&str> {
+        self.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+    }
+}
+
+pub async fn documents_rss(
+    req: HttpRequest,
+    filter: web::Query<CategoryFilter>,
+) -> Result<HttpResponse> {
+    let feed = documents_feed(Format::Rss, filter.category()).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Rss))
+}
+
+pub async fn documents_atom(
+    req: HttpRequest,
+    filter: web::Query<CategoryFilter>,
+) -> Result<HttpResponse> {
+    let feed = documents_feed(Format::Atom, filter.category()).map_err(internal_error)?;
+    Ok(respond(&req, feed, Format::Atom))
+}
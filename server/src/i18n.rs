@@ -0,0 +1,1006 @@
+use crate::src::db;
+use crate::src::feed::percent_encode;
+use actix_web::cookie::{time::Duration, Cookie};
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde_derive::Deserialize;
+
+/// Remembers a visitor's explicit choice so they are not sent back to the
+/// language their browser prefers.
+pub const COOKIE: &str = "lang";
+
+/// Public pages are served in Turkish at their own path and in English
+/// under `/en`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    Tr,
+    En,
+}
+
+/// UI strings as `(key, Turkish, English)`. An empty translation falls back
+/// to the other language.
+const CATALOGUE: &[(&str, &str, &str)] = &[
+    // Layout
+    ("nav.home", "Ana Sayfa", "Home"),
+    ("nav.announcements", "Duyurular", "Announcements"),
+    ("nav.articles", "Makaleler", "Articles"),
+    ("nav.contact", "İletişim", "Contact"),
+    ("nav.staff", "Personel", "Staff"),
+    ("nav.calendar", "Takvim", "Calendar"),
+    ("nav.notifications", "Bildirimler", "Notifications"),
+    ("nav.documents", "Doküman", "Documents"),
+    ("footer.address", "Cumhuriyet Üniversitesi 58140 SİVAS", "Cumhuriyet University 58140 SIVAS, Türkiye"),
+    ("footer.switchboard", "Santral", "Switchboard"),
+    ("footer.fax", "Faks", "Fax"),
+    ("footer.privacy", "KVKK Aydınlatma Metni", "Privacy Notice"),
+    ("language.other", "English", "Türkçe"),
+    // Page titles
+    ("title.home", "Anasayfa", "Home"),
+    ("title.announcements", "Duyurular", "Announcements"),
+    ("title.articles", "Makaleler", "Articles"),
+    ("title.contact", "İletişim", "Contact"),
+    ("title.staff", "Personel", "Staff"),
+    ("title.events", "Etkinlik Takvimi", "Event Calendar"),
+    ("title.documents", "Dokümanlar", "Documents"),
+    ("title.privacy", "KVKK Aydınlatma Metni", "Privacy Notice"),
+    ("title.notifications", "Duyuru Bildirimleri", "Announcement Notifications"),
+    (
+        "meta.description",
+        "Sivas Cumhuriyet Üniversitesi Sivas Teknik Bilimler Meslek Yüksekokulu Bilgisayar Teknolojileri Bölümü duyuruları, makaleleri, dokümanları ve etkinlikleri.",
+        "Announcements, articles, documents and events of the Department of Computer Technologies, Sivas Vocational School of Technical Sciences, Sivas Cumhuriyet University.",
+    ),
+    // Announcement and article lists
+    ("list.read", "Oku", "Read"),
+    ("list.all", "Tümü", "All"),
+    ("list.previous_page", "Önceki Sayfa", "Previous Page"),
+    ("list.next_page", "Sonraki Sayfa", "Next Page"),
+    ("list.all_announcements", "Tüm Duyurular", "All Announcements"),
+    ("list.all_articles", "Tüm Makaleler", "All Articles"),
+    ("announcements.heading", "Duyuru & Haber & Etkinlik", "Announcements & News & Events"),
+    ("announcements.notify", "Bildirim al", "Get notified"),
+    ("articles.heading", "Makaleler", "Articles"),
+    ("documents.heading", "Ders Programı & Yönetmelikler", "Course Schedules & Regulations"),
+    ("documents.search_placeholder", "Dokümanlarda ara", "Search documents"),
+    ("documents.search", "Ara", "Search"),
+    // Contact form
+    ("contact.heading", "İletişim", "Contact"),
+    ("contact.leave_empty", "Bu alanı boş bırakın", "Leave this field empty"),
+    ("contact.topic", "Konu", "Topic"),
+    ("contact.topic_prompt", "Mesajınız kimin için?", "Who is your message for?"),
+    ("contact.choose", "Seçiniz", "Choose"),
+    ("contact.name", "Adınız Soyadınız", "Full Name"),
+    ("contact.email", "Email", "E-mail"),
+    ("contact.message", "Mesajınız", "Your Message"),
+    ("contact.attachment", "Ek Dosya (isteğe bağlı)", "Attachment (optional)"),
+    ("contact.attachment_hint", "PDF, JPEG, PNG veya WebP; en fazla", "PDF, JPEG, PNG or WebP; at most"),
+    ("contact.privacy_before", "Kişisel verileriniz", "Read our"),
+    ("contact.privacy_link", "Aydınlatma Metni", "Privacy Notice"),
+    ("contact.privacy_after", "kapsamında işlenir.", "on how your personal data is processed."),
+    ("contact.send", "Gönder", "Send"),
+    ("contact.sent", "Mesajınız Alınmıştır", "Your Message Has Been Received"),
+    (
+        "contact.failed",
+        "Mesajınız Gönderilemedi Lütfen Daha Sonra Tekrar Deneyiniz",
+        "Your Message Could Not Be Sent, Please Try Again Later",
+    ),
+    ("contact.error.category", "Lütfen mesajınızın konusunu seçin.", "Please choose the topic of your message."),
+    ("contact.error.required", "Bu alan zorunludur.", "This field is required."),
+    ("contact.error.number", "Lütfen bir sayı girin.", "Please enter a number."),
+    ("contact.error.date", "Lütfen geçerli bir tarih girin.", "Please enter a valid date."),
+    ("contact.error.select", "Lütfen listeden bir seçim yapın.", "Please choose from the list."),
+    ("contact.error.too_long", "Bu alan çok uzun.", "This field is too long."),
+    ("contact.error.name", "Lütfen adınızı ve soyadınızı yazın.", "Please enter your full name."),
+    (
+        "contact.error.name_length",
+        "Ad soyad en fazla {max} karakter olabilir.",
+        "Your name can be at most {max} characters.",
+    ),
+    ("contact.error.email", "Lütfen geçerli bir e-posta adresi girin.", "Please enter a valid e-mail address."),
+    ("contact.error.message", "Mesajınız en az 10 karakter olmalıdır.", "Your message must be at least 10 characters."),
+    (
+        "contact.error.message_length",
+        "Mesajınız en fazla {max} karakter olabilir ({length} karakter yazdınız).",
+        "Your message can be at most {max} characters (you wrote {length}).",
+    ),
+    (
+        "contact.error.expired",
+        "Formun süresi doldu. Lütfen mesajınızı kontrol edip tekrar gönderin.",
+        "The form has expired. Please check your message and send it again.",
+    ),
+    (
+        "contact.error.too_fast",
+        "Form çok hızlı gönderildi. Lütfen birkaç saniye sonra tekrar deneyin.",
+        "The form was sent too quickly. Please try again in a few seconds.",
+    ),
+    (
+        "contact.error.attachment_type",
+        "Yalnızca PDF, JPEG, PNG veya WebP dosyası eklenebilir.",
+        "Only PDF, JPEG, PNG or WebP files can be attached.",
+    ),
+    (
+        "contact.error.attachment_size",
+        "Ek dosya en fazla {mb} MB olabilir.",
+        "The attachment can be at most {mb} MB.",
+    ),
+    (
+        "contact.error.rate_limit",
+        "Kısa sürede çok fazla mesaj gönderdiniz. Lütfen daha sonra tekrar deneyin.",
+        "You have sent too many messages in a short time. Please try again later.",
+    ),
+    // Calendar
+    ("month.january", "Ocak", "January"),
+    ("month.february", "Şubat", "February"),
+    ("month.march", "Mart", "March"),
+    ("month.april", "Nisan", "April"),
+    ("month.may", "Mayıs", "May"),
+    ("month.june", "Haziran", "June"),
+    ("month.july", "Temmuz", "July"),
+    ("month.august", "Ağustos", "August"),
+    ("month.september", "Eylül", "September"),
+    ("month.october", "Ekim", "October"),
+    ("month.november", "Kasım", "November"),
+    ("month.december", "Aralık", "December"),
+    ("weekday.monday", "Pzt", "Mon"),
+    ("weekday.tuesday", "Sal", "Tue"),
+    ("weekday.wednesday", "Çar", "Wed"),
+    ("weekday.thursday", "Per", "Thu"),
+    ("weekday.friday", "Cum", "Fri"),
+    ("weekday.saturday", "Cmt", "Sat"),
+    ("weekday.sunday", "Paz", "Sun"),
+    ("events.previous_month", "‹ Önceki ay", "‹ Previous month"),
+    ("events.next_month", "Sonraki ay ›", "Next month ›"),
+    ("events.more", "+{count} daha", "+{count} more"),
+    ("events.none", "Bu ay için etkinlik bulunmuyor.", "There are no events this month."),
+    ("events.subscribe", "Takvime abone ol", "Subscribe to the calendar"),
+    (
+        "events.subscribe_category",
+        "{category} takvimine abone ol",
+        "Subscribe to the {category} calendar",
+    ),
+    (
+        "events.subscribe_hint",
+        "Google Takvim veya Outlook'ta \"URL ile ekle\" seçeneğine şu adresi yapıştırın:",
+        "In Google Calendar or Outlook, paste this address into \"Add from URL\":",
+    ),
+    // Documents
+    ("documents.applications", "Uygulamalar", "Applications"),
+    ("documents.exam_system", "Sınav Sistemi", "Exam System"),
+    ("documents.sql_system", "SQL Sistemi", "SQL System"),
+    ("documents.none", "Aramanızla eşleşen doküman bulunamadı.", "No documents match your search."),
+    ("documents.previous_versions", "Önceki Sürümler", "Previous Versions"),
+    (
+        "documents.no_preview",
+        "Bu doküman için önizleme oluşturulamadı.",
+        "A preview could not be created for this document.",
+    ),
+    (
+        "documents.preview_unsupported",
+        "Bu dosya türü için önizleme desteklenmiyor.",
+        "Previews are not supported for this file type.",
+    ),
+    ("documents.changes", "Bu sürümde değişenler:", "Changes in this version:"),
+    ("documents.download", "İndir", "Download"),
+    // Staff
+    ("staff.heading", "Akademik Personel", "Academic Staff"),
+    ("staff.office", "Ofis", "Office"),
+    ("staff.phone", "Telefon", "Phone"),
+    ("staff.office_hours", "Görüşme Saatleri", "Office Hours"),
+    ("staff.none", "Henüz personel bilgisi eklenmedi.", "No staff information has been added yet."),
+    // Announcement notifications
+    (
+        "notifications.intro",
+        "Ders iptali gibi yeni duyurulardan haberdar olmak için e-posta ile abone olun ya da tarayıcı bildirimlerini açın. Yalnızca seçtiğiniz kategorilerdeki duyurular gönderilir; hiçbirini seçmezseniz tümü gönderilir. Kategorisi olmayan duyurular herkese gider.",
+        "Subscribe by e-mail or turn on browser notifications to hear about new announcements such as cancelled classes. Only announcements in the categories you choose are sent; if you choose none, all of them are. Announcements without a category go to everyone.",
+    ),
+    ("notifications.categories", "Kategoriler", "Categories"),
+    (
+        "notifications.categories_hint",
+        "Kategoriler (hiçbiri seçilmezse tümü)",
+        "Categories (all when none are chosen)",
+    ),
+    (
+        "notifications.no_categories",
+        "Henüz kategorili duyuru yok; tüm duyurular gönderilir.",
+        "There are no categorised announcements yet; all announcements are sent.",
+    ),
+    ("notifications.email", "E-posta", "E-mail"),
+    ("notifications.email_placeholder", "ornek@eposta.com", "name@example.com"),
+    ("notifications.frequency", "Ne sıklıkla?", "How often?"),
+    ("notifications.frequency.immediate", "Her yeni duyuruda", "With every new announcement"),
+    ("notifications.frequency.daily", "Günlük özet", "Daily digest"),
+    ("notifications.frequency.weekly", "Haftalık özet", "Weekly digest"),
+    ("notifications.subscribe", "Abone ol", "Subscribe"),
+    (
+        "notifications.note",
+        "Size bir onay bağlantısı göndeririz; bağlantıyı açana kadar e-posta gönderilmez. Her e-postada aboneliği tek tıkla iptal etme bağlantısı bulunur. Ayrıntılar için",
+        "We send you a confirmation link and no e-mail is sent until you open it. Every e-mail has a one-click link to unsubscribe. For details, see the",
+    ),
+    ("notifications.push", "Tarayıcı bildirimleri", "Browser notifications"),
+    ("notifications.push_on", "Bildirimleri aç", "Turn on notifications"),
+    ("notifications.push_off", "Bildirimleri kapat", "Turn off notifications"),
+    ("notifications.push_update", "Kategorileri güncelle", "Update categories"),
+    ("notifications.push_enabled", "Bildirimler açıldı.", "Notifications are on."),
+    (
+        "notifications.push_failed",
+        "Bildirimler açılamadı. Tarayıcınızın bildirim iznini kontrol edin.",
+        "Notifications could not be turned on. Check your browser's notification permission.",
+    ),
+    ("notifications.push_disabled", "Bildirimler kapatıldı.", "Notifications are off."),
+    ("notifications.subscription", "Duyuru aboneliği", "Announcement subscription"),
+    ("notifications.back", "Geri dön", "Go back"),
+    (
+        "notifications.rate_limit",
+        "Kısa sürede çok fazla istek gönderdiniz. Lütfen daha sonra tekrar deneyin.",
+        "You have sent too many requests in a short time. Please try again later.",
+    ),
+    (
+        "notifications.unavailable",
+        "E-posta aboneliği şu anda kullanılamıyor.",
+        "E-mail subscriptions are not available right now.",
+    ),
+    (
+        "notifications.invalid_email",
+        "Geçerli bir e-posta adresi girin.",
+        "Enter a valid e-mail address.",
+    ),
+    (
+        "notifications.link_sent",
+        "{email} adresine bir bağlantı gönderdik. Aboneliğiniz bağlantıyı açtığınızda başlar.",
+        "We have sent a link to {email}. Your subscription starts when you open it.",
+    ),
+    ("notifications.invalid_link", "Bağlantı geçersiz", "Invalid link"),
+    (
+        "notifications.not_found",
+        "Bu abonelik bulunamadı. İptal edilmiş ya da hiç onaylanmamış olabilir.",
+        "This subscription was not found. It may have been cancelled or never confirmed.",
+    ),
+    ("notifications.subscribe_again", "Yeniden abone ol", "Subscribe again"),
+    (
+        "notifications.confirmed",
+        "Aboneliğiniz onaylandı. Yeni duyurular e-posta adresinize gönderilecek.",
+        "Your subscription is confirmed. New announcements will be sent to your e-mail address.",
+    ),
+    ("notifications.save", "Tercihleri kaydet", "Save preferences"),
+    ("notifications.saved", "Tercihleriniz kaydedildi.", "Your preferences have been saved."),
+    ("notifications.unsubscribe", "Aboneliği iptal et", "Unsubscribe"),
+    (
+        "notifications.unsubscribe_confirm",
+        "{email} adresine duyuru e-postası gönderilmeyecek.",
+        "No more announcement e-mails will be sent to {email}.",
+    ),
+    ("notifications.unsubscribed", "Abonelik iptal edildi", "Unsubscribed"),
+    (
+        "notifications.unsubscribed_notice",
+        "Artık duyuru e-postası almayacaksınız.",
+        "You will no longer receive announcement e-mails.",
+    ),
+    // Dashboard
+    ("admin.add_announcement", "Yeni Duyuru Ekle", "Add Announcement"),
+    ("admin.add_article", "Yeni Makale Ekle", "Add Article"),
+    ("admin.add_document", "Yeni Doküman Ekle", "Add Document"),
+    ("admin.add_staff", "Yeni Personel Ekle", "Add Staff Member"),
+    ("admin.add_event", "Yeni Etkinlik Ekle", "Add Event"),
+    ("admin.edit", "Düzenle", "Edit"),
+    ("admin.delete", "Sil", "Delete"),
+    ("admin.column.title", "Başlık", "Title"),
+    ("admin.column.category", "Kategori", "Category"),
+    ("admin.column.academic_year", "Akademik Yıl", "Academic Year"),
+    ("admin.column.version", "Sürüm", "Version"),
+    ("admin.column.uploaded", "Yüklenme Tarihi", "Uploaded"),
+    ("admin.column.date", "Tarih", "Date"),
+    ("admin.column.location", "Yer", "Location"),
+    ("admin.column.photo", "Fotoğraf", "Photo"),
+    ("admin.column.name", "Ad Soyad", "Name"),
+    ("admin.column.program", "Program", "Programme"),
+    ("admin.column.email", "E-posta", "E-mail"),
+    ("admin.column.order", "Sıra", "Order"),
+    ("admin.column.status", "Durum", "Status"),
+    ("admin.column.actions", "İşlem", "Actions"),
+    ("admin.nav.users", "Kullanıcılar", "Users"),
+    ("admin.nav.announcements", "Duyurular", "Announcements"),
+    ("admin.nav.articles", "Makaleler", "Articles"),
+    ("admin.nav.gallery", "Galeri", "Gallery"),
+    ("admin.nav.documents", "Dokümanlar", "Documents"),
+    ("admin.nav.events", "Etkinlikler", "Events"),
+    ("admin.nav.staff", "Personel", "Staff"),
+    ("admin.nav.inbox", "Gelen Kutusu", "Inbox"),
+    ("admin.nav.contact_form", "İletişim Formu", "Contact Form"),
+    ("admin.nav.privacy", "Kişisel Veriler", "Personal Data"),
+    ("admin.nav.jobs", "Arka Plan İşleri", "Background Jobs"),
+    ("admin.nav.tokens", "API Anahtarları", "API Keys"),
+    ("admin.nav.account", "Hesabım", "My Account"),
+    ("admin.nav.logout", "Çıkış", "Sign Out"),
+    ("admin.save", "Kaydet", "Save"),
+    ("admin.cancel", "İptal", "Cancel"),
+    ("admin.admin_only", "Bu işlem yalnızca yöneticilere açıktır.", "Only admins can do this."),
+    ("admin.column.username", "Kullanıcı Adı", "Username"),
+    ("admin.column.role", "Rol", "Role"),
+    ("admin.users.add", "Yeni Kullanıcı Ekle", "Add User"),
+    ("admin.users.close", "Kapat", "Close"),
+    ("admin.users.password", "Şifre", "Password"),
+    ("admin.users.role.admin", "Yönetici", "Admin"),
+    ("admin.users.role.editor", "Editör", "Editor"),
+    ("admin.users.admin_only", "Kullanıcıları yalnızca yöneticiler yönetebilir.", "Only admins can manage users."),
+    ("admin.users.own_admin", "Kendi yönetici yetkinizi kaldıramazsınız.", "You cannot remove your own admin rights."),
+    ("admin.users.own_account", "Kendi hesabınızı silemezsiniz.", "You cannot delete your own account."),
+    (
+        "admin.users.delete_confirm",
+        "Bu kullanıcı silinsin mi?",
+        "Are you sure you want to delete this user?",
+    ),
+    ("admin.users.duplicate_username", "Bu kullanıcı adı zaten kullanılıyor.", "This username is already taken."),
+    ("admin.users.last_admin", "En az bir yönetici hesabı kalmalı.", "At least one admin account must remain."),
+    ("password.error", "Şifre {failures}.", "The password {failures}."),
+    ("password.rule.length", "en az {min} karakter olmalı", "must be at least {min} characters long"),
+    ("password.rule.mixed_case", "büyük ve küçük harf içermeli", "must contain upper and lower case letters"),
+    ("password.rule.digit", "en az bir rakam içermeli", "must contain a digit"),
+    ("password.rule.symbol", "en az bir sembol içermeli", "must contain a symbol"),
+    ("password.hint.length", "En az {min} karakter", "At least {min} characters"),
+    ("password.hint.mixed_case", "büyük ve küçük harf", "upper and lower case letters"),
+    ("password.hint.digit", "en az bir rakam", "a digit"),
+    ("password.hint.symbol", "en az bir sembol", "a symbol"),
+    ("admin.account.username", "Kullanıcı adı", "Username"),
+    (
+        "admin.account.intro",
+        "Değişiklikleri kaydetmek için mevcut şifrenizi girin.",
+        "Enter your current password to save changes.",
+    ),
+    (
+        "admin.account.email_hint",
+        "Şifrenizi unutursanız sıfırlama bağlantısı bu adrese gönderilir.",
+        "If you forget your password, a reset link is sent to this address.",
+    ),
+    ("admin.account.new_password", "Yeni şifre", "New password"),
+    (
+        "admin.account.new_password_hint",
+        "Değiştirmek istemiyorsanız boş bırakın.",
+        "Leave empty to keep your current password.",
+    ),
+    ("admin.account.new_password_confirm", "Yeni şifre (tekrar)", "New password (again)"),
+    ("admin.account.current_password", "Mevcut şifre", "Current password"),
+    ("admin.account.wrong_password", "Mevcut şifre yanlış.", "The current password is wrong."),
+    ("admin.account.name_required", "Ad soyad boş bırakılamaz.", "Your name cannot be empty."),
+    ("admin.account.password_mismatch", "Yeni şifreler birbiriyle eşleşmiyor.", "The new passwords do not match."),
+    ("admin.account.saved", "Hesap bilgileriniz kaydedildi.", "Your account details have been saved."),
+    (
+        "admin.tokens.intro",
+        "Başka sistemlerin {api} üzerinden duyuru ve makale yayınlayabilmesi için anahtar oluşturun.",
+        "Create keys so that other systems can publish announcements and articles through {api}.",
+    ),
+    (
+        "admin.tokens.intro_header",
+        "İstekler {header} başlığıyla gönderilir ve sizin adınıza kaydedilir.",
+        "Requests send the key in the {header} header and are recorded under your name.",
+    ),
+    ("admin.tokens.intro_docs", "Uç noktalar {link} açıklanmıştır.", "The endpoints are described in the {link}."),
+    ("admin.tokens.docs_link", "API belgelerinde", "API reference"),
+    ("admin.tokens.key", "anahtar", "key"),
+    ("admin.tokens.name", "Ad", "Name"),
+    ("admin.tokens.name_placeholder", "Anahtar adı, ör. Sınav programı aktarımı", "Key name, e.g. Exam schedule import"),
+    ("admin.tokens.expiry_placeholder", "Geçerlilik (gün, boş: süresiz)", "Valid for (days, empty: forever)"),
+    ("admin.tokens.create", "Yeni Anahtar Oluştur", "Create Key"),
+    ("admin.tokens.scopes", "Yetkiler", "Scopes"),
+    ("admin.tokens.expires", "Son geçerlilik", "Expires"),
+    ("admin.tokens.last_used", "Son kullanım", "Last used"),
+    ("admin.tokens.no_expiry", "Süresiz", "Never"),
+    ("admin.tokens.status.revoked", "İptal edildi", "Revoked"),
+    ("admin.tokens.status.expired", "Süresi doldu", "Expired"),
+    ("admin.tokens.status.active", "Etkin", "Active"),
+    ("admin.tokens.revoke", "İptal Et", "Revoke"),
+    (
+        "admin.tokens.revoke_confirm",
+        "Bu anahtar iptal edilecek ve onu kullanan sistemler erişimini kaybedecek. Devam edilsin mi?",
+        "This key will be revoked and systems using it will lose access. Continue?",
+    ),
+    ("admin.tokens.scope.announcements:write", "Duyuru ekleme, düzenleme ve silme", "Add, edit and delete announcements"),
+    ("admin.tokens.scope.articles:write", "Makale ekleme, düzenleme ve silme", "Add, edit and delete articles"),
+    ("admin.tokens.scope.images:write", "Görsel yükleme", "Upload images"),
+    ("admin.tokens.name_required", "Anahtara bir ad verin.", "Give the key a name."),
+    ("admin.tokens.scope_required", "En az bir yetki seçin.", "Choose at least one scope."),
+    (
+        "admin.tokens.created",
+        "Anahtar oluşturuldu. Şimdi kopyalayın; bir daha gösterilmeyecek.",
+        "The key has been created. Copy it now; it will not be shown again.",
+    ),
+    ("admin.tokens.revoked", "Anahtar iptal edildi.", "The key has been revoked."),
+    (
+        "admin.jobs.intro",
+        "E-posta ve bildirim gönderimi, görsel işleme ve düzenli temizlik işleri burada sırayla çalışır. Başarısız olan bir iş {attempts} kez denenir; ardından aşağıda bekler. Tamamlanan işler {days} gün sonra silinir.",
+        "E-mail and notification delivery, image processing and regular cleanup run here one after another. A failing job is tried {attempts} times and then waits below. Finished jobs are deleted after {days} days.",
+    ),
+    ("admin.jobs.refresh", "Yenile", "Refresh"),
+    ("admin.jobs.none", "İş yok.", "No jobs."),
+    ("admin.jobs.job", "İş", "Job"),
+    ("admin.jobs.added", "Eklendi", "Added"),
+    ("admin.jobs.attempts", "Deneme", "Attempts"),
+    ("admin.jobs.last_error", "Son hata", "Last error"),
+    ("admin.jobs.queued", "Sırada", "Queued"),
+    ("admin.jobs.running", "Çalışıyor", "Running"),
+    ("admin.jobs.failed", "Başarısız", "Failed"),
+    ("admin.jobs.done", "Tamamlandı", "Done"),
+    ("admin.jobs.started", "Başladı", "Started"),
+    ("admin.jobs.run_at", "Çalışacağı zaman", "Runs at"),
+    ("admin.jobs.last_attempt", "Son deneme", "Last attempt"),
+    ("admin.jobs.retry", "Yeniden Dene", "Retry"),
+    (
+        "admin.jobs.delete_confirm",
+        "Bu iş silinecek ve bir daha denenmeyecek. Devam edilsin mi?",
+        "This job will be deleted and not tried again. Continue?",
+    ),
+    ("admin.jobs.requeued", "İş yeniden sıraya alındı.", "The job has been queued again."),
+    ("admin.jobs.retry_failed_only", "Yalnızca başarısız işler yeniden denenebilir.", "Only failed jobs can be retried."),
+    ("admin.jobs.deleted", "İş silindi.", "The job has been deleted."),
+    ("admin.jobs.delete_failed_only", "Yalnızca başarısız işler silinebilir.", "Only failed jobs can be deleted."),
+    (
+        "admin.document.delete_confirm",
+        "Bu doküman silinsin mi?",
+        "Are you sure you want to delete this document?",
+    ),
+    (
+        "admin.staff.delete_confirm",
+        "Bu personel silinsin mi?",
+        "Are you sure you want to delete this staff member?",
+    ),
+    (
+        "admin.event.delete_confirm",
+        "Bu etkinlik silinsin mi?",
+        "Are you sure you want to delete this event?",
+    ),
+    (
+        "admin.inbox.delete_confirm",
+        "Bu mesaj silinsin mi?",
+        "Are you sure you want to delete this message?",
+    ),
+    ("admin.inbox.all_statuses", "Tüm durumlar", "All statuses"),
+    ("admin.inbox.all_assignees", "Tüm sorumlular", "Everyone"),
+    ("admin.inbox.unread_only", "Sadece okunmamış", "Unread only"),
+    ("admin.inbox.search", "Ara...", "Search..."),
+    ("admin.inbox.spam", "Spam klasörü", "Spam folder"),
+    (
+        "admin.inbox.bulk_confirm",
+        "Seçili mesajlara işlem uygulansın mı?",
+        "Apply the action to the selected messages?",
+    ),
+    ("admin.inbox.mark_read", "Okundu olarak işaretle", "Mark as read"),
+    ("admin.inbox.mark_unread", "Okunmadı olarak işaretle", "Mark as unread"),
+    ("admin.inbox.apply", "Uygula", "Apply"),
+    ("admin.inbox.sender", "Gönderen", "Sender"),
+    ("admin.inbox.message", "Mesaj", "Message"),
+    ("admin.inbox.assignee", "Sorumlu", "Assignee"),
+    ("admin.inbox.none", "Mesaj bulunamadı.", "No messages found."),
+    ("admin.audit.heading", "İşlem Kayıtları", "Audit Log"),
+    ("admin.audit.all_users", "Tüm kullanıcılar", "All users"),
+    ("admin.audit.all_actions", "Tüm işlemler", "All actions"),
+    ("admin.audit.all_entities", "Tüm kayıt türleri", "All record types"),
+    ("admin.audit.from", "Başlangıç", "From"),
+    ("admin.audit.to", "Bitiş", "To"),
+    ("admin.audit.export", "CSV olarak indir", "Download as CSV"),
+    ("admin.audit.count", "{count} kayıt", "{count} entries"),
+    ("admin.audit.time", "Zaman", "Time"),
+    ("admin.audit.user", "Kullanıcı", "User"),
+    ("admin.audit.action", "İşlem", "Action"),
+    ("admin.audit.entity", "Kayıt Türü", "Record Type"),
+    ("admin.audit.record", "Kayıt", "Record"),
+    ("admin.audit.before", "Önce", "Before"),
+    ("admin.audit.after", "Sonra", "After"),
+    ("admin.inbox.status.new", "Yeni", "New"),
+    ("admin.inbox.status.in_progress", "İşlemde", "In progress"),
+    ("admin.inbox.status.answered", "Yanıtlandı", "Answered"),
+    ("admin.inbox.status.spam", "Spam", "Spam"),
+    ("admin.inbox.spam_score", "Spam puanı", "Spam score"),
+    ("admin.inbox.notes", "İç notlar", "Internal notes"),
+    ("admin.inbox.unassigned", "Atanmamış", "Unassigned"),
+    ("admin.inbox.attachment", "Ek dosya", "Attachment"),
+    ("admin.inbox.reply", "Yanıtla", "Reply"),
+    (
+        "admin.inbox.reply_confirm",
+        "Yanıt gönderene e-posta olarak iletilsin mi?",
+        "Send the reply to the sender by e-mail?",
+    ),
+    ("admin.inbox.reply_subject", "Re: İletişim formu mesajınız", "Re: Your contact form message"),
+    ("admin.inbox.reply_placeholder", "Yanıtınız...", "Your reply..."),
+    ("admin.inbox.send_reply", "Yanıtı Gönder", "Send Reply"),
+    ("admin.inbox.reply_required", "Konu ve yanıt boş bırakılamaz.", "The subject and reply cannot be empty."),
+    ("admin.inbox.reply_failed", "Yanıt gönderilemedi: {error}", "The reply could not be sent: {error}"),
+    ("admin.inbox.reply_sent", "Yanıt gönderildi.", "The reply has been sent."),
+    (
+        "admin.contact_form.intro",
+        "Ziyaretçiler mesajlarının konusunu seçer; yeni mesajlar o konunun sorumlusuna atanır ve ona bildirilir.",
+        "Visitors choose the topic of their message; new messages are assigned to that topic's owner, who is notified.",
+    ),
+    ("admin.contact_form.topics", "Konular", "Topics"),
+    ("admin.contact_form.new_topic", "Yeni konu", "New topic"),
+    ("admin.contact_form.add_topic", "Yeni Konu Ekle", "Add Topic"),
+    (
+        "admin.contact_form.topic_delete_confirm",
+        "Bu konu silinsin mi?",
+        "Are you sure you want to delete this topic?",
+    ),
+    ("admin.contact_form.fields", "Ek Alanlar", "Extra Fields"),
+    ("admin.contact_form.label", "Etiket", "Label"),
+    ("admin.contact_form.type", "Tür", "Type"),
+    ("admin.contact_form.required", "Zorunlu", "Required"),
+    ("admin.contact_form.options", "Seçenekler", "Options"),
+    ("admin.contact_form.options_placeholder", "Seçenek 1, Seçenek 2", "Option 1, Option 2"),
+    ("admin.contact_form.new_field", "Yeni alan", "New field"),
+    ("admin.contact_form.new_options", "Seçenekler (virgülle)", "Options (comma separated)"),
+    ("admin.contact_form.add_field", "Yeni Alan Ekle", "Add Field"),
+    (
+        "admin.contact_form.field_delete_confirm",
+        "Bu alan silinsin mi?",
+        "Are you sure you want to delete this field?",
+    ),
+    ("admin.contact_form.type.text", "Kısa metin", "Short text"),
+    ("admin.contact_form.type.textarea", "Uzun metin", "Long text"),
+    ("admin.contact_form.type.number", "Sayı", "Number"),
+    ("admin.contact_form.type.date", "Tarih", "Date"),
+    ("admin.contact_form.type.select", "Seçim listesi", "Drop-down list"),
+    (
+        "admin.privacy.intro",
+        "KVKK başvurularında bir e-posta adresine ait kayıtları bulun, dışa aktarın, anonimleştirin veya silin. Mesajlar {messages} gün, IP adresleri {ips} gün sonra otomatik olarak işlenir (0: kapalı).",
+        "For data protection requests, find, export, anonymise or delete the records of an e-mail address. Messages are processed automatically after {messages} days and IP addresses after {ips} days (0: off).",
+    ),
+    ("admin.privacy.not_found", "{email} adresine ait kayıt bulunamadı.", "No records were found for {email}."),
+    ("admin.privacy.found", "{email} adresine ait {count} mesaj bulundu.", "{count} messages were found for {email}."),
+    (
+        "admin.privacy.subscription",
+        "Bu adresin {date} tarihinde oluşturulmuş bir duyuru aboneliği var; silme ve anonimleştirme aboneliği de kaldırır.",
+        "This address has had an announcement subscription since {date}; deleting or anonymising also removes it.",
+    ),
+    ("admin.privacy.replies", "Yanıt", "Replies"),
+    ("admin.privacy.export", "JSON olarak indir", "Download as JSON"),
+    ("admin.privacy.anonymize", "Anonimleştir", "Anonymise"),
+    (
+        "admin.privacy.anonymize_confirm",
+        "Bu adrese ait mesajlardaki kişisel veriler kalıcı olarak silinecek, mesaj kayıtları anonim olarak kalacak. Devam edilsin mi?",
+        "Personal data in the messages from this address will be deleted for good; the messages stay as anonymous records. Continue?",
+    ),
+    ("admin.privacy.erase", "Tümünü Sil", "Delete All"),
+    (
+        "admin.privacy.erase_confirm",
+        "Bu adrese ait tüm mesajlar, yanıtlar ve ek dosyalar kalıcı olarak silinecek. Devam edilsin mi?",
+        "All messages, replies and attachments from this address will be deleted for good. Continue?",
+    ),
+    ("admin.privacy.anonymized", "{count} mesaj anonimleştirildi.", "{count} messages anonymised."),
+    ("admin.privacy.erased", "{count} mesaj silindi.", "{count} messages deleted."),
+    ("admin.document.file", "Dosya (PDF, Word, Excel, PowerPoint)", "File (PDF, Word, Excel, PowerPoint)"),
+    ("admin.document.current_file", "Mevcut dosya", "Current file"),
+    ("admin.document.replace_hint", "Değiştirmek için yeni dosya seçin.", "Choose a new file to replace it."),
+    ("admin.document.category_placeholder", "Kategori (ör. Yönetmelik)", "Category (e.g. Regulation)"),
+    ("admin.document.academic_year_placeholder", "Akademik Yıl (ör. 2024-2025)", "Academic Year (e.g. 2024-2025)"),
+    (
+        "admin.document.change_note",
+        "Bu sürümde neler değişti? (isteğe bağlı)",
+        "What changed in this version? (optional)",
+    ),
+    (
+        "admin.document.announce",
+        "Yeni dosya yüklenirse duyuru olarak da yayınla",
+        "Also publish an announcement when a new file is uploaded",
+    ),
+    ("admin.document.no_file", "Dosya seçilmedi", "No file was chosen"),
+    ("admin.document.unsupported", "Desteklenmeyen dosya türü.", "Unsupported file type."),
+    ("admin.staff.photo", "Fotoğraf (isteğe bağlı, kare olarak kırpılır)", "Photo (optional, cropped to a square)"),
+    ("admin.staff.replace_photo", "Değiştirmek için yeni fotoğraf seçin.", "Choose a new photo to replace it."),
+    ("admin.staff.title_placeholder", "Unvan (ör. Öğr. Gör.)", "Title (e.g. Lecturer)"),
+    (
+        "admin.staff.program_placeholder",
+        "Program (ör. Bilgisayar Programcılığı)",
+        "Programme (e.g. Computer Programming)",
+    ),
+    (
+        "admin.staff.office_hours_placeholder",
+        "Görüşme Saatleri (ör. Salı 13:00-15:00)",
+        "Office Hours (e.g. Tuesday 13:00-15:00)",
+    ),
+    (
+        "admin.image.unsupported",
+        "Desteklenmeyen dosya biçimi. Lütfen JPEG, PNG, WebP veya GIF yükleyin.",
+        "Unsupported file format. Please upload a JPEG, PNG, WebP or GIF image.",
+    ),
+    (
+        "admin.image.unreadable",
+        "Görsel okunamadı; dosya bozuk ya da eksik olabilir.",
+        "The image could not be read; the file may be damaged or incomplete.",
+    ),
+    (
+        "admin.announcement.delete_confirm",
+        "Bu duyuru silinsin mi?",
+        "Are you sure you want to delete this announcement?",
+    ),
+    (
+        "admin.announcement.category_placeholder",
+        "Kategori (ör. Ders İptali, Sınav); boş bırakılırsa tüm abonelere gider",
+        "Category (e.g. Class Cancelled, Exam); left empty, it goes to every subscriber",
+    ),
+    ("admin.announcement.added", "Duyuru Başarıyla Eklendi", "Announcement Added"),
+    ("admin.announcement.updated", "Duyuru Başarıyla Güncellendi", "Announcement Updated"),
+    ("admin.article.delete_confirm", "Bu makale silinsin mi?", "Are you sure you want to delete this article?"),
+    ("admin.article.added", "Makale Başarıyla Eklendi", "Article Added"),
+    ("admin.article.updated", "Makale Başarıyla Güncellendi", "Article Updated"),
+    ("admin.title_en_placeholder", "İngilizce başlık (isteğe bağlı)", "English title (optional)"),
+    (
+        "admin.seo_title_placeholder",
+        "SEO başlığı; boş bırakılırsa başlık kullanılır",
+        "SEO title; the title is used when left empty",
+    ),
+    (
+        "admin.seo_description_placeholder",
+        "SEO açıklaması; boş bırakılırsa içeriğin başından üretilir",
+        "SEO description; taken from the start of the content when left empty",
+    ),
+    (
+        "admin.event.category_placeholder",
+        "Kategori (ör. Sınav, Kayıt, Seminer, Akademik Takvim)",
+        "Category (e.g. Exam, Registration, Seminar, Academic Calendar)",
+    ),
+    ("admin.event.location_placeholder", "Yer (isteğe bağlı)", "Location (optional)"),
+    ("admin.event.all_day", "Tüm gün", "All day"),
+    ("admin.event.start", "Başlangıç", "Start"),
+    ("admin.event.end", "Bitiş (isteğe bağlı)", "End (optional)"),
+    ("admin.event.time", "Saat", "Time"),
+    ("admin.event.description_placeholder", "Açıklama (isteğe bağlı)", "Description (optional)"),
+    ("admin.gallery.focal_x", "Yatay Odak (%)", "Horizontal Focus (%)"),
+    ("admin.gallery.focal_y", "Dikey Odak (%)", "Vertical Focus (%)"),
+    ("admin.gallery.upload", "Yükle", "Upload"),
+    ("admin.gallery.loading", "Yükleniyor...", "Loading..."),
+    ("admin.gallery.upload_failed", "Yükleme başarısız oldu.", "The upload failed."),
+    (
+        "admin.gallery.processing",
+        "Görseller işleniyor; birkaç saniye içinde listede görünecek.",
+        "The images are being processed; they will appear in the list in a few seconds.",
+    ),
+    // Background jobs
+    ("jobs.kind.notify_announcement", "Duyuru bildirimi dağıtımı", "Announcement notifications"),
+    ("jobs.kind.notify_message", "İletişim mesajı bildirimi", "Contact message notification"),
+    ("jobs.kind.process_slide", "Görsel işleme", "Image processing"),
+    ("jobs.kind.purge_jobs", "Tamamlanan işlerin temizliği", "Finished job cleanup"),
+    ("jobs.kind.purge_retention", "Saklama süresi temizliği", "Retention cleanup"),
+    ("jobs.kind.send_digests", "Duyuru özetleri", "Announcement digests"),
+    ("jobs.kind.send_email", "E-posta gönderimi", "E-mail delivery"),
+    ("jobs.kind.send_push", "Tarayıcı bildirimi", "Browser notification"),
+    // Audit log labels
+    ("audit.action.create", "Ekleme", "Create"),
+    ("audit.action.update", "Güncelleme", "Update"),
+    ("audit.action.delete", "Silme", "Delete"),
+    ("audit.action.upload", "Yükleme", "Upload"),
+    ("audit.action.reply", "Yanıt", "Reply"),
+    ("audit.action.mark_unread", "Okunmadı işareti", "Marked unread"),
+    ("audit.action.bulk", "Toplu işlem", "Bulk action"),
+    ("audit.action.anonymize", "Anonimleştirme", "Anonymisation"),
+    ("audit.action.erase", "Kişisel veri silme", "Personal data erasure"),
+    ("audit.action.password_reset", "Şifre sıfırlama", "Password reset"),
+    ("audit.action.revoke", "İptal", "Revocation"),
+    ("audit.action.retry", "Yeniden deneme", "Retry"),
+    ("audit.entity.announcement", "Duyuru", "Announcement"),
+    ("audit.entity.article", "Makale", "Article"),
+    ("audit.entity.document", "Doküman", "Document"),
+    ("audit.entity.user", "Kullanıcı", "User"),
+    ("audit.entity.staff", "Personel", "Staff member"),
+    ("audit.entity.image", "Slider görseli", "Slider image"),
+    ("audit.entity.message", "Mesaj", "Message"),
+    ("audit.entity.contact_category", "İletişim konusu", "Contact topic"),
+    ("audit.entity.contact_field", "İletişim formu alanı", "Contact form field"),
+    ("audit.entity.personal_data", "Kişisel veri", "Personal data"),
+    ("audit.entity.api_token", "API anahtarı", "API key"),
+    ("audit.entity.event", "Etkinlik", "Event"),
+    ("audit.entity.job", "Arka plan işi", "Background job"),
+];
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::Tr, Lang::En];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Tr => "tr",
+            Lang::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Lang> {
+        Lang::ALL.into_iter().find(|lang| lang.code() == code)
+    }
+
+    /// OpenGraph locale.
+    pub fn locale(self) -> &'static str {
+        match self {
+            Lang::Tr => "tr_TR",
+            Lang::En => "en_US",
+        }
+    }
+
+    fn other(self) -> Lang {
+        match self {
+            Lang::Tr => Lang::En,
+            Lang::En => Lang::Tr,
+        }
+    }
+
+    /// The language of a public page, decided by its address alone so every
+    /// URL has one language for search engines and shared links.
+    pub fn of(req: &HttpRequest) -> Lang {
+        let path = req.path();
+        if path == "/en" || path.starts_with("/en/") {
+            Lang::En
+        } else {
+            Lang::Tr
+        }
+    }
+
+    /// The language a visitor chose with the switcher, or else the first one
+    /// their browser asks for. Used where addresses carry no language, like
+    /// the dashboard.
+    pub fn preferred(req: &HttpRequest) -> Lang {
+        req.cookie(COOKIE)
+            .and_then(|cookie| Lang::from_code(cookie.value()))
+            .or_else(|| negotiate(req))
+            .unwrap_or(Lang::Tr)
+    }
+
+    /// `path` in this language, e.g. `/duyurular` becomes `/en/duyurular`.
+    pub fn path(self, path: &str) -> String {
+        let path = base_path(path);
+        match self {
+            Lang::Tr => path.to_string(),
+            Lang::En if path == "/" => String::from("/en/"),
+            Lang::En => format!("/en{}", path),
+        }
+    }
+
+    /// Prefix for links in page templates, written as `{{base}}/duyurular`.
+    fn base(self) -> &'static str {
+        match self {
+            Lang::Tr => "",
+            Lang::En => "/en",
+        }
+    }
+}
+
+/// `path` without its language prefix.
+pub fn base_path(path: &str) -> &str {
+    match path.strip_prefix("/en") {
+        Some("") => "/",
+        Some(rest) if rest.starts_with('/') => rest,
+        _ => path,
+    }
+}
+
+/// The language Accept-Language ranks highest among the supported ones.
+fn negotiate(req: &HttpRequest) -> Option<Lang> {
+    let header = req.headers().get(header::ACCEPT_LANGUAGE)?.to_str().ok()?;
+    header
+        .split(',')
+        .enumerate()
+        .filter_map(|(position, range)| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim().to_lowercase();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            let lang = Lang::from_code(tag.split('-').next()?)?;
+            (quality > 0.0).then_some((lang, quality, position))
+        })
+        // Highest quality first; the header's order breaks ties.
+        .min_by(|a, b| b.1.total_cmp(&a.1).then(a.2.cmp(&b.2)))
+        .map(|(lang, _, _)| lang)
+}
+
+/// Sends a first-time visitor whose browser prefers English from a Turkish
+/// page to its English version. Visitors who picked a language with the
+/// switcher stay where they are.
+pub fn redirect_to_preferred(req: &HttpRequest) -> Option<HttpResponse> {
+    if req.method() != actix_web::http::Method::GET
+        || Lang::of(req) != Lang::Tr
+        || req.cookie(COOKIE).is_some()
+        || negotiate(req) != Some(Lang::En)
+    {
+        return None;
+    }
+
+    let mut location = Lang::En.path(req.path());
+    if !req.query_string().is_empty() {
+        location = format!("{}?{}", location, req.query_string());
+    }
+    Some(
+        HttpResponse::Found()
+            .insert_header((header::LOCATION, location))
+            .insert_header((header::VARY, "Accept-Language, Cookie"))
+            .finish(),
+    )
+}
+
+/// A UI string in `lang`, or in the other language when it has no
+/// translation yet. A key missing from the catalogue is shown as is, so the
+/// mistake is visible on the page.
+pub fn t(lang: Lang, key: &str) -> &str {
+    let Some((_, tr, en)) = CATALOGUE.iter().find(|(k, _, _)| *k == key) else {
+        return key;
+    };
+    match (lang, *tr, *en) {
+        (Lang::Tr, "", en) => en,
+        (Lang::Tr, tr, _) => tr,
+        (Lang::En, tr, "") => tr,
+        (Lang::En, _, en) => en,
+    }
+}
+
+/// Fills a page template's `{{t.key}}` strings and `{{base}}` link prefixes.
+pub fn translate(template: &str, lang: Lang) -> String {
+    let mut html = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{t.") {
+        html.push_str(&rest[..start]);
+        let after = &rest[start + 4..];
+        match after.find("}}") {
+            Some(end) => {
+                html.push_str(t(lang, &after[..end]));
+                rest = &after[end + 2..];
+            }
+            None => {
+                html.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    html.push_str(rest);
+    html.replace("{{base}}", lang.base())
+}
+
+/// Link to the same page in the other language, through [`switch_handler`]
+/// so the choice sticks.
+pub fn switch_link(req: &HttpRequest) -> String {
+    let other = Lang::of(req).other();
+    let mut next = other.path(req.path());
+    if !req.query_string().is_empty() {
+        next = format!("{}?{}", next, req.query_string());
+    }
+    format!(
+        "<a id='language-switch' href='/dil/{0}?next={1}' hreflang='{0}' lang='{0}'
+            class='font-semibold block py-2 px-3 text-gray-900 rounded hover:bg-gray-100 md:hover:bg-transparent md:border-0 md:hover:text-blue-700 md:p-0'>{2}</a>",
+        other.code(),
+        percent_encode(&next),
+        t(Lang::of(req), "language.other")
+    )
+}
+
+#[derive(Deserialize)]
+pub struct SwitchQuery {
+    next: Option<String>,
+}
+
+/// Remembers the chosen language and continues to the page in it.
+pub async fn switch_handler(code: web::Path<String>, query: web::Query<SwitchQuery>) -> HttpResponse {
+    let Some(lang) = Lang::from_code(&code) else {
+        return HttpResponse::NotFound().finish();
+    };
+    // Only paths on this site, so the switcher cannot be used to send
+    // visitors elsewhere.
+    let next = query
+        .next
+        .as_deref()
+        .filter(|next| next.starts_with('/') && !next.starts_with("//") && !next.contains('\\'))
+        .map(str::to_string)
+        .unwrap_or_else(|| lang.path("/"));
+
+    HttpResponse::SeeOther()
+        .cookie(
+            Cookie::build(COOKIE, lang.code())
+                .path("/")
+                .max_age(Duration::days(365))
+                .http_only(true)
+                .finish(),
+        )
+        .insert_header((header::LOCATION, next))
+        .finish()
+}
+
+/// A translation of an announcement's or article's title and content.
+#[derive(Clone, Default)]
+pub struct Translation {
+    pub title: String,
+    pub content: String,
+}
+
+/// Shows an announcement or article in `lang`. The stored title and content
+/// are Turkish; English comes from its translation. Whatever is missing in
+/// one language is taken from the other.
+pub fn localize(
+    entity: &str,
+    id: i32,
+    lang: Lang,
+    title: &mut String,
+    content: &mut String,
+) -> rusqlite::Result<()> {
+    if lang == Lang::Tr && !title.is_empty() && !content.is_empty() {
+        return Ok(());
+    }
+    let Some(translation) = db::get_translation(entity, id, Lang::En.code())? else {
+        return Ok(());
+    };
+
+    let replace = |own: &mut String, translated: String| {
+        let missing = own.trim().is_empty();
+        if (lang == Lang::En && !translated.trim().is_empty()) || missing {
+            *own = translated;
+        }
+    };
+    replace(title, translation.title);
+    replace(content, translation.content);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// The key of every `t(lang, "key")` call in `source`. Calls whose key
+    /// is not a literal are skipped.
+    fn literal_keys(source: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+        for (start, _) in source.match_indices("t(") {
+            let before = source[..start].chars().next_back();
+            if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            let mut depth = 0;
+            let args = &source[start + 2..];
+            let end = args
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' if depth == 0 => return true,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    false
+                })
+                .map_or(args.len(), |(end, _)| end);
+            let args = &args[..end];
+            if let Some((_, key)) = args.split_once(", \"") {
+                if let Some(key) = key.strip_suffix('"') {
+                    keys.push(key.to_string());
+                }
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn every_key_used_is_in_the_catalogue() {
+        let mut missing = Vec::new();
+        for entry in fs::read_dir("src").unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            let code = source.split("#[cfg(test)]").next().unwrap_or("");
+            for key in literal_keys(code) {
+                if !CATALOGUE.iter().any(|(k, _, _)| *k == key) {
+                    missing.push(format!("{}: {}", path.display(), key));
+                }
+            }
+        }
+        for entry in fs::read_dir("../public/pages").unwrap() {
+            let path = entry.unwrap().path();
+            let template = fs::read_to_string(&path).unwrap();
+            for (start, _) in template.match_indices("{{t.") {
+                let rest = &template[start + 4..];
+                let key = &rest[..rest.find("}}").unwrap_or(rest.len())];
+                if !CATALOGUE.iter().any(|(k, _, _)| *k == key) {
+                    missing.push(format!("{}: {}", path.display(), key));
+                }
+            }
+        }
+        assert!(
+            missing.is_empty(),
+            "keys missing from the catalogue: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn unknown_keys_fall_back_to_the_key() {
+        assert_eq!(t(Lang::En, "list.read"), "Read");
+        assert_eq!(t(Lang::En, "no.such.key"), "no.such.key");
+    }
+}
@@ -0,0 +1,263 @@
+use image::error::{ImageFormatHint, UnsupportedError, UnsupportedErrorKind};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageError, ImageFormat, ImageResult};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const SLIDER_DIR: &str = "../public/assets/slider";
+pub const SLIDER_URL: &str = "/assets/slider";
+pub const SLIDER_ORIGINAL_DIR: &str = "../public/assets/slider/original";
+
+// Desktop derivatives live directly in SLIDER_DIR so the gallery and the
+// slider keep listing them by file name; smaller widths go in a subfolder
+// named after the width.
+pub const SLIDE_WIDTHS: [u32; 3] = [640, 1024, 1280];
+pub const SLIDE_DESKTOP_WIDTH: u32 = 1280;
+pub const SLIDE_ASPECT: (u32, u32) = (16, 9);
+
+pub const STAFF_PHOTO_DIR: &str = "../public/assets/image/staff";
+pub const STAFF_PHOTO_URL: &str = "/assets/image/staff";
+pub const STAFF_PHOTO_SIZE: u32 = 400;
+
+const ACCEPTED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Jpeg,
+    ImageFormat::Png,
+    ImageFormat::WebP,
+    ImageFormat::Gif,
+];
+
+/// Point of interest kept inside the crop, as fractions of width and height.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FocalPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for FocalPoint {
+    fn default() -> Self {
+        FocalPoint { x: 0.5, y: 0.5 }
+    }
+}
+
+impl FocalPoint {
+    /// Builds a focal point from percentages (0-100) as sent by the gallery form.
+    pub fn from_percent(x: Option<f32>, y: Option<f32>) -> Self {
+        let clamp = |v: Option<f32>| (v.unwrap_or(50.0) / 100.0).clamp(0.0, 1.0);
+        FocalPoint {
+            x: clamp(x),
+            y: clamp(y),
+        }
+    }
+}
+
+fn unsupported(format: ImageFormat) -> ImageError {
+    ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+        ImageFormatHint::Exact(format),
+        UnsupportedErrorKind::Format(ImageFormatHint::Exact(format)),
+    ))
+}
+
+/// Decodes an upload, rejecting anything that is not one of the accepted formats.
+pub fn decode(bytes: &[u8]) -> ImageResult<(DynamicImage, ImageFormat)> {
+    let format = image::guess_format(bytes)?;
+    if !ACCEPTED_FORMATS.contains(&format) {
+        return Err(unsupported(format));
+    }
+    let img = image::load_from_memory_with_format(bytes, format)?;
+    Ok((img, format))
+}
+
+/// Crops the largest region with the given aspect ratio, centred on the
+/// focal point as far as the image bounds allow.
+pub fn crop_to_aspect(img: &DynamicImage, aspect: (u32, u32), focal: FocalPoint) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let (aw, ah) = (aspect.0 as u64, aspect.1 as u64);
+
+    let (crop_w, crop_h) = if width as u64 * ah > height as u64 * aw {
+        ((height as u64 * aw / ah) as u32, height)
+    } else {
+        (width, (width as u64 * ah / aw) as u32)
+    };
+
+    let offset = |size: u32, crop: u32, focus: f32| -> u32 {
+        let centre = (size as f32 * focus) - crop as f32 / 2.0;
+        centre.clamp(0.0, (size - crop) as f32) as u32
+    };
+
+    img.crop_imm(
+        offset(width, crop_w, focal.x),
+        offset(height, crop_h, focal.y),
+        crop_w.max(1),
+        crop_h.max(1),
+    )
+}
+
+pub fn derivative_path(dir: &str, width: u32, name: &str) -> PathBuf {
+    if width == SLIDE_DESKTOP_WIDTH {
+        Path::new(dir).join(name)
+    } else {
+        Path::new(dir).join(width.to_string()).join(name)
+    }
+}
+
+/// Decodes an upload, so that corrupt files are turned away before anything
+/// is queued, and stores it untouched. Returns the original's file name,
+/// which `create_slide` turns into the slide; that is slow, so it runs as a
+/// background job.
+pub fn store_slide_original(bytes: &[u8]) -> ImageResult<String> {
+    let (_, format) = decode(bytes)?;
+
+    fs::create_dir_all(SLIDER_ORIGINAL_DIR)?;
+    let extension = format.extensions_str().first().copied().unwrap_or("bin");
+    let name = format!("{}.{}", Uuid::new_v4(), extension);
+    fs::write(Path::new(SLIDER_ORIGINAL_DIR).join(&name), bytes)?;
+
+    Ok(name)
+}
+
+/// Removes an original that never became a slide.
+pub fn delete_slide_original(original: &str) -> std::io::Result<()> {
+    fs::remove_file(Path::new(SLIDER_ORIGINAL_DIR).join(original))
+}
+
+/// The name the slide made from `original` is listed and deleted under.
+pub fn slide_name(original: &str) -> String {
+    let stem = Path::new(original)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(original);
+    format!("{}.webp", stem)
+}
+
+/// Writes one WebP derivative per slide width from a stored original.
+/// Returns the file name of the desktop derivative.
+pub fn create_slide(original: &str, focal: FocalPoint) -> ImageResult<String> {
+    let bytes = fs::read(Path::new(SLIDER_ORIGINAL_DIR).join(original))?;
+    let (img, _) = decode(&bytes)?;
+
+    let cropped = crop_to_aspect(&img, SLIDE_ASPECT, focal);
+    let name = slide_name(original);
+    for width in SLIDE_WIDTHS {
+        let height = width * SLIDE_ASPECT.1 / SLIDE_ASPECT.0;
+        let resized = cropped.resize_exact(width, height, FilterType::Lanczos3);
+        let path = derivative_path(SLIDER_DIR, width, &name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        resized.save_with_format(path, ImageFormat::WebP)?;
+    }
+
+    Ok(name)
+}
+
+/// Arguments of the job that turns a stored original into a slide.
+#[derive(Deserialize, Serialize)]
+pub struct SlideJob {
+    pub original: String,
+    pub focal: FocalPoint,
+}
+
+/// Runs `create_slide` for a queued upload.
+pub async fn process_slide(job: SlideJob) -> Result<(), String> {
+    actix_web::web::block(move || create_slide(&job.original, job.focal))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Removes a slide's derivatives and its original upload.
+pub fn delete_slide(name: &str) -> std::io::Result<()> {
+    fs::remove_file(derivative_path(SLIDER_DIR, SLIDE_DESKTOP_WIDTH, name))?;
+
+    for width in SLIDE_WIDTHS {
+        let _ = fs::remove_file(derivative_path(SLIDER_DIR, width, name));
+    }
+
+    let stem = Path::new(name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(name);
+    if let Ok(entries) = fs::read_dir(SLIDER_ORIGINAL_DIR) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.file_stem().and_then(|s| s.to_str()) == Some(stem) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `srcset` value for a slide, listing only the widths that exist on disk.
+pub fn slide_srcset(url_prefix: &str, name: &str) -> String {
+    SLIDE_WIDTHS
+        .iter()
+        .filter(|width| derivative_path(SLIDER_DIR, **width, name).is_file())
+        .map(|width| {
+            if *width == SLIDE_DESKTOP_WIDTH {
+                format!("{}/{} {}w", url_prefix, name, width)
+            } else {
+                format!("{}/{}/{} {}w", url_prefix, width, name, width)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Crops a staff photo to a square and stores it as WebP. Returns the file name.
+pub fn save_staff_photo(bytes: &[u8]) -> ImageResult<String> {
+    let (img, _) = decode(bytes)?;
+    let square = crop_to_aspect(&img, (1, 1), FocalPoint::default()).resize_exact(
+        STAFF_PHOTO_SIZE,
+        STAFF_PHOTO_SIZE,
+        FilterType::Lanczos3,
+    );
+
+    fs::create_dir_all(STAFF_PHOTO_DIR)?;
+    let name = format!("{}.webp", Uuid::new_v4());
+    square.save_with_format(Path::new(STAFF_PHOTO_DIR).join(&name), ImageFormat::WebP)?;
+
+    Ok(name)
+}
+
+/// Removes a photo written by `save_staff_photo`; other paths (such as the
+/// shared avatar) are left alone.
+pub fn delete_staff_photo(url: &str) {
+    if let Some(name) = url
+        .strip_prefix(STAFF_PHOTO_URL)
+        .map(|name| name.trim_start_matches('/'))
+    {
+        if let Ok(path) = crate::src::safe_path::resolve(STAFF_PHOTO_DIR, name) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn truncated_uploads_fail_to_decode() {
+        let mut png = Vec::new();
+        DynamicImage::new_rgb8(64, 64)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        assert!(decode(&png).is_ok());
+        assert!(matches!(
+            store_slide_original(&png[..png.len() / 2]),
+            Err(image::ImageError::Decoding(_))
+        ));
+    }
+
+    #[test]
+    fn slide_name_matches_the_original() {
+        assert_eq!(slide_name("abc.jpg"), "abc.webp");
+    }
+}
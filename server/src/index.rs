@@ -1,8 +1,7 @@
+use crate::src::main_content;
 use actix_web::{HttpRequest, HttpResponse, Result};
-use std::path::PathBuf;
 
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/index.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
-}
\ No newline at end of file
+/// The main page, inside the site layout unless htmx asked for it.
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    main_content::handler(req).await
+}
@@ -0,0 +1,241 @@
+use crate::src::config;
+use crate::src::db;
+use crate::src::i18n::{self, Lang};
+use crate::src::images;
+use crate::src::mailer;
+use crate::src::notify;
+use crate::src::privacy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How often the workers look for due jobs when nothing wakes them earlier.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// How often the scheduler checks whether a periodic task is due.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+/// Retries never wait longer than this, however many attempts came before.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 60 * 60);
+/// Completed jobs are kept this long for the dashboard, then deleted.
+pub const DONE_RETENTION_DAYS: i64 = 7;
+
+pub const NOTIFY_ANNOUNCEMENT: &str = "notify_announcement";
+pub const NOTIFY_MESSAGE: &str = "notify_message";
+pub const PROCESS_SLIDE: &str = "process_slide";
+pub const PURGE_JOBS: &str = "purge_jobs";
+pub const PURGE_RETENTION: &str = "purge_retention";
+pub const SEND_DIGESTS: &str = "send_digests";
+pub const SEND_EMAIL: &str = "send_email";
+pub const SEND_PUSH: &str = "send_push";
+
+/// Tasks the scheduler queues again once this long has passed since the
+/// previous one was queued.
+const PERIODIC: [(&str, Duration); 3] = [
+    (PURGE_RETENTION, Duration::from_secs(24 * 60 * 60)),
+    (PURGE_JOBS, Duration::from_secs(24 * 60 * 60)),
+    (SEND_DIGESTS, Duration::from_secs(60 * 60)),
+];
+
+/// Catalogue keys of the names shown on the dashboard's Jobs panel.
+pub const KINDS: [(&str, &str); 8] = [
+    (NOTIFY_ANNOUNCEMENT, "jobs.kind.notify_announcement"),
+    (NOTIFY_MESSAGE, "jobs.kind.notify_message"),
+    (PROCESS_SLIDE, "jobs.kind.process_slide"),
+    (PURGE_JOBS, "jobs.kind.purge_jobs"),
+    (PURGE_RETENTION, "jobs.kind.purge_retention"),
+    (SEND_DIGESTS, "jobs.kind.send_digests"),
+    (SEND_EMAIL, "jobs.kind.send_email"),
+    (SEND_PUSH, "jobs.kind.send_push"),
+];
+
+/// Work kept in the `jobs` table so it survives restarts and does not hold
+/// up the request that asked for it.
+#[derive(Clone, Serialize)]
+pub struct Job {
+    pub id: i32,
+    pub kind: String,
+    /// JSON arguments, read by the module that handles `kind`.
+    pub payload: String,
+    /// `queued`, `running`, `done` or `failed`. Failed jobs have used up
+    /// `[jobs] max_attempts` and are not tried again on their own.
+    pub status: String,
+    pub attempts: i32,
+    pub run_at: String,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl Job {
+    pub fn label(&self, lang: Lang) -> &str {
+        KINDS
+            .iter()
+            .find(|(kind, _)| *kind == self.kind)
+            .map(|(_, label)| i18n::t(lang, label))
+            .unwrap_or(&self.kind)
+    }
+}
+
+fn format_time(time: chrono::DateTime<chrono::Local>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn now() -> String {
+    format_time(chrono::Local::now())
+}
+
+fn wake() -> &'static Notify {
+    static WAKE: OnceLock<Notify> = OnceLock::new();
+    WAKE.get_or_init(Notify::new)
+}
+
+/// Queues a job to run as soon as a worker gets to it.
+pub fn enqueue<T: Serialize>(kind: &str, payload: &T) -> rusqlite::Result<i32> {
+    let payload = serde_json::to_string(payload)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let now = now();
+    let id = db::add_job(kind, &payload, &now, &now)?;
+    wake().notify_one();
+    Ok(id)
+}
+
+/// Gives a failed job another full set of attempts, starting now.
+pub fn retry(id: i32) -> rusqlite::Result<bool> {
+    let requeued = db::requeue_failed_job(id, &now())?;
+    if requeued {
+        wake().notify_one();
+    }
+    Ok(requeued)
+}
+
+fn payload<T: DeserializeOwned>(job: &Job) -> Result<T, String> {
+    serde_json::from_str(&job.payload).map_err(|e| format!("geçersiz iş verisi: {}", e))
+}
+
+async fn run(job: &Job) -> Result<(), String> {
+    match job.kind.as_str() {
+        NOTIFY_ANNOUNCEMENT => notify::fan_out(payload(job)?),
+        NOTIFY_MESSAGE => mailer::send_message_notification(payload(job)?).await,
+        PROCESS_SLIDE => images::process_slide(payload(job)?).await,
+        PURGE_JOBS => purge_done_jobs(),
+        PURGE_RETENTION => privacy::apply_retention(),
+        SEND_DIGESTS => notify::send_digests(),
+        SEND_EMAIL => notify::send_email(payload(job)?).await,
+        SEND_PUSH => notify::send_push(payload(job)?).await,
+        kind => Err(format!("bilinmeyen iş türü: {}", kind)),
+    }
+}
+
+fn purge_done_jobs() -> Result<(), String> {
+    let cutoff = format_time(chrono::Local::now() - chrono::Duration::days(DONE_RETENTION_DAYS));
+    db::delete_finished_jobs(&cutoff)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Wait before the next attempt: `[jobs] retry_delay_secs`, doubled for
+/// every attempt after the first.
+fn retry_delay(attempts: i32) -> Duration {
+    let base = Duration::from_secs(config::get().jobs.retry_delay_secs);
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1).max(0) as u32);
+    base.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+/// Records how a job went: done, queued again after a delay, or failed for
+/// good once it has used up its attempts.
+fn settle(job: &Job, result: Result<(), String>) -> rusqlite::Result<()> {
+    let error = match result {
+        Ok(()) => return db::finish_job(job.id, &now()),
+        Err(error) => error,
+    };
+
+    if job.attempts >= config::get().jobs.max_attempts {
+        eprintln!(
+            "Job {} ({}) failed after {} attempts: {}",
+            job.id, job.kind, job.attempts, error
+        );
+        return db::fail_job(job.id, &error, &now());
+    }
+
+    let delay = retry_delay(job.attempts);
+    eprintln!(
+        "Job {} ({}) failed, retrying in {}s: {}",
+        job.id,
+        job.kind,
+        delay.as_secs(),
+        error
+    );
+    let run_at = chrono::Local::now()
+        + chrono::Duration::from_std(delay).unwrap_or_else(|_| chrono::Duration::zero());
+    db::retry_job(job.id, &error, &format_time(run_at))
+}
+
+async fn work() {
+    loop {
+        loop {
+            let job = match db::claim_job(&now()) {
+                Ok(Some(job)) => job,
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Failed to read the job queue: {}", e);
+                    break;
+                }
+            };
+            let result = run(&job).await;
+            if let Err(e) = settle(&job, result) {
+                eprintln!("Failed to update job {}: {}", job.id, e);
+            }
+        }
+        // Retries and jobs queued by another process have no one to wake
+        // the workers, so they are picked up by polling.
+        let _ = actix_web::rt::time::timeout(POLL_INTERVAL, wake().notified()).await;
+    }
+}
+
+/// Whether a periodic task last queued at `last` should be queued again.
+fn periodic_due(last: Option<&str>, every: Duration) -> bool {
+    let Some(last) =
+        last.and_then(|last| chrono::NaiveDateTime::parse_from_str(last, "%Y-%m-%d %H:%M:%S").ok())
+    else {
+        return true;
+    };
+    let every = chrono::Duration::from_std(every).unwrap_or_else(|_| chrono::Duration::zero());
+    chrono::Local::now().naive_local() - last >= every
+}
+
+async fn schedule() {
+    let mut interval = actix_web::rt::time::interval(SCHEDULE_INTERVAL);
+    loop {
+        interval.tick().await;
+        for (kind, every) in PERIODIC {
+            let result = db::get_last_job_created_at(kind).and_then(|last| {
+                if periodic_due(last.as_deref(), every) {
+                    enqueue(kind, &()).map(|_| ())
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to schedule {}: {}", kind, e);
+            }
+        }
+    }
+}
+
+/// Starts `[jobs] workers` workers and the scheduler for periodic tasks.
+pub fn start_workers() {
+    // Jobs that were running when the server stopped are tried again.
+    match db::requeue_running_jobs() {
+        Ok(0) => (),
+        Ok(count) => println!("Requeued {} interrupted jobs", count),
+        Err(e) => eprintln!("Failed to requeue interrupted jobs: {}", e),
+    }
+
+    for _ in 0..config::get().jobs.workers.max(1) {
+        actix_web::rt::spawn(work());
+    }
+    actix_web::rt::spawn(schedule());
+}
+
@@ -0,0 +1,112 @@
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::seo::Meta;
+use actix_web::{HttpRequest, HttpResponse, Result};
+use std::path::PathBuf;
+
+pub const SITE_NAME: &str = "CÜ Sivas TBMYO Bilgisayar Teknolojileri Bölümü";
+
+/// Whether htmx asked for a fragment to swap into `#main-container`. After a
+/// history cache miss htmx asks for the whole page again, so that is
+/// answered like a normal visit.
+pub fn is_fragment_request(req: &HttpRequest) -> bool {
+    req.headers().contains_key("HX-Request")
+        && !req.headers().contains_key("HX-History-Restore-Request")
+}
+
+/// Attributes of a link to a public page in `lang`: a plain `href` for
+/// visitors without JavaScript, and an htmx swap that updates the address
+/// bar for everyone else.
+pub fn link(lang: Lang, path: &str) -> String {
+    format!(
+        "href='{0}' hx-get='{0}' hx-target='#main-container' hx-push-url='true'",
+        lang.path(path)
+    )
+}
+
+/// A page template from `public/pages` with its `{{t.key}}` strings and
+/// `{{base}}` links filled in for `lang`. Fill in the page's data only after
+/// this, so text an editor wrote is never taken for a placeholder.
+pub async fn template(lang: Lang, name: &str) -> Result<String> {
+    let path: PathBuf = format!("../public/pages/{}", name).parse().unwrap();
+    let template = tokio::fs::read_to_string(path).await?;
+    Ok(i18n::translate(&template, lang))
+}
+
+fn full_title(title: &str) -> String {
+    if title.is_empty() {
+        SITE_NAME.to_string()
+    } else {
+        format!("{} | {}", escape_html(title), SITE_NAME)
+    }
+}
+
+/// Responds with a public page's content: on its own when htmx swaps it in,
+/// otherwise inside the site layout (header, navigation and footer) so deep
+/// links, search engines and browsers without JavaScript get a whole page.
+pub async fn page(req: &HttpRequest, title: &str, content: &str) -> Result<HttpResponse> {
+    page_with_meta(req, &Meta::page(Lang::of(req), req.path(), title), content).await
+}
+
+/// Like [`page`], with metadata describing a particular item.
+pub async fn page_with_meta(req: &HttpRequest, meta: &Meta, content: &str) -> Result<HttpResponse> {
+    if !is_fragment_request(req) {
+        if let Some(redirect) = i18n::redirect_to_preferred(req) {
+            return Ok(redirect);
+        }
+    }
+
+    let lang = Lang::of(req);
+    let title = full_title(&meta.title);
+    let body = if is_fragment_request(req) {
+        // htmx takes the document title from the fragment's <title>.
+        format!("<title>{}</title>{}", title, content)
+    } else {
+        template(lang, "index.html")
+            .await?
+            .replace("{{lang}}", lang.code())
+            .replace("{{title}}", &title)
+            .replace("{{meta}}", &meta.to_html())
+            .replace("{{language_switch}}", &i18n::switch_link(req))
+            .replace("{{content}}", content)
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        // The same URL answers with a fragment or a whole page, and a first
+        // visit may be redirected by language.
+        .insert_header(("Vary", "HX-Request, Accept-Language, Cookie"))
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use actix_web::test::TestRequest;
+
+    const CONTENT: &str = "<p>{{t.nav.home}} {{base}}/duyurular</p>";
+
+    async fn body(response: HttpResponse) -> String {
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn fragments_keep_content_as_written() {
+        let req = TestRequest::get()
+            .uri("/en/duyurular")
+            .insert_header(("HX-Request", "true"))
+            .to_http_request();
+        let html = body(page(&req, "Duyurular", CONTENT).await.unwrap()).await;
+        assert!(html.ends_with(CONTENT));
+    }
+
+    #[actix_web::test]
+    async fn whole_pages_translate_the_layout_but_not_the_content() {
+        let req = TestRequest::get().uri("/en/duyurular").to_http_request();
+        let html = body(page(&req, "Duyurular", CONTENT).await.unwrap()).await;
+        assert!(html.contains(CONTENT));
+        assert!(html.contains(">Home<"));
+        assert!(!html.replace(CONTENT, "").contains("{{t."));
+    }
+}
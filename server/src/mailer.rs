@@ -0,0 +1,283 @@
+use crate::src::config::{self, MailConfig, SmtpEncryption};
+use crate::src::contact::Message;
+use crate::src::db;
+use crate::src::jobs;
+use lettre::message::header::{ContentType, HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MessageBuilder};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MailError {
+    Disabled,
+    Address(lettre::address::AddressError),
+    Build(lettre::error::Error),
+    Smtp(lettre::transport::smtp::Error),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Disabled => write!(f, "e-posta gönderimi yapılandırılmamış"),
+            MailError::Address(e) => write!(f, "geçersiz e-posta adresi: {}", e),
+            MailError::Build(e) => write!(f, "e-posta oluşturulamadı: {}", e),
+            MailError::Smtp(e) => write!(f, "SMTP hatası: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MailError {}
+
+impl From<lettre::address::AddressError> for MailError {
+    fn from(e: lettre::address::AddressError) -> Self {
+        MailError::Address(e)
+    }
+}
+
+impl From<lettre::error::Error> for MailError {
+    fn from(e: lettre::error::Error) -> Self {
+        MailError::Build(e)
+    }
+}
+
+impl From<lettre::transport::smtp::Error> for MailError {
+    fn from(e: lettre::transport::smtp::Error) -> Self {
+        MailError::Smtp(e)
+    }
+}
+
+fn transport(mail: &MailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, MailError> {
+    let mut builder = match mail.encryption {
+        SmtpEncryption::None => {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(mail.host.as_str())
+        }
+        SmtpEncryption::StartTls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&mail.host)?
+        }
+        SmtpEncryption::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&mail.host)?,
+    }
+    .port(mail.port);
+
+    if let (Some(username), Some(password)) = (&mail.username, &mail.password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    Ok(builder.build())
+}
+
+/// Sends a plain-text mail using the `[mail]` section of the server config.
+pub async fn send(
+    to: &str,
+    reply_to: Option<&str>,
+    subject: &str,
+    body: &str,
+) -> Result<(), MailError> {
+    send_with(&config::get().mail, to, reply_to, subject, body).await
+}
+
+async fn send_with(
+    mail: &MailConfig,
+    to: &str,
+    reply_to: Option<&str>,
+    subject: &str,
+    body: &str,
+) -> Result<(), MailError> {
+    let mut builder = message_builder(mail, to, subject)?;
+    if let Some(reply_to) = reply_to {
+        builder = builder.reply_to(reply_to.parse::<Mailbox>()?);
+    }
+    deliver(mail, builder.body(body.to_string())?).await
+}
+
+/// Sends mail to an announcement subscriber, with the headers mail clients
+/// use to offer one-click unsubscribe (RFC 8058). `unsubscribe_url` must
+/// cancel the subscription on a POST.
+pub async fn send_to_subscriber(
+    to: &str,
+    subject: &str,
+    body: &str,
+    unsubscribe_url: &str,
+) -> Result<(), MailError> {
+    let mail = &config::get().mail;
+    let email = message_builder(mail, to, subject)?
+        .raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str("List-Unsubscribe"),
+            format!("<{}>", unsubscribe_url),
+        ))
+        .raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+            String::from("List-Unsubscribe=One-Click"),
+        ))
+        .body(body.to_string())?;
+    deliver(mail, email).await
+}
+
+fn message_builder(mail: &MailConfig, to: &str, subject: &str) -> Result<MessageBuilder, MailError> {
+    if !mail.enabled {
+        return Err(MailError::Disabled);
+    }
+
+    Ok(lettre::Message::builder()
+        .from(mail.from.parse::<Mailbox>()?)
+        .to(to.parse::<Mailbox>()?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN))
+}
+
+async fn deliver(mail: &MailConfig, email: lettre::Message) -> Result<(), MailError> {
+    transport(mail)?.send(email).await?;
+    Ok(())
+}
+
+/// Lets the assigned staff member know about a contact message. Messages
+/// nobody is assigned to yet go to the `notify` addresses instead.
+pub async fn notify_message(message: &Message) -> Result<(), MailError> {
+    let mail = &config::get().mail;
+    if !mail.enabled {
+        return Ok(());
+    }
+
+    let recipients = match message
+        .assigned_staff_id
+        .and_then(|id| db::get_staff_member(id).ok())
+        .filter(|staff| !staff.email.is_empty())
+    {
+        Some(staff) => vec![staff.email],
+        None => mail.notify.clone(),
+    };
+
+    let subject = format!("Yeni iletişim mesajı: {}", message.name);
+    let body = format!(
+        "{} <{}> iletişim formundan bir mesaj gönderdi:\n\n{}\n\nGelen kutusu: {}/dashboard",
+        message.name, message.email, message.message, mail.site_url
+    );
+    let reply_to = Some(message.email.as_str()).filter(|email| email.parse::<Mailbox>().is_ok());
+    for recipient in recipients {
+        send(&recipient, reply_to, &subject, &body).await?;
+    }
+
+    Ok(())
+}
+
+/// Arguments of the job that sends `notify_message`.
+#[derive(Deserialize, Serialize)]
+pub struct MessageJob {
+    pub message_id: i32,
+}
+
+/// Queues `notify_message` so it does not hold up the request that saved
+/// the message.
+pub fn notify_message_in_background(message_id: i32) {
+    if let Err(e) = jobs::enqueue(jobs::NOTIFY_MESSAGE, &MessageJob { message_id }) {
+        eprintln!(
+            "Failed to queue notification for message {}: {}",
+            message_id, e
+        );
+    }
+}
+
+/// Runs a queued `notify_message`. Messages deleted in the meantime are
+/// skipped.
+pub async fn send_message_notification(job: MessageJob) -> Result<(), String> {
+    let message = match db::get_message(job.message_id) {
+        Ok(message) => message,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+    notify_message(&message).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// A minimal SMTP server that accepts one message and returns its DATA.
+    async fn smtp_sink(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    writer.write_all(b"250 queued\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                continue;
+            }
+            let command = line.to_ascii_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") {
+                b"250 sink\r\n"
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+        data
+    }
+
+    fn sink_config(port: u16) -> MailConfig {
+        MailConfig {
+            enabled: true,
+            host: String::from("127.0.0.1"),
+            port,
+            from: String::from("SMYO <noreply@localhost>"),
+            ..MailConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_through_a_local_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = tokio::spawn(smtp_sink(listener));
+
+        send_with(
+            &sink_config(port),
+            "ogrenci@example.com",
+            Some("sekreter@example.com"),
+            "Re: Staj başvurusu",
+            "Başvurunuz alınmıştır.",
+        )
+        .await
+        .unwrap();
+
+        let data = sink.await.unwrap();
+        let (headers, body) = data.split_once("\n\n").unwrap();
+        assert!(headers.contains("To: ogrenci@example.com"), "{}", headers);
+        assert!(headers.contains("Reply-To: sekreter@example.com"), "{}", headers);
+        assert!(headers.contains("From: SMYO <noreply@localhost>"), "{}", headers);
+        assert!(headers.contains("Content-Transfer-Encoding: base64"), "{}", headers);
+        let body = base64::engine::general_purpose::STANDARD
+            .decode(body.replace('\n', ""))
+            .unwrap();
+        assert_eq!(String::from_utf8(body).unwrap(), "Başvurunuz alınmıştır.");
+    }
+
+    #[tokio::test]
+    async fn refuses_to_send_when_disabled() {
+        let mail = MailConfig {
+            enabled: false,
+            ..sink_config(1)
+        };
+        let result = send_with(&mail, "ogrenci@example.com", None, "Konu", "Metin").await;
+        assert!(matches!(result, Err(MailError::Disabled)));
+    }
+}
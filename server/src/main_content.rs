@@ -1,12 +1,22 @@
+use crate::src::announcements;
+use crate::src::articles;
 use crate::src::db;
+use crate::src::i18n::Lang;
+use crate::src::docs;
+use crate::src::layout;
+use crate::src::seo::Meta;
+use crate::src::slider;
 use actix_web::{web::Json, HttpRequest, HttpResponse, Responder, Result};
 use serde::Serialize;
 use serde_derive::Deserialize;
-use std::path::PathBuf;
 use actix_web::web::Query;
 
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/main_content.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    let mut content = layout::template(lang, "main_content.html").await?;
+    content = content.replace("{{slides}}", &slider::render_slides());
+    content = content.replace("{{announcements}}", &announcements::render_list(lang, 1, true)?);
+    content = content.replace("{{articles}}", &articles::render_list(lang, 1, true)?);
+    content = content.replace("{{documents}}", &docs::render_all(lang)?);
+    layout::page_with_meta(&req, &Meta::home(lang), &content).await
 }
\ No newline at end of file
@@ -1,10 +1,33 @@
+pub mod account;
 pub mod admin;
 pub mod announcements;
+pub mod api;
+pub mod api_token;
 pub mod articles;
+pub mod audit;
+pub mod client_ip;
+pub mod config;
 pub mod contact;
 pub mod db;
 pub mod docs;
+pub mod events;
+pub mod feed;
+pub mod html;
+pub mod i18n;
+pub mod images;
 pub mod index;
+pub mod jobs;
+pub mod layout;
+pub mod mailer;
 pub mod main_content;
+pub mod notify;
+pub mod openapi;
 pub mod personel;
-pub mod slider;
\ No newline at end of file
+pub mod preview;
+pub mod privacy;
+pub mod push;
+pub mod safe_path;
+pub mod seo;
+pub mod slider;
+pub mod spam;
+pub mod upload;
\ No newline at end of file
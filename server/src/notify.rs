@@ -0,0 +1,740 @@
+use crate::src::announcements::Announcement;
+use crate::src::client_ip;
+use crate::src::config;
+use crate::src::contact;
+use crate::src::db;
+use crate::src::feed;
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::jobs;
+use crate::src::layout;
+use crate::src::mailer;
+use crate::src::push::{self, PushError};
+use crate::src::spam;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::Timelike;
+use rand::RngCore;
+use serde::Serialize;
+use serde_derive::Deserialize;
+use std::time::Duration;
+
+const SUBSCRIBE_LIMIT: usize = 5;
+const SUBSCRIBE_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// Subscriptions whose opt-in link was never opened are deleted after this.
+const UNCONFIRMED_DAYS: i64 = 7;
+
+/// Stored values with the catalogue keys of their labels.
+pub const FREQUENCIES: [(&str, &str); 3] = [
+    ("immediate", "notifications.frequency.immediate"),
+    ("daily", "notifications.frequency.daily"),
+    ("weekly", "notifications.frequency.weekly"),
+];
+
+/// An e-mail subscription to new announcements.
+#[derive(Clone, Serialize)]
+pub struct Subscription {
+    pub id: i32,
+    pub email: String,
+    /// One of `FREQUENCIES`.
+    pub frequency: String,
+    /// Announcement categories to hear about; empty means all of them.
+    pub categories: Vec<String>,
+    /// Secret part of the confirm, manage and unsubscribe links.
+    #[serde(skip)]
+    pub token: String,
+    pub created_at: String,
+    /// Set once the address is confirmed; nothing is sent before that.
+    pub confirmed_at: Option<String>,
+    /// Newest announcement a digest has covered.
+    pub last_announcement_id: i32,
+    pub last_sent_at: Option<String>,
+}
+
+/// A browser that accepted Web Push notifications.
+#[derive(Clone)]
+pub struct PushSubscription {
+    pub id: i32,
+    /// Push service URL notifications are posted to.
+    pub endpoint: String,
+    /// Browser keys for payload encryption, base64url.
+    pub p256dh: String,
+    pub auth: String,
+    pub categories: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AnnouncementJob {
+    pub announcement_id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailJob {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub unsubscribe_url: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PushJob {
+    pub subscription_id: i32,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// The frequency as e-mails name it; they are written in Turkish.
+fn frequency_label(frequency: &str) -> &str {
+    FREQUENCIES
+        .iter()
+        .find(|(value, _)| *value == frequency)
+        .map_or(frequency, |(_, key)| i18n::t(Lang::Tr, key))
+}
+
+/// Announcements without a category go to everybody.
+fn wants(categories: &[String], announcement: &Announcement) -> bool {
+    announcement.category.is_empty()
+        || categories.is_empty()
+        || categories.contains(&announcement.category)
+}
+
+fn manage_url(token: &str) -> String {
+    format!("{}/bildirimler/{}", feed::site_url(), token)
+}
+
+fn unsubscribe_url(token: &str) -> String {
+    format!("{}/bildirimler/{}/iptal", feed::site_url(), token)
+}
+
+fn announcement_url(id: i32) -> String {
+    format!("{}/announcement/{}", feed::site_url(), id)
+}
+
+/// Queues notifications about a freshly published announcement.
+pub fn announcement_published(id: i32) {
+    let job = AnnouncementJob {
+        announcement_id: id,
+    };
+    if let Err(e) = jobs::enqueue(jobs::NOTIFY_ANNOUNCEMENT, &job) {
+        eprintln!(
+            "Failed to queue notifications for announcement {}: {}",
+            id, e
+        );
+    }
+}
+
+fn mail_footer(subscription: &Subscription) -> String {
+    format!(
+        "Bu e-postayı duyuru aboneliğiniz ({}) nedeniyle aldınız.\n\
+        Tercihlerinizi değiştirmek için: {}\n\
+        Aboneliği iptal etmek için: {}",
+        frequency_label(&subscription.frequency),
+        manage_url(&subscription.token),
+        unsubscribe_url(&subscription.token)
+    )
+}
+
+fn announcement_text(announcement: &Announcement) -> String {
+    let category = if announcement.category.is_empty() {
+        String::new()
+    } else {
+        format!(" · {}", announcement.category)
+    };
+    format!(
+        "{}\n{}{}\n{}\n{}",
+        announcement.title,
+        announcement.date,
+        category,
+        feed::summary(&announcement.content),
+        announcement_url(announcement.id)
+    )
+}
+
+fn queue_mail(subscription: &Subscription, subject: String, body: String) -> Result<(), String> {
+    let job = EmailJob {
+        to: subscription.email.clone(),
+        subject,
+        body: format!("{}\n\n--\n{}", body, mail_footer(subscription)),
+        unsubscribe_url: unsubscribe_url(&subscription.token),
+    };
+    jobs::enqueue(jobs::SEND_EMAIL, &job)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Splits an announcement into one job per immediate e-mail subscriber and
+/// per browser, so a failing address does not hold up the others.
+pub fn fan_out(job: AnnouncementJob) -> Result<(), String> {
+    let announcement = match db::get_announcement(job.announcement_id) {
+        Ok(announcement) => announcement,
+        // Deleted before the notifications went out.
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    if config::get().mail.enabled {
+        let subscriptions =
+            db::get_confirmed_subscriptions("immediate").map_err(|e| e.to_string())?;
+        for subscription in subscriptions
+            .iter()
+            .filter(|subscription| wants(&subscription.categories, &announcement))
+        {
+            queue_mail(
+                subscription,
+                format!("Yeni duyuru: {}", announcement.title),
+                announcement_text(&announcement),
+            )?;
+        }
+    }
+
+    let browsers = db::get_push_subscriptions().map_err(|e| e.to_string())?;
+    for browser in browsers
+        .iter()
+        .filter(|browser| wants(&browser.categories, &announcement))
+    {
+        let job = PushJob {
+            subscription_id: browser.id,
+            title: announcement.title.clone(),
+            body: feed::summary(&announcement.content),
+            url: announcement_url(announcement.id),
+        };
+        jobs::enqueue(jobs::SEND_PUSH, &job).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Whether a digest of `frequency` last sent at `last_sent_at` is due today.
+fn digest_due(frequency: &str, last_sent_at: Option<&str>, today: chrono::NaiveDate) -> bool {
+    let days = match frequency {
+        "daily" => 1,
+        "weekly" => 7,
+        _ => return false,
+    };
+    last_sent_at
+        .and_then(|sent| chrono::NaiveDate::parse_from_str(sent.get(..10)?, "%Y-%m-%d").ok())
+        .is_none_or(|sent| (today - sent).num_days() >= days)
+}
+
+/// Queues the daily and weekly digests that are due, each listing the
+/// announcements published since the subscriber's previous one. Runs every
+/// hour; nothing goes out before `[notifications] digest_hour`.
+pub fn send_digests() -> Result<(), String> {
+    let local = chrono::Local::now();
+    if !config::get().mail.enabled || local.hour() < config::get().notifications.digest_hour {
+        return Ok(());
+    }
+    let today = local.date_naive();
+
+    for frequency in ["daily", "weekly"] {
+        let subscriptions =
+            db::get_confirmed_subscriptions(frequency).map_err(|e| e.to_string())?;
+        for subscription in subscriptions {
+            if !digest_due(frequency, subscription.last_sent_at.as_deref(), today) {
+                continue;
+            }
+
+            let published = db::get_announcements_after(subscription.last_announcement_id)
+                .map_err(|e| e.to_string())?;
+            let last_id = published
+                .last()
+                .map_or(subscription.last_announcement_id, |announcement| {
+                    announcement.id
+                });
+            let wanted: Vec<String> = published
+                .iter()
+                .filter(|announcement| wants(&subscription.categories, announcement))
+                .map(announcement_text)
+                .collect();
+
+            if !wanted.is_empty() {
+                queue_mail(
+                    &subscription,
+                    format!(
+                        "{}: {} yeni duyuru",
+                        frequency_label(frequency),
+                        wanted.len()
+                    ),
+                    wanted.join("\n\n"),
+                )?;
+            }
+            db::set_subscription_sent(subscription.id, last_id, &now())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn send_email(job: EmailJob) -> Result<(), String> {
+    mailer::send_to_subscriber(&job.to, &job.subject, &job.body, &job.unsubscribe_url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn send_push(job: PushJob) -> Result<(), String> {
+    let Some(browser) =
+        db::get_push_subscription(job.subscription_id).map_err(|e| e.to_string())?
+    else {
+        // Unsubscribed since the job was queued.
+        return Ok(());
+    };
+
+    let payload = serde_json::json!({
+        "title": job.title,
+        "body": job.body,
+        "url": job.url,
+    })
+    .to_string();
+    let endpoint = browser.endpoint.clone();
+    let result = web::block(move || {
+        push::send(
+            &endpoint,
+            &browser.p256dh,
+            &browser.auth,
+            payload.as_bytes(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(PushError::Gone | PushError::InvalidSubscription) => {
+            db::delete_push_subscription(job.subscription_id).map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Deletes subscriptions nobody confirmed within `UNCONFIRMED_DAYS`, and
+/// returns how many there were.
+pub fn purge_unconfirmed() -> rusqlite::Result<usize> {
+    let cutoff = (chrono::Local::now() - chrono::Duration::days(UNCONFIRMED_DAYS))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    db::delete_unconfirmed_subscriptions(&cutoff)
+}
+
+fn frequency_options(lang: Lang, selected: &str) -> String {
+    FREQUENCIES
+        .iter()
+        .map(|(value, key)| {
+            format!(
+                "<label class='inline-flex items-center gap-2 me-4'><input type='radio' name='frequency' value='{}'{}> {}</label>",
+                value,
+                if *value == selected { " checked" } else { "" },
+                i18n::t(lang, key)
+            )
+        })
+        .collect()
+}
+
+fn category_options(lang: Lang, selected: &[String]) -> Result<String> {
+    let categories = db::get_announcement_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    if categories.is_empty() {
+        return Ok(format!(
+            "<p class='text-sm text-gray-500'>{}</p>",
+            i18n::t(lang, "notifications.no_categories")
+        ));
+    }
+
+    Ok(categories
+        .iter()
+        .map(|category| {
+            format!(
+                "<label class='inline-flex items-center gap-2 me-4'><input type='checkbox' name='categories' value='{0}'{1}> {0}</label>",
+                escape_html(category),
+                if selected.contains(category) { " checked" } else { "" }
+            )
+        })
+        .collect())
+}
+
+/// The chosen frequency and the known categories among the chosen ones.
+fn read_preferences(form: &[(String, String)]) -> Result<(String, Vec<String>)> {
+    let known = db::get_announcement_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let frequency = form
+        .iter()
+        .find(|(key, _)| key == "frequency")
+        .map(|(_, value)| value.as_str())
+        .filter(|value| FREQUENCIES.iter().any(|(frequency, _)| frequency == value))
+        .unwrap_or("immediate")
+        .to_string();
+    let categories = form
+        .iter()
+        .filter(|(key, value)| key == "categories" && known.contains(value))
+        .map(|(_, value)| value.clone())
+        .collect();
+    Ok((frequency, categories))
+}
+
+fn form_value<'a>(form: &'a [(String, String)], name: &str) -> &'a str {
+    form.iter()
+        .find(|(key, _)| key == name)
+        .map_or("", |(_, value)| value.trim())
+}
+
+/// The subscription page shown in the main container.
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    let mut content = layout::template(lang, "bildirimler.html").await?;
+    content = content.replace("{{frequencies}}", &frequency_options(lang, "immediate"));
+    content = content.replace("{{categories}}", &category_options(lang, &[])?);
+    // Without a key the page still offers e-mail; the push button stays hidden.
+    content = content.replace(
+        "{{vapid_public_key}}",
+        &push::public_key().unwrap_or_else(|e| {
+            eprintln!("Web Push is unavailable: {}", e);
+            String::new()
+        }),
+    );
+    layout::page(&req, i18n::t(lang, "title.notifications"), &content).await
+}
+
+/// Outcome of the subscription form: swapped in under the form by htmx, or
+/// a page of its own when the form was posted without JavaScript.
+async fn result_notice(req: &HttpRequest, text: &str, color: &str) -> Result<HttpResponse> {
+    if layout::is_fragment_request(req) {
+        return Ok(HttpResponse::Ok()
+            .content_type("text/html")
+            .body(notice(text, color)));
+    }
+    let lang = Lang::of(req);
+    page(
+        lang,
+        &format!(
+            "<h1 class='text-xl font-bold text-gray-900'>{}</h1>
+            {}
+            <a href='{}' class='block text-sm text-blue-600 underline'>{}</a>",
+            i18n::t(lang, "notifications.subscription"),
+            notice(text, color),
+            lang.path("/bildirimler"),
+            i18n::t(lang, "notifications.back")
+        ),
+    )
+    .await
+}
+
+/// Starts the double opt-in for an address. The answer does not reveal
+/// whether the address was already subscribed.
+pub async fn subscribe_handler(
+    req: HttpRequest,
+    form: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    if !spam::subscription_limiter().check(
+        &client_ip::client_ip(&req),
+        SUBSCRIBE_LIMIT,
+        SUBSCRIBE_WINDOW,
+    ) {
+        return result_notice(&req, i18n::t(lang, "notifications.rate_limit"), "red").await;
+    }
+    if !config::get().mail.enabled {
+        return result_notice(&req, i18n::t(lang, "notifications.unavailable"), "red").await;
+    }
+
+    let email = form_value(&form, "email");
+    if !contact::is_valid_email(email) {
+        return result_notice(&req, i18n::t(lang, "notifications.invalid_email"), "red").await;
+    }
+    let (frequency, categories) = read_preferences(&form)?;
+
+    let existing = db::get_subscription_by_email(email)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let (subscription, body) = match existing {
+        Some(subscription) if subscription.confirmed_at.is_some() => {
+            let body = format!(
+                "Bu adres duyurulara zaten abone. Tercihlerinizi değiştirmek için aşağıdaki bağlantıyı açın:\n\n{}",
+                manage_url(&subscription.token)
+            );
+            (subscription, body)
+        }
+        existing => {
+            let subscription = match existing {
+                Some(subscription) => {
+                    db::edit_subscription(subscription.id, &frequency, &categories)
+                        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+                    subscription
+                }
+                None => {
+                    let mut bytes = [0u8; 32];
+                    rand::thread_rng().fill_bytes(&mut bytes);
+                    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                    db::add_subscription(email, &frequency, &categories, &token, &now())
+                        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+                    db::get_subscription_by_email(email)
+                        .ok()
+                        .flatten()
+                        .ok_or_else(|| {
+                            actix_web::error::ErrorInternalServerError("subscription not saved")
+                        })?
+                }
+            };
+            let body = format!(
+                "Bölüm duyurularına e-posta ile abone olmak için aşağıdaki bağlantıyı açın. \
+                Bağlantıyı açana kadar size duyuru gönderilmez.\n\n{}\n\n\
+                Bu isteği siz yapmadıysanız bu e-postayı dikkate almayın; {} gün içinde kaydınız silinir.",
+                manage_url(&subscription.token),
+                UNCONFIRMED_DAYS
+            );
+            (subscription, body)
+        }
+    };
+
+    let job = EmailJob {
+        to: subscription.email.clone(),
+        subject: String::from("Duyuru aboneliği"),
+        body,
+        unsubscribe_url: unsubscribe_url(&subscription.token),
+    };
+    jobs::enqueue(jobs::SEND_EMAIL, &job)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    result_notice(
+        &req,
+        &i18n::t(lang, "notifications.link_sent").replace("{email}", &escape_html(email)),
+        "green",
+    )
+    .await
+}
+
+async fn page(lang: Lang, content: &str) -> Result<HttpResponse> {
+    let template = layout::template(lang, "subscription.html").await?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(
+        template
+            .replace("{{lang}}", lang.code())
+            .replace("{{content}}", content),
+    ))
+}
+
+fn notice(text: &str, color: &str) -> String {
+    format!("<p class='text-sm text-{}-700'>{}</p>", color, text)
+}
+
+fn subscribe_again_link(lang: Lang) -> String {
+    format!(
+        "<a href='{}' class='block text-sm text-blue-600 underline'>{}</a>",
+        lang.path("/bildirimler"),
+        i18n::t(lang, "notifications.subscribe_again")
+    )
+}
+
+fn invalid_link(lang: Lang) -> String {
+    format!(
+        "<h1 class='text-xl font-bold text-gray-900'>{}</h1>
+        {}
+        {}",
+        i18n::t(lang, "notifications.invalid_link"),
+        notice(i18n::t(lang, "notifications.not_found"), "red"),
+        subscribe_again_link(lang)
+    )
+}
+
+fn preferences_form(lang: Lang, subscription: &Subscription, message: &str) -> Result<String> {
+    Ok(format!(
+        "<h1 class='text-xl font-bold text-gray-900'>{}</h1>
+        <p class='text-sm text-gray-600'>{}</p>
+        {}
+        <form class='space-y-4' action='/bildirimler/{}' method='post'>
+            <fieldset>
+                <legend class='mb-2 text-sm font-medium text-gray-900'>{}</legend>
+                {}
+            </fieldset>
+            <fieldset>
+                <legend class='mb-2 text-sm font-medium text-gray-900'>{}</legend>
+                {}
+            </fieldset>
+            <button type='submit' class='w-full text-white bg-gray-500 hover:bg-gray-700 font-medium rounded-lg text-sm px-5 py-2.5'>{}</button>
+        </form>
+        <form action='/bildirimler/{3}/iptal' method='post'>
+            <button type='submit' class='w-full text-red-700 border border-red-700 hover:bg-red-50 font-medium rounded-lg text-sm px-5 py-2.5'>{}</button>
+        </form>",
+        i18n::t(lang, "notifications.subscription"),
+        escape_html(&subscription.email),
+        message,
+        escape_html(&subscription.token),
+        i18n::t(lang, "notifications.frequency"),
+        frequency_options(lang, &subscription.frequency),
+        i18n::t(lang, "notifications.categories_hint"),
+        category_options(lang, &subscription.categories)?,
+        i18n::t(lang, "notifications.save"),
+        i18n::t(lang, "notifications.unsubscribe")
+    ))
+}
+
+fn token_subscription(token: &str) -> Result<Option<Subscription>> {
+    db::get_subscription_by_token(token)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))
+}
+
+/// The link in every subscription mail: confirms the address the first
+/// time and lets the subscriber change their preferences.
+pub async fn manage_handler(req: HttpRequest, token: web::Path<String>) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
+    let Some(subscription) = token_subscription(&token)? else {
+        return page(lang, &invalid_link(lang)).await;
+    };
+
+    let message = if subscription.confirmed_at.is_none() {
+        let last_id = db::get_last_announcement_id()
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        db::confirm_subscription(subscription.id, &now(), last_id)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        notice(i18n::t(lang, "notifications.confirmed"), "green")
+    } else {
+        String::new()
+    };
+
+    page(lang, &preferences_form(lang, &subscription, &message)?).await
+}
+
+pub async fn update_handler(
+    req: HttpRequest,
+    token: web::Path<String>,
+    form: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
+    let Some(mut subscription) = token_subscription(&token)? else {
+        return page(lang, &invalid_link(lang)).await;
+    };
+
+    let (frequency, categories) = read_preferences(&form)?;
+    db::edit_subscription(subscription.id, &frequency, &categories)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    subscription.frequency = frequency;
+    subscription.categories = categories;
+
+    page(
+        lang,
+        &preferences_form(
+            lang,
+            &subscription,
+            &notice(i18n::t(lang, "notifications.saved"), "green"),
+        )?,
+    )
+    .await
+}
+
+/// Asks before unsubscribing, since mail scanners open links on their own.
+pub async fn unsubscribe_page(req: HttpRequest, token: web::Path<String>) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
+    let Some(subscription) = token_subscription(&token)? else {
+        return page(lang, &invalid_link(lang)).await;
+    };
+
+    page(
+        lang,
+        &format!(
+            "<h1 class='text-xl font-bold text-gray-900'>{0}</h1>
+            <p class='text-sm text-gray-600'>{1}</p>
+            <form action='/bildirimler/{2}/iptal' method='post'>
+                <button type='submit' class='w-full text-white bg-red-600 hover:bg-red-700 font-medium rounded-lg text-sm px-5 py-2.5'>{0}</button>
+            </form>",
+            i18n::t(lang, "notifications.unsubscribe"),
+            i18n::t(lang, "notifications.unsubscribe_confirm")
+                .replace("{email}", &escape_html(&subscription.email)),
+            escape_html(&subscription.token)
+        ),
+    )
+    .await
+}
+
+/// Also the target of one-click unsubscribe from mail clients (RFC 8058),
+/// so it needs nothing but the token.
+pub async fn unsubscribe_handler(
+    req: HttpRequest,
+    token: web::Path<String>,
+) -> Result<HttpResponse> {
+    let lang = Lang::preferred(&req);
+    if let Some(subscription) = token_subscription(&token)? {
+        db::delete_subscription(subscription.id)
+            .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    }
+
+    page(
+        lang,
+        &format!(
+            "<h1 class='text-xl font-bold text-gray-900'>{}</h1>
+            {}
+            {}",
+            i18n::t(lang, "notifications.unsubscribed"),
+            notice(i18n::t(lang, "notifications.unsubscribed_notice"), "green"),
+            subscribe_again_link(lang)
+        ),
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct PushKeys {
+    p256dh: String,
+    auth: String,
+}
+
+/// `PushSubscription.toJSON()` from the browser, plus the chosen categories.
+#[derive(Deserialize)]
+pub struct PushSubscriptionInput {
+    endpoint: String,
+    keys: PushKeys,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PushEndpoint {
+    endpoint: String,
+}
+
+pub async fn push_subscribe_handler(
+    req: HttpRequest,
+    input: web::Json<PushSubscriptionInput>,
+) -> Result<HttpResponse> {
+    if !spam::subscription_limiter().check(
+        &client_ip::client_ip(&req),
+        SUBSCRIBE_LIMIT,
+        SUBSCRIBE_WINDOW,
+    ) {
+        return Ok(HttpResponse::TooManyRequests().finish());
+    }
+
+    let input = input.into_inner();
+    // The server posts to this address, so only accept an endpoint on a
+    // known browser push service, with keys we can encrypt for.
+    if !push::is_push_service(&input.endpoint)
+        || push::decode_keys(&input.keys.p256dh, &input.keys.auth).is_err()
+    {
+        return Ok(HttpResponse::BadRequest().body("Geçersiz push aboneliği"));
+    }
+    let known = db::get_announcement_categories()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    db::save_push_subscription(&PushSubscription {
+        id: 0,
+        endpoint: input.endpoint,
+        p256dh: input.keys.p256dh,
+        auth: input.keys.auth,
+        categories: input
+            .categories
+            .into_iter()
+            .filter(|category| known.contains(category))
+            .collect(),
+        created_at: now(),
+    })
+    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn push_unsubscribe_handler(input: web::Json<PushEndpoint>) -> Result<HttpResponse> {
+    db::delete_push_subscription_by_endpoint(&input.endpoint)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,107 @@
+use crate::src::api;
+use crate::src::api_token;
+use actix_web::{HttpResponse, Result};
+use std::path::PathBuf;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "CÜ Sivas TBMYO Bilgisayar Teknolojileri Bölümü API",
+        description = "Public reads of announcements, articles, documents and staff, and \
+            token-authenticated writes. Tokens are created under API Anahtarları in the dashboard. \
+            Any site may read from a browser; writes are accepted from the same origin or from \
+            servers, since cross-origin preflight requests are not answered."
+    ),
+    paths(
+        api::announcements_handler,
+        api::announcement_handler,
+        api::create_announcement_handler,
+        api::update_announcement_handler,
+        api::delete_announcement_handler,
+        api::articles_handler,
+        api::article_handler,
+        api::create_article_handler,
+        api::update_article_handler,
+        api::delete_article_handler,
+        api::upload_image_handler,
+        api::documents_handler,
+        api::document_handler,
+        api::staff_handler,
+        api::staff_member_handler,
+    ),
+    modifiers(&BearerAuth)
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let scopes: Vec<String> = api_token::SCOPES
+            .iter()
+            .map(|(scope, _)| format!("`{}`", scope))
+            .collect();
+        let scheme = HttpBuilder::new()
+            .scheme(HttpAuthScheme::Bearer)
+            .description(Some(format!(
+                "API token starting with `{}`. Scopes: {}.",
+                api_token::TOKEN_PREFIX,
+                scopes.join(", ")
+            )))
+            .build();
+        // Not a published package, so there is no license to show.
+        openapi.info.license = None;
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme("bearer", SecurityScheme::Http(scheme));
+    }
+}
+
+pub fn spec_json() -> String {
+    ApiDoc::openapi()
+        .to_pretty_json()
+        .expect("OpenAPI document serialises")
+}
+
+pub async fn spec_handler() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(("Access-Control-Allow-Origin", "*"))
+        .body(spec_json())
+}
+
+/// Reference page rendering `/api/openapi.json` in the browser.
+pub async fn docs_handler() -> Result<HttpResponse> {
+    let path: PathBuf = "../public/pages/api_docs.html".parse().unwrap();
+    let content = tokio::fs::read_to_string(path).await?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The committed copy of the specification, so changes to the API show
+    /// up in review. Regenerate it with `UPDATE_OPENAPI=1 cargo test openapi`.
+    const SPEC_FILE: &str = "openapi.json";
+
+    #[test]
+    fn openapi_spec_matches_committed_file() {
+        let generated = spec_json() + "\n";
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(SPEC_FILE, &generated).unwrap();
+            return;
+        }
+
+        let committed = std::fs::read_to_string(SPEC_FILE).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "{} is out of date with the API handlers; run `UPDATE_OPENAPI=1 cargo test openapi` \
+            and commit the result",
+            SPEC_FILE
+        );
+    }
+}
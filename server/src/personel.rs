@@ -1,8 +1,119 @@
+use crate::src::db;
+use crate::src::html::escape_html;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
 use actix_web::{HttpRequest, HttpResponse, Result};
-use std::path::PathBuf;
+use serde::Serialize;
+use utoipa::ToSchema;
 
-pub async fn handler(_req: HttpRequest) -> Result<HttpResponse> {
-    let path: PathBuf = "../public/pages/personel.html".parse().unwrap();
-    let content = tokio::fs::read_to_string(path).await?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(content))
-}
\ No newline at end of file
+pub const DEFAULT_PHOTO: &str = "/assets/image/avatar.png";
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Staff {
+    pub id: i32,
+    pub name: String,
+    pub title: String,
+    pub program: String,
+    pub office: String,
+    pub phone: String,
+    pub email: String,
+    pub photo: String,
+    pub office_hours: String,
+    pub display_order: i32,
+}
+
+impl Staff {
+    /// Name with the academic title in front, e.g. "Öğr. Gör. Ayşe Yılmaz".
+    pub fn full_name(&self) -> String {
+        if self.title.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.title, self.name)
+        }
+    }
+}
+
+fn render_detail(label: &str, value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p class='text-sm text-center text-gray-700'><span class='font-semibold'>{}:</span> {}</p>",
+            label,
+            escape_html(value)
+        )
+    }
+}
+
+fn render_staff(lang: Lang, staff: &Staff) -> String {
+    let email = if staff.email.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p class='text-sm text-center'><a href='mailto:{0}' class='text-blue-600 underline'>{0}</a></p>",
+            escape_html(&staff.email)
+        )
+    };
+
+    format!(
+        "<div class='w-full sm:w-1/2 md:w-1/3 lg:w-1/4 xl:w-1/5 px-4 mb-4'>
+            <div class='bg-gray-200 rounded shadow p-6 h-full'>
+                <img class='w-full mb-4 rounded-full' src='{}' alt='{}' loading='lazy'>
+                <h3 class='text-xl text-center font-bold'>{}</h3>
+                <div class='mt-4 space-y-1'>{}{}{}{}</div>
+            </div>
+        </div>",
+        escape_html(&staff.photo),
+        escape_html(&staff.name),
+        escape_html(&staff.full_name()),
+        render_detail(i18n::t(lang, "staff.office"), &staff.office),
+        render_detail(i18n::t(lang, "staff.phone"), &staff.phone),
+        render_detail(i18n::t(lang, "staff.office_hours"), &staff.office_hours),
+        email
+    )
+}
+
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    let lang = Lang::of(&req);
+    let staff =
+        db::get_staff().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // Rows come ordered by program, so each run of equal programs is one group.
+    let mut groups: Vec<(String, String)> = Vec::new();
+    for member in &staff {
+        match groups.last_mut() {
+            Some((program, cards)) if *program == member.program => {
+                cards.push_str(&render_staff(lang, member))
+            }
+            _ => groups.push((member.program.clone(), render_staff(lang, member))),
+        }
+    }
+
+    let programs: String = groups
+        .iter()
+        .map(|(program, cards)| {
+            format!(
+                "<h3 class='mt-12 mb-4 text-2xl font-semibold text-center'>{}</h3>
+                <section class='flex flex-wrap items-stretch justify-center'>{}</section>",
+                escape_html(program),
+                cards
+            )
+        })
+        .collect();
+
+    let mut content = layout::template(lang, "personel.html").await?;
+    let none = format!(
+        "<p class='mt-10 text-center text-gray-600'>{}</p>",
+        i18n::t(lang, "staff.none")
+    );
+    content = content.replace(
+        "{{programs}}",
+        if programs.is_empty() {
+            &none
+        } else {
+            &programs
+        },
+    );
+
+    layout::page(&req, i18n::t(lang, "title.staff"), &content).await
+}
@@ -0,0 +1,205 @@
+use crate::src::html::escape_html;
+use roxmltree::{Document as XmlDocument, Node};
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const WORD_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+
+fn read_document_xml(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")?
+        .read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+fn is_word(node: &Node, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node.tag_name().namespace() == Some(WORD_NS)
+}
+
+fn word_child<'a, 'input>(node: &Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_word(child, name))
+}
+
+fn word_attr<'a>(node: &Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attribute((WORD_NS, name))
+}
+
+/// `w:b`/`w:i` toggles are on unless explicitly set to false.
+fn has_toggle(properties: Option<Node>, name: &str) -> bool {
+    properties
+        .and_then(|props| word_child(&props, name))
+        .map(|toggle| {
+            !matches!(
+                word_attr(&toggle, "val"),
+                Some("0") | Some("false") | Some("none")
+            )
+        })
+        .unwrap_or(false)
+}
+
+fn run_text(run: &Node) -> String {
+    let mut text = String::new();
+    for child in run.children() {
+        if is_word(&child, "t") {
+            text.push_str(child.text().unwrap_or(""));
+        } else if is_word(&child, "tab") {
+            text.push('\t');
+        } else if is_word(&child, "br") || is_word(&child, "cr") {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn paragraph_runs<'a, 'input>(paragraph: &Node<'a, 'input>) -> Vec<Node<'a, 'input>> {
+    paragraph
+        .descendants()
+        .filter(|node| is_word(node, "r"))
+        .collect()
+}
+
+fn paragraph_text(paragraph: &Node) -> String {
+    paragraph_runs(paragraph).iter().map(run_text).collect()
+}
+
+fn heading_level(paragraph: &Node) -> Option<u8> {
+    let style = word_child(paragraph, "pPr")
+        .and_then(|props| word_child(&props, "pStyle"))
+        .and_then(|style| word_attr(&style, "val"))?
+        .to_lowercase();
+
+    if style == "title" || style == "konubaşlığı" {
+        return Some(1);
+    }
+    let level = style
+        .strip_prefix("heading")
+        .or_else(|| style.strip_prefix("başlık"))?
+        .parse::<u8>()
+        .ok()?;
+    Some((level + 1).min(6))
+}
+
+fn paragraph_html(paragraph: &Node) -> String {
+    // Word splits text into many runs; merge neighbours with the same
+    // formatting so the output is not a wall of <strong> fragments.
+    let mut segments: Vec<(String, [bool; 3])> = Vec::new();
+    for run in paragraph_runs(paragraph) {
+        let text = run_text(&run);
+        if text.is_empty() {
+            continue;
+        }
+        let properties = word_child(&run, "rPr");
+        let style = [
+            has_toggle(properties, "b"),
+            has_toggle(properties, "i"),
+            has_toggle(properties, "u"),
+        ];
+        match segments.last_mut() {
+            Some((last_text, last_style)) if *last_style == style => last_text.push_str(&text),
+            _ => segments.push((text, style)),
+        }
+    }
+
+    let mut inner = String::new();
+    for (text, [bold, italic, underline]) in segments {
+        let mut html = escape_html(&text).replace('\n', "<br>");
+        if bold {
+            html = format!("<strong>{}</strong>", html);
+        }
+        if italic {
+            html = format!("<em>{}</em>", html);
+        }
+        if underline {
+            html = format!("<u>{}</u>", html);
+        }
+        inner.push_str(&html);
+    }
+
+    if inner.trim().is_empty() {
+        return String::new();
+    }
+
+    match heading_level(paragraph) {
+        Some(level) => format!("<h{0} class='font-bold mt-4 mb-2'>{1}</h{0}>", level, inner),
+        None => format!("<p class='mb-2'>{}</p>", inner),
+    }
+}
+
+fn table_html(table: &Node) -> String {
+    let mut html = String::from(
+        "<table class='table-auto border-collapse border border-gray-400 my-4 w-full'>",
+    );
+    for row in table.children().filter(|node| is_word(node, "tr")) {
+        html.push_str("<tr>");
+        for cell in row.children().filter(|node| is_word(node, "tc")) {
+            html.push_str("<td class='border border-gray-400 px-2 py-1 align-top'>");
+            html.push_str(&block_html(&cell));
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn block_html(container: &Node) -> String {
+    container
+        .children()
+        .map(|child| {
+            if is_word(&child, "p") {
+                paragraph_html(&child)
+            } else if is_word(&child, "tbl") {
+                table_html(&child)
+            } else if is_word(&child, "sdt") {
+                word_child(&child, "sdtContent")
+                    .map(|content| block_html(&content))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .collect()
+}
+
+/// Converts the body of a .docx file to simple HTML (headings, paragraphs,
+/// bold/italic/underline runs and tables). Images and layout are dropped.
+pub fn docx_to_html(path: &Path) -> Result<String, Box<dyn Error>> {
+    let xml = read_document_xml(path)?;
+    let document = XmlDocument::parse(&xml)?;
+    let body = document
+        .descendants()
+        .find(|node| is_word(node, "body"))
+        .ok_or("document.xml has no body")?;
+
+    Ok(block_html(&body))
+}
+
+/// Plain text of a .docx file, one paragraph per line, for search indexing.
+pub fn docx_to_text(path: &Path) -> Result<String, Box<dyn Error>> {
+    let xml = read_document_xml(path)?;
+    let document = XmlDocument::parse(&xml)?;
+
+    Ok(document
+        .descendants()
+        .filter(|node| is_word(node, "p"))
+        .map(|paragraph| paragraph_text(&paragraph))
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+/// Extracts searchable text for the formats we know how to read.
+pub fn extract_text(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "docx" => docx_to_text(path).ok(),
+        "txt" => std::fs::read_to_string(path).ok(),
+        _ => None,
+    }
+}
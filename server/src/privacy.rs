@@ -0,0 +1,142 @@
+use crate::src::client_ip;
+use crate::src::config;
+use crate::src::contact::{self, Message, MessageReply};
+use crate::src::db;
+use crate::src::i18n::{self, Lang};
+use crate::src::layout;
+use crate::src::notify::{self, Subscription};
+use actix_web::{HttpRequest, HttpResponse, Result};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct MessageRecord {
+    #[serde(flatten)]
+    pub message: Message,
+    pub replies: Vec<MessageReply>,
+}
+
+/// Everything stored about one e-mail address, as handed out for a KVKK
+/// access request.
+#[derive(Serialize)]
+pub struct PersonalData {
+    pub email: String,
+    pub exported_at: String,
+    pub messages: Vec<MessageRecord>,
+    /// Announcement e-mail subscription, if there is one.
+    pub subscription: Option<Subscription>,
+}
+
+pub fn find_personal_data(email: &str) -> rusqlite::Result<PersonalData> {
+    let mut messages = Vec::new();
+    for message in db::get_messages_by_email(email)? {
+        let replies = db::get_message_replies(message.id)?;
+        messages.push(MessageRecord { message, replies });
+    }
+
+    Ok(PersonalData {
+        email: email.trim().to_string(),
+        exported_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        messages,
+        subscription: db::get_subscription_by_email(email.trim())?,
+    })
+}
+
+/// Deletes every message sent with `email`, and any announcement
+/// subscription, and returns the message ids.
+pub fn erase_personal_data(email: &str) -> rusqlite::Result<Vec<i32>> {
+    let messages = db::get_messages_by_email(email)?;
+    for message in &messages {
+        contact::delete_message_with_attachment(message.id)?;
+    }
+    db::delete_subscription_by_email(email)?;
+
+    Ok(messages.iter().map(|message| message.id).collect())
+}
+
+/// Strips the sender's details from every message sent with `email` but keeps
+/// the messages themselves, and returns the ids of the changed messages. A
+/// subscription cannot work without the address, so it is deleted.
+pub fn anonymize_personal_data(email: &str) -> rusqlite::Result<Vec<i32>> {
+    db::delete_subscription_by_email(email)?;
+    let messages = db::get_messages_by_email(email)?;
+    for message in &messages {
+        db::anonymize_message(message.id)?;
+        if let Some(filename) = &message.attachment_filename {
+            let _ = std::fs::remove_file(Path::new(contact::ATTACHMENTS_DIR).join(filename));
+        }
+    }
+
+    Ok(messages.iter().map(|message| message.id).collect())
+}
+
+/// Deletes messages older than `[privacy] message_retention_days` and returns
+/// how many were removed.
+pub fn purge_old_messages() -> rusqlite::Result<usize> {
+    let days = config::get().privacy.message_retention_days;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = (chrono::Local::now() - chrono::Duration::days(days.into()))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    let ids = db::get_message_ids_before(&cutoff)?;
+    for id in &ids {
+        contact::delete_message_with_attachment(*id)?;
+    }
+
+    Ok(ids.len())
+}
+
+/// Applies the retention policies. Runs once a day as a periodic job.
+pub fn apply_retention() -> Result<(), String> {
+    match purge_old_messages().map_err(|e| e.to_string())? {
+        0 => (),
+        count => println!("Deleted {} messages past their retention period", count),
+    }
+    match client_ip::anonymize_old_ips().map_err(|e| e.to_string())? {
+        0 => (),
+        count => println!("Anonymised {} stored IP addresses", count),
+    }
+    match notify::purge_unconfirmed().map_err(|e| e.to_string())? {
+        0 => (),
+        count => println!("Deleted {} unconfirmed subscriptions", count),
+    }
+    Ok(())
+}
+
+fn retention_text(days: u32, kept: &str, after: &str) -> String {
+    if days == 0 {
+        format!("{} silinmesini talep edene kadar saklanır.", kept)
+    } else {
+        format!("{} {} gün sonra {}.", kept, days, after)
+    }
+}
+
+/// The privacy notice (aydınlatma metni) linked from the contact form.
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+    let privacy = &config::get().privacy;
+
+    let lang = Lang::of(&req);
+    let mut content = layout::template(lang, "privacy.html").await?;
+    content = content.replace(
+        "{{message_retention}}",
+        &retention_text(
+            privacy.message_retention_days,
+            "Mesajlar, yanıtları ve ek dosyaları",
+            "otomatik olarak silinir",
+        ),
+    );
+    content = content.replace(
+        "{{ip_retention}}",
+        &retention_text(
+            privacy.ip_retention_days,
+            "IP adresleri",
+            "son kısmı silinerek anonim hale getirilir",
+        ),
+    );
+
+    layout::page(&req, i18n::t(lang, "title.privacy"), &content).await
+}
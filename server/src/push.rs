@@ -0,0 +1,361 @@
+use crate::src::config;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use ring::{aead, agreement, hkdf};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Size of the single encrypted record; payloads are far smaller.
+const RECORD_SIZE: u32 = 4096;
+/// How long push services keep a notification for an offline browser.
+const TTL_SECS: u32 = 24 * 60 * 60;
+/// Lifetime of the VAPID token; push services refuse more than a day.
+const VAPID_TOKEN_SECS: i64 = 12 * 60 * 60;
+/// Hosts of the push services browsers hand out endpoints for (Chrome/Edge
+/// via FCM, Firefox, Edge's own service and Safari). The server posts to the
+/// endpoint, so anything else could point it at an internal address.
+const PUSH_SERVICES: [&str; 4] = [
+    "fcm.googleapis.com",
+    "push.services.mozilla.com",
+    "notify.windows.com",
+    "push.apple.com",
+];
+
+#[derive(Debug)]
+pub enum PushError {
+    Key(String),
+    /// The browser sent keys or an endpoint we cannot use.
+    InvalidSubscription,
+    Crypto,
+    /// The push service no longer knows the subscription; it should be dropped.
+    Gone,
+    Http(String),
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::Key(e) => write!(f, "VAPID anahtarı kullanılamıyor: {}", e),
+            PushError::InvalidSubscription => write!(f, "geçersiz push aboneliği"),
+            PushError::Crypto => write!(f, "bildirim şifrelenemedi"),
+            PushError::Gone => write!(f, "push aboneliği artık geçerli değil"),
+            PushError::Http(e) => write!(f, "push servisi hatası: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+impl From<ring::error::Unspecified> for PushError {
+    fn from(_: ring::error::Unspecified) -> Self {
+        PushError::Crypto
+    }
+}
+
+/// Loads the VAPID key from `[notifications] vapid_key_path`, creating it on
+/// first use.
+fn load_key() -> Result<EcdsaKeyPair, String> {
+    let path = &config::get().notifications.vapid_key_path;
+    let rng = SystemRandom::new();
+    let pkcs8 = match std::fs::read(path) {
+        Ok(pkcs8) => pkcs8,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let document = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+                .map_err(|_| String::from("anahtar oluşturulamadı"))?;
+            std::fs::write(path, document.as_ref()).map_err(|e| e.to_string())?;
+            document.as_ref().to_vec()
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &rng)
+        .map_err(|e| e.to_string())
+}
+
+fn signing_key() -> Result<&'static EcdsaKeyPair, PushError> {
+    static KEY: OnceLock<Result<EcdsaKeyPair, String>> = OnceLock::new();
+    KEY.get_or_init(load_key)
+        .as_ref()
+        .map_err(|e| PushError::Key(e.clone()))
+}
+
+/// The application server key browsers subscribe with, base64url encoded.
+pub fn public_key() -> Result<String, PushError> {
+    Ok(URL_SAFE_NO_PAD.encode(signing_key()?.public_key().as_ref()))
+}
+
+/// Whether `endpoint` is an https URL on one of the `PUSH_SERVICES`, or a
+/// subdomain of one, on the default port.
+pub fn is_push_service(endpoint: &str) -> bool {
+    let Some(rest) = endpoint.strip_prefix("https://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.contains(['@', '\\']) {
+        return false;
+    }
+    let host = match authority.split_once(':') {
+        Some((host, "443")) => host,
+        Some(_) => return false,
+        None => authority,
+    }
+    .to_ascii_lowercase();
+
+    PUSH_SERVICES.iter().any(|service| {
+        host == *service
+            || host
+                .strip_suffix(service)
+                .is_some_and(|subdomain| subdomain.ends_with('.'))
+    })
+}
+
+/// `Authorization` header proving to the push service that the notification
+/// comes from the key the browser subscribed with (RFC 8292).
+fn vapid_authorization(key: &EcdsaKeyPair, endpoint: &str) -> Result<String, PushError> {
+    let scheme_end = endpoint.find("://").ok_or(PushError::InvalidSubscription)? + 3;
+    let origin_end = endpoint[scheme_end..]
+        .find('/')
+        .map_or(endpoint.len(), |slash| scheme_end + slash);
+
+    let header = URL_SAFE_NO_PAD.encode(r#"{"typ":"JWT","alg":"ES256"}"#);
+    let claims = URL_SAFE_NO_PAD.encode(
+        serde_json::json!({
+            "aud": &endpoint[..origin_end],
+            "exp": chrono::Utc::now().timestamp() + VAPID_TOKEN_SECS,
+            "sub": config::get().notifications.vapid_subject,
+        })
+        .to_string(),
+    );
+    let message = format!("{}.{}", header, claims);
+    let signature = key.sign(&SystemRandom::new(), message.as_bytes())?;
+
+    Ok(format!(
+        "vapid t={}.{}, k={}",
+        message,
+        URL_SAFE_NO_PAD.encode(signature.as_ref()),
+        URL_SAFE_NO_PAD.encode(key.public_key().as_ref())
+    ))
+}
+
+struct Length(usize);
+
+impl hkdf::KeyType for Length {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+fn hkdf(salt: &[u8], secret: &[u8], info: &[&[u8]], out: &mut [u8]) -> Result<(), PushError> {
+    hkdf::Salt::new(hkdf::HKDF_SHA256, salt)
+        .extract(secret)
+        .expand(info, Length(out.len()))?
+        .fill(out)?;
+    Ok(())
+}
+
+/// Decodes the base64url `p256dh` and `auth` keys of a browser subscription.
+pub fn decode_keys(p256dh: &str, auth: &str) -> Result<(Vec<u8>, Vec<u8>), PushError> {
+    let decode = |key: &str| {
+        URL_SAFE_NO_PAD
+            .decode(key.trim_end_matches('='))
+            .map_err(|_| PushError::InvalidSubscription)
+    };
+    let (p256dh, auth) = (decode(p256dh)?, decode(auth)?);
+    if p256dh.len() != 65 || auth.len() != 16 {
+        return Err(PushError::InvalidSubscription);
+    }
+    Ok((p256dh, auth))
+}
+
+/// Encrypts `plaintext` for one browser with the `aes128gcm` content coding
+/// of RFC 8291, using the `p256dh` and `auth` keys from its subscription.
+/// `salt` and `private_key` must be fresh for every message.
+pub fn encrypt(
+    p256dh: &[u8],
+    auth: &[u8],
+    salt: &[u8; 16],
+    private_key: agreement::EphemeralPrivateKey,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, PushError> {
+    if p256dh.len() != 65 || auth.len() != 16 {
+        return Err(PushError::InvalidSubscription);
+    }
+
+    let public_key = private_key.compute_public_key()?;
+    let shared_secret = agreement::agree_ephemeral(
+        private_key,
+        &agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, p256dh),
+        |secret| secret.to_vec(),
+    )?;
+
+    let mut ikm = [0u8; 32];
+    hkdf(
+        auth,
+        &shared_secret,
+        &[b"WebPush: info\0", p256dh, public_key.as_ref()],
+        &mut ikm,
+    )?;
+
+    let mut cek = [0u8; 16];
+    hkdf(salt, &ikm, &[b"Content-Encoding: aes128gcm\0"], &mut cek)?;
+    let mut nonce = [0u8; 12];
+    hkdf(salt, &ikm, &[b"Content-Encoding: nonce\0"], &mut nonce)?;
+
+    // A single record, so it is also the last one.
+    let mut record = plaintext.to_vec();
+    record.push(2);
+    let key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_128_GCM, &cek)?);
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::empty(),
+        &mut record,
+    )?;
+
+    let mut body = Vec::with_capacity(16 + 4 + 1 + 65 + record.len());
+    body.extend_from_slice(salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(public_key.as_ref().len() as u8);
+    body.extend_from_slice(public_key.as_ref());
+    body.extend_from_slice(&record);
+    Ok(body)
+}
+
+/// Delivers `payload` to one browser. Blocks until the push service answers,
+/// so call it off the async runtime.
+pub fn send(endpoint: &str, p256dh: &str, auth: &str, payload: &[u8]) -> Result<(), PushError> {
+    if !is_push_service(endpoint) {
+        return Err(PushError::InvalidSubscription);
+    }
+    let (p256dh, auth) = decode_keys(p256dh, auth)?;
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; 16];
+    ring::rand::SecureRandom::fill(&rng, &mut salt)?;
+    let private_key = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)?;
+    let body = encrypt(&p256dh, &auth, &salt, private_key, payload)?;
+
+    // A redirect could lead away from the push service.
+    let response = ureq::AgentBuilder::new()
+        .redirects(0)
+        .build()
+        .post(endpoint)
+        .set(
+            "Authorization",
+            &vapid_authorization(signing_key()?, endpoint)?,
+        )
+        .set("Content-Encoding", "aes128gcm")
+        .set("Content-Type", "application/octet-stream")
+        .set("TTL", &TTL_SECS.to_string())
+        .send_bytes(&body);
+    match response {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(404 | 410, _)) => Err(PushError::Gone),
+        Err(ureq::Error::Status(status, response)) => Err(PushError::Http(format!(
+            "{} {}",
+            status,
+            response.into_string().unwrap_or_default()
+        ))),
+        Err(e) => Err(PushError::Http(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
+
+    fn decode(value: &str) -> Vec<u8> {
+        URL_SAFE_NO_PAD.decode(value).unwrap()
+    }
+
+    /// The worked example of RFC 8291, section 5.
+    #[test]
+    fn encrypts_the_rfc_8291_example() {
+        let as_private = decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw");
+        let private_key = agreement::EphemeralPrivateKey::generate(
+            &agreement::ECDH_P256,
+            &ring::test::rand::FixedSliceRandom { bytes: &as_private },
+        )
+        .unwrap();
+        let (p256dh, auth) = decode_keys(
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            "BTBZMqHH6r4Tts7J_aSIgg",
+        )
+        .unwrap();
+        let salt: [u8; 16] = decode("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap();
+
+        let body = encrypt(
+            &p256dh,
+            &auth,
+            &salt,
+            private_key,
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn vapid_token_verifies_with_the_advertised_key() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+
+        let header = vapid_authorization(&key, "https://fcm.googleapis.com/fcm/send/abc").unwrap();
+        let (token, public_key) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .unwrap();
+        let (message, signature) = token.rsplit_once('.').unwrap();
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, decode(public_key))
+            .verify(message.as_bytes(), &decode(signature))
+            .unwrap();
+
+        let (header, claims) = message.split_once('.').unwrap();
+        let header: serde_json::Value = serde_json::from_slice(&decode(header)).unwrap();
+        assert_eq!(header["alg"], "ES256");
+        let claims: serde_json::Value = serde_json::from_slice(&decode(claims)).unwrap();
+        assert_eq!(claims["aud"], "https://fcm.googleapis.com");
+        assert_eq!(
+            claims["sub"],
+            config::get().notifications.vapid_subject.as_str()
+        );
+        let expires = claims["exp"].as_i64().unwrap() - chrono::Utc::now().timestamp();
+        assert!(0 < expires && expires <= VAPID_TOKEN_SECS);
+    }
+
+    #[test]
+    fn accepts_browser_push_services() {
+        for endpoint in [
+            "https://fcm.googleapis.com/fcm/send/abc",
+            "https://updates.push.services.mozilla.com/wpush/v2/abc",
+            "https://wns2-db5p.notify.windows.com/w/?token=abc",
+            "https://web.push.apple.com/abc",
+            "https://FCM.googleapis.com:443/fcm/send/abc",
+        ] {
+            assert!(is_push_service(endpoint), "{}", endpoint);
+        }
+    }
+
+    #[test]
+    fn rejects_other_hosts() {
+        for endpoint in [
+            "http://fcm.googleapis.com/fcm/send/abc",
+            "https://127.0.0.1/abc",
+            "https://localhost/abc",
+            "https://169.254.169.254/latest/meta-data",
+            "https://fcm.googleapis.com.example.com/abc",
+            "https://evilfcm.googleapis.com.attacker.net/abc",
+            "https://notfcm.googleapis.com@10.0.0.1/abc",
+            "https://fcm.googleapis.com:8080/abc",
+            "https://example.com/?https://fcm.googleapis.com",
+        ] {
+            assert!(!is_push_service(endpoint), "{}", endpoint);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves a user-supplied file name against `root`, refusing anything that
+/// would end up outside of it (`..`, absolute paths, symlinks pointing away).
+pub fn resolve(root: &str, requested: &str) -> Result<PathBuf> {
+    let requested_path = Path::new(requested);
+    let is_plain = !requested.is_empty()
+        && requested_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !is_plain {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            "path escapes the allowed directory",
+        ));
+    }
+
+    let root = Path::new(root).canonicalize()?;
+    let resolved = root.join(requested_path).canonicalize()?;
+    if !resolved.starts_with(&root) {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            "path escapes the allowed directory",
+        ));
+    }
+
+    Ok(resolved)
+}
+
+/// Maps a resolver error onto the response the client should see.
+pub fn to_http_error(error: Error) -> actix_web::Error {
+    match error.kind() {
+        ErrorKind::NotFound => actix_web::error::ErrorNotFound("File not found"),
+        ErrorKind::PermissionDenied => actix_web::error::ErrorBadRequest("Invalid file name"),
+        _ => actix_web::error::ErrorInternalServerError(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fresh directory with `root/report.pdf`, `root/sub/notes.txt` and an
+    /// `outside.txt` next to `root`. Returns the directory and the root.
+    fn fixture() -> (PathBuf, String) {
+        let base = std::env::temp_dir().join(format!("safe-path-{}", uuid::Uuid::new_v4()));
+        let root = base.join("root");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("report.pdf"), b"pdf").unwrap();
+        fs::write(root.join("sub").join("notes.txt"), b"notes").unwrap();
+        fs::write(base.join("outside.txt"), b"secret").unwrap();
+        let root_str = root.to_str().unwrap().to_string();
+        (base, root_str)
+    }
+
+    #[test]
+    fn accepts_plain_names() {
+        let (base, root) = fixture();
+        let resolved = resolve(&root, "report.pdf").unwrap();
+        assert_eq!(resolved, Path::new(&root).canonicalize().unwrap().join("report.pdf"));
+        assert!(resolve(&root, "sub/notes.txt").is_ok());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn rejects_parent_components() {
+        let (base, root) = fixture();
+        for name in ["../outside.txt", "sub/../../outside.txt", "..", "./report.pdf", ""] {
+            let error = resolve(&root, name).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::PermissionDenied, "{}", name);
+        }
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        let (base, root) = fixture();
+        let absolute = base.join("outside.txt");
+        let error = resolve(&root, absolute.to_str().unwrap()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(
+            resolve(&root, "/etc/passwd").unwrap_err().kind(),
+            ErrorKind::PermissionDenied
+        );
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leaving_the_root() {
+        let (base, root) = fixture();
+        std::os::unix::fs::symlink(base.join("outside.txt"), Path::new(&root).join("link.txt"))
+            .unwrap();
+        std::os::unix::fs::symlink(&base, Path::new(&root).join("up")).unwrap();
+
+        let error = resolve(&root, "link.txt").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        let error = resolve(&root, "up/outside.txt").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn missing_files_are_not_found() {
+        let (base, root) = fixture();
+        assert_eq!(resolve(&root, "missing.pdf").unwrap_err().kind(), ErrorKind::NotFound);
+        fs::remove_dir_all(base).unwrap();
+    }
+}
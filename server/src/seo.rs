@@ -0,0 +1,271 @@
+use crate::src::db;
+use crate::src::feed::site_url;
+use crate::src::html::{escape_html, excerpt};
+use crate::src::i18n::{self, Lang};
+use crate::src::layout::SITE_NAME;
+use actix_web::{HttpResponse, Result};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+/// Search engines show about this many characters of a description.
+const DESCRIPTION_LENGTH: usize = 160;
+const DEFAULT_IMAGE: &str = "/assets/image/logonix.png";
+/// Public pages listed in the sitemap besides the content items.
+const STATIC_PAGES: [&str; 8] = [
+    "/",
+    "/duyurular",
+    "/makaleler",
+    "/dokumanlar",
+    "/etkinlikler",
+    "/personel",
+    "/contact",
+    "/gizlilik",
+];
+
+/// What a page tells search engines and link previews about itself.
+pub struct Meta {
+    pub lang: Lang,
+    pub title: String,
+    pub description: String,
+    /// Canonical path, without the query string. The page's versions in
+    /// other languages are linked as alternates.
+    pub path: String,
+    /// An image under `../public`, e.g. a post's cover.
+    pub image: String,
+    /// OpenGraph type: `website` or `article`.
+    pub kind: &'static str,
+    pub json_ld: Option<Value>,
+}
+
+impl Meta {
+    pub fn page(lang: Lang, path: &str, title: &str) -> Self {
+        Meta {
+            lang,
+            title: title.to_string(),
+            description: i18n::t(lang, "meta.description").to_string(),
+            path: path.to_string(),
+            image: DEFAULT_IMAGE.to_string(),
+            kind: "website",
+            json_ld: None,
+        }
+    }
+
+    /// The home page, which also describes the department as an organisation.
+    pub fn home(lang: Lang) -> Self {
+        Meta {
+            json_ld: Some(organization()),
+            ..Meta::page(lang, &lang.path("/"), i18n::t(lang, "title.home"))
+        }
+    }
+
+    /// An announcement or article. Empty SEO fields fall back to the title
+    /// and the start of the content.
+    pub fn post(lang: Lang, path: &str, schema_type: &str, post: Post) -> Self {
+        let title = non_empty(post.seo_title).unwrap_or(post.title);
+        let description = non_empty(post.seo_description)
+            .map(str::to_string)
+            .unwrap_or_else(|| excerpt(post.content, DESCRIPTION_LENGTH));
+        let url = format!("{}{}", site_url(), path);
+
+        let mut json_ld = json!({
+            "@context": "https://schema.org",
+            "@type": schema_type,
+            "headline": title,
+            "inLanguage": lang.code(),
+            "description": description,
+            "image": [format!("{}{}", site_url(), post.image)],
+            "author": { "@type": "Person", "name": post.author },
+            "publisher": organization(),
+            "mainEntityOfPage": url,
+        });
+        if let Some(date) = iso_date(post.date) {
+            json_ld["datePublished"] = json!(date);
+        }
+
+        Meta {
+            lang,
+            title: title.to_string(),
+            description,
+            path: path.to_string(),
+            image: post.image.to_string(),
+            kind: "article",
+            json_ld: Some(json_ld),
+        }
+    }
+
+    /// Tags for the document head.
+    pub fn to_html(&self) -> String {
+        let site = site_url();
+        let url = format!("{}{}", site, self.path);
+        let image = format!("{}{}", site, self.image);
+        let description = escape_html(&self.description);
+        let title = escape_html(&self.title);
+
+        let mut html = format!(
+            "<meta name=\"description\" content=\"{description}\">
+    <link rel=\"canonical\" href=\"{url}\">
+    <meta property=\"og:site_name\" content=\"{site_name}\">
+    <meta property=\"og:locale\" content=\"{locale}\">
+    <meta property=\"og:type\" content=\"{kind}\">
+    <meta property=\"og:title\" content=\"{title}\">
+    <meta property=\"og:description\" content=\"{description}\">
+    <meta property=\"og:url\" content=\"{url}\">
+    <meta property=\"og:image\" content=\"{image}\">
+    <meta name=\"twitter:card\" content=\"summary_large_image\">
+    <meta name=\"twitter:title\" content=\"{title}\">
+    <meta name=\"twitter:description\" content=\"{description}\">
+    <meta name=\"twitter:image\" content=\"{image}\">",
+            url = escape_html(&url),
+            image = escape_html(&image),
+            site_name = SITE_NAME,
+            kind = self.kind,
+            locale = self.lang.locale(),
+        );
+        for lang in Lang::ALL {
+            html.push_str(&format!(
+                "\n    <link rel=\"alternate\" hreflang=\"{}\" href=\"{}{}\">",
+                lang.code(),
+                site,
+                escape_html(&lang.path(&self.path))
+            ));
+        }
+        html.push_str(&format!(
+            "\n    <link rel=\"alternate\" hreflang=\"x-default\" href=\"{}{}\">",
+            site,
+            escape_html(&Lang::Tr.path(&self.path))
+        ));
+        if let Some(json_ld) = &self.json_ld {
+            // A "</script>" inside a string would end the element early.
+            html.push_str(&format!(
+                "\n    <script type=\"application/ld+json\">{}</script>",
+                json_ld.to_string().replace("</", "<\\/")
+            ));
+        }
+        html
+    }
+}
+
+/// An announcement's or article's own search result title and description.
+/// Empty ones are derived from the title and content.
+#[derive(Default)]
+pub struct Overrides {
+    pub title: String,
+    pub description: String,
+}
+
+/// The fields of an announcement or article that metadata is built from.
+pub struct Post<'a> {
+    pub title: &'a str,
+    pub content: &'a str,
+    pub image: &'a str,
+    pub date: &'a str,
+    pub author: &'a str,
+    pub seo_title: &'a str,
+    pub seo_description: &'a str,
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|value| !value.is_empty())
+}
+
+/// The stored `dd-mm-yyyy` as `yyyy-mm-dd`.
+fn iso_date(date: &str) -> Option<String> {
+    NaiveDate::parse_from_str(date, "%d-%m-%Y")
+        .ok()
+        .map(|day| day.format("%Y-%m-%d").to_string())
+}
+
+fn organization() -> Value {
+    let site = site_url();
+    json!({
+        "@context": "https://schema.org",
+        "@type": "EducationalOrganization",
+        "name": SITE_NAME,
+        "url": format!("{}/", site),
+        "logo": format!("{}{}", site, DEFAULT_IMAGE),
+        "telephone": "+90 346 219 1010",
+        "faxNumber": "+90 346 219 1110",
+        "address": {
+            "@type": "PostalAddress",
+            "streetAddress": "Cumhuriyet Üniversitesi",
+            "postalCode": "58140",
+            "addressLocality": "Sivas",
+            "addressCountry": "TR",
+        },
+    })
+}
+
+fn sitemap_url(xml: &mut String, site: &str, path: &str, date: Option<&str>) {
+    xml.push_str(&format!("<url><loc>{}{}</loc>", site, escape_html(path)));
+    if let Some(date) = date.and_then(iso_date) {
+        xml.push_str(&format!("<lastmod>{}</lastmod>", date));
+    }
+    xml.push_str("</url>\n");
+}
+
+fn sitemap() -> rusqlite::Result<String> {
+    let site = site_url();
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+
+    let announcements = db::get_post_dates("announcements")?;
+    let articles = db::get_post_dates("articles")?;
+    let documents = db::get_documents(None, None)?;
+    for lang in Lang::ALL {
+        for path in STATIC_PAGES {
+            sitemap_url(&mut xml, &site, &lang.path(path), None);
+        }
+        for (id, date) in &announcements {
+            let path = lang.path(&format!("/announcement/{}", id));
+            sitemap_url(&mut xml, &site, &path, Some(date));
+        }
+        for (id, date) in &articles {
+            let path = lang.path(&format!("/article/{}", id));
+            sitemap_url(&mut xml, &site, &path, Some(date));
+        }
+        for document in &documents {
+            let path = lang.path(&format!("/dokumanlar/{}/onizleme", document.filename));
+            sitemap_url(&mut xml, &site, &path, Some(&document.upload_date));
+        }
+    }
+
+    xml.push_str("</urlset>\n");
+    Ok(xml)
+}
+
+pub async fn sitemap_handler() -> Result<HttpResponse> {
+    let xml = sitemap().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .body(xml))
+}
+
+pub async fn robots_handler() -> HttpResponse {
+    let body = format!(
+        "User-agent: *
+Disallow: /admin/
+Disallow: /dashboard
+Disallow: /api/
+Disallow: /pages/
+Disallow: /bildirimler/
+Disallow: /password-reset
+Disallow: /main
+Disallow: /announcements/
+Disallow: /articles/
+Disallow: /dil/
+Disallow: /en/main
+Disallow: /en/announcements/
+Disallow: /en/articles/
+Disallow: /en/bildirimler/
+
+Sitemap: {}/sitemap.xml
+",
+        site_url()
+    );
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(body)
+}
@@ -1,3 +1,4 @@
+use crate::src::images;
 use actix_web::web::Query;
 use actix_web::{web::Json, HttpRequest, HttpResponse, Responder, Result};
 use rand::prelude::SliceRandom;
@@ -10,31 +11,39 @@ pub struct SliderResponse {
     paths: Vec<String>,
 }
 
-pub async fn handler(req: HttpRequest) -> impl Responder {
-    let path: PathBuf = "../public/assets/slider".parse().unwrap();
+/// Up to ten slides, shuffled, as the slider's markup.
+pub fn render_slides() -> String {
+    let path: PathBuf = images::SLIDER_DIR.parse().unwrap();
     let mut paths: Vec<String> = std::fs::read_dir(path)
         .unwrap()
-        .map(|entry| entry.unwrap().path().display().to_string())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .map(|path| path.display().to_string())
         .collect();
     paths.sort();
     paths.shuffle(&mut rand::thread_rng());
     let paths: Vec<String> = paths.into_iter().take(10).collect();
 
-    let slides: String = paths
+    paths
         .into_iter()
         .map(|path| {
+            let name = path.rsplit('/').next().unwrap_or_default();
             format!(
                 r#"
                 <div class="swiper-slide">
                     <div class="flex items-center justify-center h-full w-full">
-                        <img class="w-full h-full rounded-xl" src="{}" />
+                        <img class="w-full h-full rounded-xl object-cover" src="{}/{}" srcset="{}" sizes="(max-width: 640px) 640px, (max-width: 1024px) 1024px, 1280px" />
                     </div>
                 </div>
                 "#,
-                path
+                images::SLIDER_URL,
+                name,
+                images::slide_srcset(images::SLIDER_URL, name)
             )
         })
-        .collect();
+        .collect()
+}
 
-    HttpResponse::Ok().body(slides)
+pub async fn handler(req: HttpRequest) -> impl Responder {
+    HttpResponse::Ok().body(render_slides())
 }
@@ -0,0 +1,264 @@
+use crate::src::config;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const FORM_TOKEN_LIFETIME_SECS: u64 = 24 * 60 * 60;
+
+/// Words and phrases matched against whole words, so "crypto" does not hit
+/// "cryptography".
+const SPAM_WORDS: [&str; 15] = [
+    "viagra",
+    "cialis",
+    "casino",
+    "bitcoin",
+    "crypto",
+    "forex",
+    "loan",
+    "seo services",
+    "backlink",
+    "porn",
+    "escort",
+    "bahis",
+    "kumar",
+    "iddaa",
+    "kredi kartı",
+];
+
+/// Counts recent submissions per client and refuses the ones over the limit.
+pub struct RateLimiter {
+    hits: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    fn new() -> Self {
+        RateLimiter {
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a hit for `key` and returns whether it is still within
+    /// `limit` hits per `window`.
+    pub fn check(&self, key: &str, limit: usize, window: Duration) -> bool {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap();
+
+        // Drop clients that have been quiet for a whole window so the map
+        // does not grow without bound.
+        hits.retain(|_, times| {
+            times
+                .back()
+                .is_some_and(|last| now.duration_since(*last) < window)
+        });
+
+        let times = hits.entry(key.to_string()).or_default();
+        while times
+            .front()
+            .is_some_and(|first| now.duration_since(*first) >= window)
+        {
+            times.pop_front();
+        }
+        if times.len() >= limit {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
+
+/// Limiter shared by the public contact form.
+pub fn contact_limiter() -> &'static RateLimiter {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(RateLimiter::new)
+}
+
+/// Limiter for password reset requests, which send mail on the visitor's behalf.
+pub fn password_reset_limiter() -> &'static RateLimiter {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(RateLimiter::new)
+}
+
+/// Limiter for announcement subscriptions, which also send mail on the
+/// visitor's behalf.
+pub fn subscription_limiter() -> &'static RateLimiter {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(RateLimiter::new)
+}
+
+#[derive(Serialize, Deserialize)]
+struct FormClaims {
+    iat: u64,
+    exp: u64,
+}
+
+// Only needs to survive as long as the process; a restart just means forms
+// opened before it have to be submitted again.
+fn token_secret() -> &'static [u8; 32] {
+    static SECRET: OnceLock<[u8; 32]> = OnceLock::new();
+    SECRET.get_or_init(|| {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        secret
+    })
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Signed timestamp embedded in the form when it is rendered.
+pub fn issue_form_token() -> String {
+    let now = unix_now();
+    let claims = FormClaims {
+        iat: now,
+        exp: now + FORM_TOKEN_LIFETIME_SECS,
+    };
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(token_secret()),
+    )
+    .unwrap_or_default()
+}
+
+/// Seconds since the form was rendered, or `None` if the token is missing,
+/// forged or expired.
+pub fn form_age_secs(token: &str) -> Option<u64> {
+    let claims = jsonwebtoken::decode::<FormClaims>(
+        token,
+        &DecodingKey::from_secret(token_secret()),
+        &Validation::default(),
+    )
+    .ok()?
+    .claims;
+    Some(unix_now().saturating_sub(claims.iat))
+}
+
+fn count_links(text: &str) -> usize {
+    let lower = text.to_lowercase();
+    lower.matches("http://").count()
+        + lower.matches("https://").count()
+        + lower.matches("www.").count()
+}
+
+/// Lower-case words of `text`, split on anything that is not a letter or digit.
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `phrase` appears in `words` as consecutive whole words.
+fn contains_phrase(words: &[String], phrase: &str) -> bool {
+    let phrase: Vec<&str> = phrase.split(' ').collect();
+    words
+        .windows(phrase.len())
+        .any(|window| window.iter().zip(&phrase).all(|(word, part)| word == part))
+}
+
+/// Heuristic score for how spam-like a submission is; messages reaching
+/// `[contact] spam_threshold` go to the spam folder.
+pub fn spam_score(name: &str, email: &str, message: &str) -> i32 {
+    let mut score = 0;
+    let lower = message.to_lowercase();
+
+    let links = count_links(message);
+    if links > 2 {
+        score += 2 * (links as i32 - 2);
+    }
+    if count_links(name) > 0 || name.contains('@') {
+        score += 3;
+    }
+    if lower.contains("[url=") || lower.contains("<a href") {
+        score += 3;
+    }
+
+    let message_words = words(message);
+    let email_words = words(email);
+    score += 3 * SPAM_WORDS
+        .iter()
+        .filter(|phrase| {
+            contains_phrase(&message_words, phrase) || contains_phrase(&email_words, phrase)
+        })
+        .count() as i32;
+
+    let letters: Vec<char> = message.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() >= 20 {
+        let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+        if upper * 10 > letters.len() * 6 {
+            score += 2;
+        }
+
+        // The site is Turkish; mostly Cyrillic or CJK text is almost always spam.
+        let foreign = letters
+            .iter()
+            .filter(|c| matches!(**c as u32, 0x0400..=0x04FF | 0x3040..=0x30FF | 0x4E00..=0x9FFF))
+            .count();
+        if foreign * 2 > letters.len() {
+            score += 5;
+        }
+    }
+
+    score
+}
+
+pub fn is_spam(score: i32) -> bool {
+    score >= config::get().contact.spam_threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spam_words_match_whole_words_only() {
+        let words = words("Cryptography, downloads and Crypto-trading");
+        assert!(contains_phrase(&words, "crypto"));
+        assert!(!contains_phrase(&words, "loan"));
+        assert!(contains_phrase(&super::words("Kredi kartı ile ödeme"), "kredi kartı"));
+        assert!(!contains_phrase(&super::words("kredi notu ve kartı"), "kredi kartı"));
+    }
+
+    #[test]
+    fn ordinary_student_messages_are_not_spam() {
+        let messages = [
+            (
+                "Ayşe Yılmaz",
+                "ayse.yilmaz@ogr.cumhuriyet.edu.tr",
+                "Merhaba hocam, Kriptografi dersindeki RSA ödevinde anahtar üretimi kısmını \
+                 anlamadım. Sınıfın WhatsApp grubunda ödev teslim tarihinin değiştiği söylendi, \
+                 doğru mu? Ayrıca staj başvurusu için kredi notu ortalamamı nereden görebilirim?",
+            ),
+            (
+                "John Smith",
+                "john.smith@example.com",
+                "Hello, I am an Erasmus student taking Cryptography and Network Security. \
+                 The course VM download link on the department page is broken; could you send \
+                 it again? My classmates shared notes over WhatsApp but I could not join. \
+                 Also, is the database lab on Thursday still in room 104?",
+            ),
+        ];
+        for (name, email, message) in messages {
+            let score = spam_score(name, email, message);
+            assert!(score < 5, "{} scored {}", name, score);
+        }
+    }
+
+    #[test]
+    fn obvious_spam_is_flagged() {
+        let score = spam_score(
+            "Best Offers",
+            "promo@casino-deals.example",
+            "Cheap viagra and online casino bonus, fast loan approval today!",
+        );
+        assert!(score >= 5, "scored {}", score);
+    }
+}
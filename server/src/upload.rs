@@ -0,0 +1,158 @@
+use actix_multipart::Multipart;
+use actix_web::web::{Bytes, BytesMut};
+use actix_web::Error;
+use futures::{StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+/// Longest text field accepted, far above any form's own length limits.
+const MAX_FIELD_BYTES: usize = 64 * 1024;
+
+/// Text fields and the optional `file` part of a multipart form.
+pub struct FormUpload {
+    pub fields: HashMap<String, String>,
+    pub file: Option<(String, Bytes)>,
+    /// Set when the file was larger than the limit passed to `read_form_upload`;
+    /// `file` is left empty in that case.
+    pub file_too_large: bool,
+}
+
+impl FormUpload {
+    pub fn field(&self, name: &str) -> String {
+        self.fields
+            .get(name)
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Reads a multipart form. The part named `file` is kept as an upload with a
+/// sanitized file name; everything else is treated as a text field. A broken
+/// stream or an over-long text field fails the whole form.
+pub async fn read_form_upload(
+    mut payload: Multipart,
+    max_file_bytes: Option<usize>,
+) -> Result<FormUpload, Error> {
+    let mut upload = FormUpload {
+        fields: HashMap::new(),
+        file: None,
+        file_too_large: false,
+    };
+
+    while let Some(mut field) = payload.try_next().await? {
+        let content_disposition = field.content_disposition().clone();
+        let name = content_disposition.get_name().unwrap_or("").to_string();
+        let filename = content_disposition.get_filename().map(String::from);
+        let limit = if name == "file" {
+            max_file_bytes
+        } else {
+            Some(MAX_FIELD_BYTES)
+        };
+
+        let mut bytes = BytesMut::new();
+        let mut too_large = false;
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk?;
+            // Keep draining the stream but stop buffering once over the limit.
+            if limit.is_some_and(|limit| bytes.len() + chunk.len() > limit) {
+                too_large = true;
+                bytes.clear();
+            }
+            if !too_large {
+                bytes.extend_from_slice(&chunk);
+            }
+        }
+
+        match (name.as_str(), filename) {
+            ("file", _) if too_large => upload.file_too_large = true,
+            ("file", Some(filename)) if !bytes.is_empty() => {
+                upload.file = Some((sanitize_filename::sanitize(filename), bytes.freeze()));
+            }
+            ("file", _) => (),
+            _ if too_large => {
+                return Err(actix_web::error::ErrorPayloadTooLarge(format!(
+                    "Field `{}` is too long",
+                    name
+                )));
+            }
+            _ => {
+                upload
+                    .fields
+                    .insert(name, String::from_utf8_lossy(&bytes).to_string());
+            }
+        }
+    }
+
+    Ok(upload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+    const BOUNDARY: &str = "upload-test";
+
+    fn form(body: String) -> Multipart {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_str(&format!("multipart/form-data; boundary={}", BOUNDARY)).unwrap(),
+        );
+        let stream = futures::stream::once(async move {
+            Ok::<_, actix_web::error::PayloadError>(Bytes::from(body))
+        });
+        Multipart::new(&headers, stream)
+    }
+
+    fn part(name: &str, filename: Option<&str>, value: &str) -> String {
+        let filename = filename
+            .map(|filename| format!("; filename=\"{}\"", filename))
+            .unwrap_or_default();
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"{}\r\n\r\n{}\r\n",
+            BOUNDARY, name, filename, value
+        )
+    }
+
+    #[actix_web::test]
+    async fn reads_fields_and_file() {
+        let body = format!(
+            "{}{}--{}--\r\n",
+            part("title", None, " Staj Yönergesi "),
+            part("file", Some("../yönerge.pdf"), "%PDF"),
+            BOUNDARY
+        );
+        let upload = read_form_upload(form(body), Some(16)).await.unwrap();
+        assert_eq!(upload.field("title"), "Staj Yönergesi");
+        let (name, bytes) = upload.file.unwrap();
+        assert!(!name.contains('/'));
+        assert_eq!(&bytes[..], b"%PDF");
+    }
+
+    #[actix_web::test]
+    async fn file_over_the_limit_is_flagged() {
+        let body = format!("{}--{}--\r\n", part("file", Some("a.pdf"), "%PDF-1.7"), BOUNDARY);
+        let upload = read_form_upload(form(body), Some(4)).await.unwrap();
+        assert!(upload.file_too_large);
+        assert!(upload.file.is_none());
+    }
+
+    #[actix_web::test]
+    async fn long_text_field_is_an_error() {
+        let long = "a".repeat(MAX_FIELD_BYTES + 1);
+        let body = format!("{}--{}--\r\n", part("message", None, &long), BOUNDARY);
+        assert!(read_form_upload(form(body), None).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn malformed_part_is_an_error() {
+        // The second part has no Content-Disposition header.
+        let body = format!(
+            "{}--{}\r\nContent-Type: text/plain\r\n\r\nmetin\r\n--{}--\r\n",
+            part("title", None, "Başlık"),
+            BOUNDARY,
+            BOUNDARY
+        );
+        assert!(read_form_upload(form(body), None).await.is_err());
+    }
+}